@@ -288,6 +288,24 @@ pub struct CompileArgs {
     #[arg(long = "pdf-standard", value_delimiter = ',')]
     pub pdf_standard: Vec<PdfStandard>,
 
+    /// An ICC profile that describes the printing condition a PDF/X document
+    /// is prepared for. Must be combined with `--pdf-output-condition`.
+    #[arg(
+        long = "pdf-output-intent",
+        value_name = "PATH",
+        requires = "pdf_output_condition"
+    )]
+    pub pdf_output_intent: Option<PathBuf>,
+
+    /// An identifier for the printing condition described by the profile given
+    /// with `--pdf-output-intent` (e.g. `FOGRA39`).
+    #[arg(
+        long = "pdf-output-condition",
+        value_name = "NAME",
+        requires = "pdf_output_intent"
+    )]
+    pub pdf_output_condition: Option<String>,
+
     /// By default, even when not producing a `PDF/UA-1` document, a tagged PDF
     /// document is written to provide a baseline of accessibility. In some
     /// circumstances (for example when trying to reduce the size of a document)
@@ -295,6 +313,32 @@ pub struct CompileArgs {
     #[arg(long = "no-pdf-tags")]
     pub no_pdf_tags: bool,
 
+    /// How to handle fonts whose license does not permit embedding them into
+    /// a PDF. Unless `--pdf-full-fonts` is given, fonts that may only be
+    /// embedded in full also count as restricted.
+    #[arg(long = "pdf-restricted-fonts", default_value_t)]
+    pub pdf_restricted_fonts: RestrictedFonts,
+
+    /// Embeds fonts in full instead of as subsets, so that the text can be
+    /// edited in other tools. Not supported with PDF/A and PDF/UA.
+    #[arg(long = "pdf-full-fonts")]
+    pub pdf_full_fonts: bool,
+
+    /// Linearizes the PDF for fast web view, so that PDF readers can display
+    /// the first page while the rest of the file is still loading.
+    #[arg(long = "pdf-linearize")]
+    pub pdf_linearize: bool,
+
+    /// Reserves space for a digital signature of at most the given number of
+    /// bytes, so that the PDF can be signed later with an external tool.
+    #[arg(long = "pdf-signature-placeholder", value_name = "BYTES")]
+    pub pdf_signature_placeholder: Option<usize>,
+
+    /// Splits HTML output into multiple files, which are written into the
+    /// output directory.
+    #[arg(long = "html-split", value_name = "BOUNDARY")]
+    pub html_split: Option<HtmlSplit>,
+
     /// The PPI (pixels per inch) to use for PNG export.
     #[arg(long = "ppi", default_value_t = 144.0)]
     pub ppi: f32,
@@ -654,10 +698,44 @@ pub enum PdfStandard {
     /// PDF/UA-1.
     #[value(name = "ua-1")]
     UA_1,
+    /// PDF/X-1a:2003.
+    #[value(name = "x-1a")]
+    X_1a,
+    /// PDF/X-3:2003.
+    #[value(name = "x-3")]
+    X_3,
+    /// PDF/X-4.
+    #[value(name = "x-4")]
+    X_4,
 }
 
 display_possible_values!(PdfStandard);
 
+/// How to handle fonts whose license does not permit embedding them.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum RestrictedFonts {
+    /// Embed the fonts anyway.
+    #[default]
+    Embed,
+    /// Fail with an error.
+    Error,
+    /// Draw the text set in these fonts as outlines.
+    Outline,
+}
+
+display_possible_values!(RestrictedFonts);
+
+/// Where to split HTML output into multiple files.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum HtmlSplit {
+    /// Start a new file for each `html.page`.
+    Page,
+    /// Start a new file for each `html.page` and each level-1 heading.
+    Heading,
+}
+
+display_possible_values!(HtmlSplit);
+
 /// Output file format for query and info commands
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, ValueEnum)]
 pub enum SerializationFormat {
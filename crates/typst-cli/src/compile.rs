@@ -12,15 +12,15 @@ use typst::diag::{
     At, HintedStrResult, HintedString, Severity, SourceDiagnostic, SourceResult,
     StrResult, Warned, bail,
 };
-use typst::foundations::{Datetime, Smart};
+use typst::foundations::{Bytes, Datetime, Smart};
 use typst::layout::{Page, PageRanges, PagedDocument};
 use typst::syntax::{FileId, Lines, Span};
 use typst_html::HtmlDocument;
-use typst_pdf::{PdfOptions, PdfStandards, Timestamp};
+use typst_pdf::{PdfOptions, PdfSigning, PdfStandards, Timestamp};
 
 use crate::args::{
-    CompileArgs, CompileCommand, DepsFormat, DiagnosticFormat, Input, Output,
-    OutputFormat, PdfStandard, WatchCommand,
+    CompileArgs, CompileCommand, DepsFormat, DiagnosticFormat, HtmlSplit, Input, Output,
+    OutputFormat, PdfStandard, RestrictedFonts, WatchCommand,
 };
 use crate::deps::write_deps;
 #[cfg(feature = "http-server")]
@@ -68,6 +68,16 @@ pub struct CompileConfig {
     pub pdf_standards: PdfStandards,
     /// Whether to write PDF (accessibility) tags.
     pub tagged: bool,
+    /// How to handle fonts whose license does not permit embedding them.
+    pub restricted_fonts: RestrictedFonts,
+    /// Whether to embed fonts in full instead of as subsets.
+    pub full_fonts: bool,
+    /// Whether to linearize the PDF for fast web view.
+    pub linearize: bool,
+    /// The number of bytes to reserve for a PDF signature, if any.
+    pub signature_placeholder: Option<usize>,
+    /// Where to split HTML output into multiple files, if at all.
+    pub html_split: Option<HtmlSplit>,
     /// A destination to write a list of dependencies to.
     pub deps: Option<Output>,
     /// The format to use for dependencies.
@@ -104,6 +114,8 @@ impl CompileConfig {
 
         let output_format = if let Some(specified) = args.format {
             specified
+        } else if args.html_split.is_some() {
+            OutputFormat::Html
         } else if let Some(Output::Path(output)) = &args.output {
             match output.extension() {
                 Some(ext) if ext.eq_ignore_ascii_case("pdf") => OutputFormat::Pdf,
@@ -124,6 +136,9 @@ impl CompileConfig {
             let Input::Path(path) = &input else {
                 panic!("output must be specified when input is from stdin, as guarded by the CLI");
             };
+            if args.html_split.is_some() {
+                return Output::Path(path.with_extension(""));
+            }
             Output::Path(path.with_extension(
                 match output_format {
                     OutputFormat::Pdf => "pdf",
@@ -170,9 +185,18 @@ impl CompileConfig {
             }
         }
 
-        let pdf_standards = PdfStandards::new(
+        let mut pdf_standards = PdfStandards::new(
             &args.pdf_standard.iter().copied().map(Into::into).collect::<Vec<_>>(),
         )?;
+        if let (Some(path), Some(condition)) =
+            (&args.pdf_output_intent, &args.pdf_output_condition)
+        {
+            let profile = std::fs::read(path).map_err(|err| {
+                eco_format!("failed to read output intent profile ({err})")
+            })?;
+            pdf_standards = pdf_standards
+                .with_output_intent(Bytes::new(profile), condition.as_str())?;
+        }
 
         #[cfg(feature = "http-server")]
         let server = match watch {
@@ -197,6 +221,15 @@ impl CompileConfig {
             );
         }
 
+        if args.html_split.is_some() {
+            if output_format != OutputFormat::Html {
+                bail!("--html-split can only be used with HTML output");
+            }
+            if matches!(output, Output::Stdout) {
+                bail!("cannot write multiple HTML files to stdout");
+            }
+        }
+
         match (&output, &deps, watch) {
             (Output::Stdout, _, Some(_)) => {
                 bail!("cannot write document to stdout in watch mode");
@@ -219,6 +252,11 @@ impl CompileConfig {
             pages,
             pdf_standards,
             tagged,
+            restricted_fonts: args.pdf_restricted_fonts,
+            full_fonts: args.pdf_full_fonts,
+            linearize: args.pdf_linearize,
+            signature_placeholder: args.pdf_signature_placeholder,
+            html_split: args.html_split,
             creation_timestamp: args.world.creation_timestamp,
             ppi: args.ppi,
             diagnostic_format: args.process.diagnostic_format,
@@ -302,11 +340,11 @@ fn compile_and_export(
     match config.output_format {
         OutputFormat::Html => {
             let Warned { output, warnings } = typst::compile::<HtmlDocument>(world);
-            let result = output.and_then(|document| export_html(&document, config));
-            Warned {
-                output: result.map(|()| vec![config.output.clone()]),
-                warnings,
-            }
+            let result = output.and_then(|document| match config.html_split {
+                Some(split) => export_html_files(&document, config, split.into()),
+                None => export_html(&document, config),
+            });
+            Warned { output: result, warnings }
         }
         _ => {
             let Warned { output, warnings } = typst::compile::<PagedDocument>(world);
@@ -317,18 +355,88 @@ fn compile_and_export(
 }
 
 /// Export to HTML.
-fn export_html(document: &HtmlDocument, config: &CompileConfig) -> SourceResult<()> {
+fn export_html(
+    document: &HtmlDocument,
+    config: &CompileConfig,
+) -> SourceResult<Vec<Output>> {
     let html = typst_html::html(document)?;
+    let mut outputs = match &config.output {
+        Output::Path(path) => {
+            write_assets(document, path.parent().unwrap_or(Path::new("")))?
+        }
+        Output::Stdout if document.assets.is_empty() => vec![],
+        Output::Stdout => bail!(
+            Span::detached(),
+            "cannot write HTML assets to stdout";
+            hint: "provide an output file or disable assets in `html.document`"
+        ),
+    };
     let result = config.output.write(html.as_bytes());
 
     #[cfg(feature = "http-server")]
     if let Some(server) = &config.server {
-        server.update(html);
+        server.update(html, &document.assets);
     }
 
     result
         .map_err(|err| eco_format!("failed to write HTML file ({err})"))
-        .at(Span::detached())
+        .at(Span::detached())?;
+
+    outputs.insert(0, config.output.clone());
+    Ok(outputs)
+}
+
+/// Export to multiple HTML files in the output directory.
+fn export_html_files(
+    document: &HtmlDocument,
+    config: &CompileConfig,
+    split: typst_html::HtmlSplit,
+) -> SourceResult<Vec<Output>> {
+    let Output::Path(dir) = &config.output else {
+        unreachable!("splitting into stdout is guarded by the CLI");
+    };
+
+    let files = typst_html::html_files(document, split)?;
+    let mut outputs = vec![];
+    for file in &files {
+        let html = typst_html::html_file(document, file)?;
+        let path = dir.join(file.path.as_str());
+        path.parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::write(&path, html.as_bytes()))
+            .map_err(|err| eco_format!("failed to write HTML file ({err})"))
+            .at(Span::detached())?;
+
+        #[cfg(feature = "http-server")]
+        if let Some(server) = &config.server
+            && outputs.is_empty()
+        {
+            server.update(html, &document.assets);
+        }
+
+        outputs.push(Output::Path(path));
+    }
+
+    outputs.extend(write_assets(document, dir)?);
+    Ok(outputs)
+}
+
+/// Writes the images and frames of an HTML document that were moved into
+/// separate files into the output directory.
+fn write_assets(document: &HtmlDocument, dir: &Path) -> SourceResult<Vec<Output>> {
+    document
+        .assets
+        .iter()
+        .map(|asset| {
+            let path = dir.join(asset.path.as_str());
+            path.parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|()| std::fs::write(&path, asset.data.as_slice()))
+                .map_err(|err| eco_format!("failed to write HTML asset ({err})"))
+                .at(Span::detached())?;
+            Ok(Output::Path(path))
+        })
+        .collect()
 }
 
 /// Export to a paged target format.
@@ -373,6 +481,10 @@ fn export_pdf(document: &PagedDocument, config: &CompileConfig) -> SourceResult<
         page_ranges: config.pages.clone(),
         standards: config.pdf_standards.clone(),
         tagged: config.tagged,
+        signing: config.signature_placeholder.map(PdfSigning::Placeholder),
+        restricted_fonts: config.restricted_fonts.into(),
+        full_fonts: config.full_fonts,
+        linearize: config.linearize,
     };
     let buffer = typst_pdf::pdf(document, &options)?;
     config
@@ -581,6 +693,12 @@ fn open_output(config: &mut CompileConfig) -> StrResult<()> {
     // Can't open stdout.
     let Output::Path(path) = &config.output else { return Ok(()) };
 
+    // Split HTML output is a directory, so we open its first file instead.
+    let path = &match config.html_split {
+        Some(_) => path.join("index.html"),
+        None => path.clone(),
+    };
+
     // Some resource openers require the path to be canonicalized.
     let path = path
         .canonicalize()
@@ -725,6 +843,25 @@ impl<'a> codespan_reporting::files::Files<'a> for SystemWorld {
     }
 }
 
+impl From<HtmlSplit> for typst_html::HtmlSplit {
+    fn from(split: HtmlSplit) -> Self {
+        match split {
+            HtmlSplit::Page => typst_html::HtmlSplit::Page,
+            HtmlSplit::Heading => typst_html::HtmlSplit::Heading,
+        }
+    }
+}
+
+impl From<RestrictedFonts> for typst_pdf::RestrictedFonts {
+    fn from(policy: RestrictedFonts) -> Self {
+        match policy {
+            RestrictedFonts::Embed => typst_pdf::RestrictedFonts::Embed,
+            RestrictedFonts::Error => typst_pdf::RestrictedFonts::Error,
+            RestrictedFonts::Outline => typst_pdf::RestrictedFonts::Outline,
+        }
+    }
+}
+
 impl From<PdfStandard> for typst_pdf::PdfStandard {
     fn from(standard: PdfStandard) -> Self {
         match standard {
@@ -745,6 +882,9 @@ impl From<PdfStandard> for typst_pdf::PdfStandard {
             PdfStandard::A_4f => typst_pdf::PdfStandard::A_4f,
             PdfStandard::A_4e => typst_pdf::PdfStandard::A_4e,
             PdfStandard::UA_1 => typst_pdf::PdfStandard::Ua_1,
+            PdfStandard::X_1a => typst_pdf::PdfStandard::X_1a,
+            PdfStandard::X_3 => typst_pdf::PdfStandard::X_3,
+            PdfStandard::X_4 => typst_pdf::PdfStandard::X_4,
         }
     }
 }
@@ -6,6 +6,7 @@ use ecow::eco_format;
 use parking_lot::{Condvar, Mutex, MutexGuard};
 use tiny_http::{Header, Request, Response, StatusCode};
 use typst::diag::{StrResult, bail};
+use typst_html::HtmlAsset;
 
 use crate::args::{Input, ServerArgs};
 
@@ -13,6 +14,7 @@ use crate::args::{Input, ServerArgs};
 pub struct HtmlServer {
     addr: SocketAddr,
     bucket: Arc<Bucket<String>>,
+    assets: Arc<Mutex<Vec<HtmlAsset>>>,
 }
 
 impl HtmlServer {
@@ -24,14 +26,16 @@ impl HtmlServer {
         let placeholder = PLACEHOLDER_HTML.replace("{INPUT}", &input.to_string());
         let bucket = Arc::new(Bucket::new(placeholder));
         let bucket2 = bucket.clone();
+        let assets = Arc::new(Mutex::new(vec![]));
+        let assets2 = assets.clone();
 
         std::thread::spawn(move || {
             for req in server.incoming_requests() {
-                let _ = handle(req, reload, &bucket2);
+                let _ = handle(req, reload, &bucket2, &assets2);
             }
         });
 
-        Ok(Self { addr, bucket })
+        Ok(Self { addr, bucket, assets })
     }
 
     /// The address that we serve the HTML on.
@@ -39,8 +43,10 @@ impl HtmlServer {
         self.addr
     }
 
-    /// Updates the HTML, triggering a reload all connected browsers.
-    pub fn update(&self, html: String) {
+    /// Updates the HTML and the assets it refers to, triggering a reload all
+    /// connected browsers.
+    pub fn update(&self, html: String, assets: &[HtmlAsset]) {
+        *self.assets.lock() = assets.to_vec();
         self.bucket.put(html);
     }
 }
@@ -84,12 +90,17 @@ fn start_server(port: Option<u16>) -> StrResult<(SocketAddr, tiny_http::Server)>
 }
 
 /// Handles a request.
-fn handle(req: Request, reload: bool, bucket: &Arc<Bucket<String>>) -> io::Result<()> {
+fn handle(
+    req: Request,
+    reload: bool,
+    bucket: &Arc<Bucket<String>>,
+    assets: &Mutex<Vec<HtmlAsset>>,
+) -> io::Result<()> {
     let path = req.url();
     match path {
         "/" => handle_root(req, reload, bucket),
         "/events" => handle_events(req, bucket.clone()),
-        _ => req.respond(Response::new_empty(StatusCode(404))),
+        _ => handle_asset(req, assets),
     }
 }
 
@@ -108,6 +119,36 @@ fn handle_root(req: Request, reload: bool, bucket: &Bucket<String>) -> io::Resul
     ))
 }
 
+/// Handler for all other routes. Serves the assets of the compiled HTML.
+fn handle_asset(req: Request, assets: &Mutex<Vec<HtmlAsset>>) -> io::Result<()> {
+    let path = req.url().trim_start_matches('/');
+    let mime = match path.rsplit_once('.').map(|(_, ext)| ext) {
+        Some("png") => "image/png",
+        Some("jpg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        _ => "application/octet-stream",
+    };
+    let data = assets
+        .lock()
+        .iter()
+        .find(|asset| asset.path == path)
+        .map(|asset| asset.data.clone());
+
+    let Some(data) = data else {
+        return req.respond(Response::new_empty(StatusCode(404)));
+    };
+
+    req.respond(Response::new(
+        StatusCode(200),
+        vec![Header::from_bytes("Content-Type", mime).unwrap()],
+        data.as_slice(),
+        Some(data.len()),
+        None,
+    ))
+}
+
 /// Handler for the `/events` route.
 fn handle_events(req: Request, bucket: Arc<Bucket<String>>) -> io::Result<()> {
     std::thread::spawn(move || {
@@ -0,0 +1,387 @@
+//! Emission of images and frames as separate asset files.
+
+use std::io::Cursor;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use ecow::{EcoString, EcoVec, eco_format};
+use image::ImageFormat;
+use image::imageops::FilterType;
+use rustc_hash::FxHashSet;
+use typst_library::foundations::{Bytes, Packed, Smart, StyleChain};
+use typst_library::layout::{Abs, Frame, FrameItem, Length, Rel, Size, Sizing};
+use typst_library::visualize::{
+    ExchangeFormat, Image, ImageElem, RasterFormat, RasterImage,
+};
+use typst_utils::Numeric;
+
+use crate::{HtmlAttr, HtmlElement, HtmlFrame, HtmlNode, attr, css, tag};
+
+/// The directory, relative to the output directory, that assets are written
+/// into.
+const DIR: &str = "assets";
+
+/// A file that is referenced by an HTML document.
+#[derive(Debug, Clone, Hash)]
+pub struct HtmlAsset {
+    /// The path of the file, relative to the output directory.
+    pub path: EcoString,
+    /// The contents of the file.
+    pub data: Bytes,
+}
+
+/// Determines the size at which an image is displayed, if it doesn't depend on
+/// the size of its container.
+pub fn image_size(
+    elem: &Packed<ImageElem>,
+    image: &Image,
+    styles: StyleChain,
+) -> Option<Size> {
+    let absolute = |rel: Rel<Length>| rel.rel.is_zero().then(|| rel.abs.resolve(styles));
+    let width = match elem.width.get(styles) {
+        Smart::Auto => None,
+        Smart::Custom(rel) => Some(absolute(rel)?),
+    };
+    let height = match elem.height.get(styles) {
+        Sizing::Auto => None,
+        Sizing::Rel(rel) => Some(absolute(rel)?),
+        Sizing::Fr(_) => return None,
+    };
+
+    // Matches the natural size that layout uses for images.
+    let dpi = image.dpi().unwrap_or(Image::DEFAULT_DPI);
+    let natural =
+        Size::new(Abs::inches(image.width() / dpi), Abs::inches(image.height() / dpi));
+    let ratio = natural.x / natural.y;
+
+    Some(match (width, height) {
+        (Some(width), Some(height)) => Size::new(width, height),
+        (Some(width), None) => Size::new(width, width / ratio),
+        (None, Some(height)) => Size::new(height * ratio, height),
+        (None, None) => natural,
+    })
+}
+
+/// Encodes a raster image, downscaled to the given width, in the format of the
+/// original image if it is a JPEG and as a PNG otherwise. Returns the encoded
+/// image along with its file extension.
+#[comemo::memoize]
+fn downscale(raster: &RasterImage, width: u32) -> Option<(Bytes, &'static str)> {
+    let dynamic = raster.dynamic();
+    let height = (u64::from(dynamic.height()) * u64::from(width)
+        / u64::from(dynamic.width()))
+    .max(1) as u32;
+    let resized = dynamic.resize_exact(width, height, FilterType::Lanczos3);
+
+    let (extension, format) = match raster.format() {
+        RasterFormat::Exchange(ExchangeFormat::Jpg) => ("jpg", ImageFormat::Jpeg),
+        _ => ("png", ImageFormat::Png),
+    };
+
+    let mut buf = Cursor::new(vec![]);
+    resized.write_to(&mut buf, format).ok()?;
+    Some((Bytes::new(buf.into_inner()), extension))
+}
+
+/// Moves images that are embedded as data URLs and frames without links out
+/// of the document and into separate asset files.
+#[typst_macros::time(name = "extract assets")]
+pub fn extract_assets(root: &mut HtmlElement) -> Vec<HtmlAsset> {
+    let mut assets = Assets::default();
+    assets.visit(root);
+    assets.list
+}
+
+/// Rewrites references to assets in the nodes of the file at `path`, so that
+/// they are relative to the file's directory.
+pub fn relocate_assets(path: &str, nodes: &mut EcoVec<HtmlNode>, assets: &[HtmlAsset]) {
+    if !path.contains('/') || assets.is_empty() {
+        return;
+    }
+
+    for node in nodes.make_mut() {
+        if let HtmlNode::Element(elem) = node {
+            relocate_in_element(path, elem, assets);
+        }
+    }
+}
+
+/// Rewrites references to assets in an element and its descendants.
+fn relocate_in_element(path: &str, elem: &mut HtmlElement, assets: &[HtmlAsset]) {
+    for (attr, value) in elem.attrs.0.make_mut() {
+        if *attr == attr::src || *attr == attr::srcset {
+            *value = map_urls(*attr, value, |url| {
+                assets
+                    .iter()
+                    .any(|asset| asset.path == url)
+                    .then(|| crate::link::relative_href(path, url))
+            });
+        }
+    }
+
+    relocate_assets(path, &mut elem.children, assets);
+}
+
+/// Applies a function to the URLs in the value of a `src` or `srcset`
+/// attribute, keeping those for which it returns `None`.
+fn map_urls(
+    attr: HtmlAttr,
+    value: &str,
+    mut f: impl FnMut(&str) -> Option<EcoString>,
+) -> EcoString {
+    if attr == attr::src {
+        return f(value).unwrap_or_else(|| value.into());
+    }
+
+    let mut mapped = EcoString::new();
+    for (i, candidate) in value.split(", ").enumerate() {
+        if i > 0 {
+            mapped.push_str(", ");
+        }
+        let (url, descriptor) = match candidate.split_once(' ') {
+            Some((url, descriptor)) => (url, Some(descriptor)),
+            None => (candidate, None),
+        };
+        match f(url) {
+            Some(url) => mapped.push_str(&url),
+            None => mapped.push_str(url),
+        }
+        if let Some(descriptor) = descriptor {
+            mapped.push(' ');
+            mapped.push_str(descriptor);
+        }
+    }
+    mapped
+}
+
+/// Collects assets from the document.
+#[derive(Default)]
+struct Assets {
+    /// The distinct assets, in order of first appearance.
+    list: Vec<HtmlAsset>,
+    /// The paths of the assets in `list`.
+    paths: FxHashSet<EcoString>,
+}
+
+impl Assets {
+    /// Extracts the assets of the element and its descendants.
+    fn visit(&mut self, elem: &mut HtmlElement) {
+        if elem.tag == tag::img {
+            self.image(elem);
+        }
+
+        for (attr, value) in elem.attrs.0.make_mut() {
+            if *attr == attr::src || *attr == attr::srcset {
+                *value = map_urls(*attr, value, |url| self.data_url(url));
+            }
+        }
+
+        for node in elem.children.make_mut() {
+            match node {
+                HtmlNode::Element(child) => self.visit(child),
+                HtmlNode::Frame(frame) if !has_links(frame) => {
+                    *node = HtmlNode::Element(self.frame(frame));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Turns the data URL of an image into an asset.
+    ///
+    /// If the image has a `width` attribute and is a raster image that is
+    /// larger than that, it additionally receives a `srcset` with downscaled
+    /// versions.
+    fn image(&mut self, elem: &mut HtmlElement) {
+        let Some(src) = elem.attrs.get_mut(attr::src) else { return };
+        let Some((data, extension)) = decode_data_url(src) else { return };
+        let path = self.add(data.clone(), extension);
+        *src = path.clone();
+
+        if elem.attrs.get(attr::srcset).is_none()
+            && let Some(width) = elem.attrs.get(attr::width)
+            && let Ok(width) = width.parse()
+            && let Some(format) = raster_format(extension)
+            && let Ok(raster) = RasterImage::plain(data, format)
+            && let Some(srcset) = self.srcset(&raster, &path, width)
+        {
+            elem.attrs.push(attr::srcset, srcset);
+        }
+    }
+
+    /// Builds a `srcset` with versions of a raster image that are downscaled
+    /// for displays with one and two device pixels per CSS pixel, adding them
+    /// as assets.
+    ///
+    /// The `src` is the path of the full image, which is listed last. Returns
+    /// `None` if the image is not larger than the displayed width in CSS
+    /// pixels.
+    fn srcset(
+        &mut self,
+        raster: &RasterImage,
+        src: &str,
+        width: u32,
+    ) -> Option<EcoString> {
+        let natural = raster.width();
+        if width == 0 || natural <= width {
+            return None;
+        }
+
+        let mut candidates: Vec<EcoString> = [1, 2]
+            .into_iter()
+            .filter(|density| width * density < natural)
+            .filter_map(|density| {
+                let (data, extension) = downscale(raster, width * density)?;
+                Some(eco_format!("{} {density}x", self.add(data, extension)))
+            })
+            .collect();
+        candidates
+            .push(eco_format!("{src} {}x", css::number(natural as f32 / width as f32)));
+
+        Some(candidates.join(", ").into())
+    }
+
+    /// Turns a data URL into an asset, returning the asset's path.
+    ///
+    /// Returns `None` for other URLs and unknown image formats.
+    fn data_url(&mut self, url: &str) -> Option<EcoString> {
+        let (data, extension) = decode_data_url(url)?;
+        Some(self.add(data, extension))
+    }
+
+    /// Turns a frame into an image that refers to an SVG asset.
+    fn frame(&mut self, frame: &HtmlFrame) -> HtmlElement {
+        let svg = typst_svg::svg_frame(&frame.inner);
+        let path = self.add(Bytes::from_string(svg), "svg");
+        let size = frame.inner.size();
+        HtmlElement::new(tag::img)
+            .with_attr(attr::src, path)
+            .with_attr(attr::width, eco_format!("{}", css::pixels(size.x)))
+            .with_attr(attr::height, eco_format!("{}", css::pixels(size.y)))
+            .with_styles(
+                css::Properties::new()
+                    .with("width", eco_format!("{}em", size.x / frame.text_size))
+                    .with("height", eco_format!("{}em", size.y / frame.text_size)),
+            )
+            .spanned(frame.span)
+    }
+
+    /// Adds an asset with a name derived from its contents, returning its path.
+    fn add(&mut self, data: Bytes, extension: &str) -> EcoString {
+        let hash = typst_utils::hash128(data.as_slice());
+        let path = eco_format!("{DIR}/{hash:032x}.{extension}");
+        if self.paths.insert(path.clone()) {
+            self.list.push(HtmlAsset { path: path.clone(), data });
+        }
+        path
+    }
+}
+
+/// Decodes the contents of a data URL, along with the file extension for its
+/// MIME type.
+///
+/// Returns `None` for other URLs and unknown image formats.
+fn decode_data_url(url: &str) -> Option<(Bytes, &'static str)> {
+    let (mime, data) = url.strip_prefix("data:")?.split_once(";base64,")?;
+    let extension = match mime {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        _ => return None,
+    };
+    let data = STANDARD.decode(data).ok()?;
+    Some((Bytes::new(data), extension))
+}
+
+/// The raster format for a file extension, if it is one.
+fn raster_format(extension: &str) -> Option<ExchangeFormat> {
+    Some(match extension {
+        "png" => ExchangeFormat::Png,
+        "jpg" => ExchangeFormat::Jpg,
+        "gif" => ExchangeFormat::Gif,
+        "webp" => ExchangeFormat::Webp,
+        _ => return None,
+    })
+}
+
+/// Whether a frame must stay inline because it contains links or is the
+/// target of one.
+fn has_links(frame: &HtmlFrame) -> bool {
+    frame.id.is_some() || !frame.link_points.is_empty() || contains_links(&frame.inner)
+}
+
+/// Whether a frame or any of its groups contains a link.
+fn contains_links(frame: &Frame) -> bool {
+    frame.items().any(|(_, item)| match item {
+        FrameItem::Group(group) => contains_links(&group.frame),
+        FrameItem::Link(..) => true,
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_data_urls() {
+        let mut assets = Assets::default();
+        let url = eco_format!("data:image/png;base64,{}", STANDARD.encode(b"png"));
+        let path = assets.data_url(&url).unwrap();
+        assert!(path.starts_with("assets/") && path.ends_with(".png"));
+        assert_eq!(assets.data_url(&url), Some(path));
+        assert_eq!(assets.list.len(), 1);
+        assert_eq!(assets.list[0].data.as_slice(), b"png");
+        assert_eq!(assets.data_url("data:text/plain;base64,AA=="), None);
+        assert_eq!(assets.data_url("tiger.jpg"), None);
+    }
+
+    #[test]
+    fn test_srcset() {
+        let mut buf = Cursor::new(vec![]);
+        let image = image::DynamicImage::new_rgb8(8, 4);
+        image.write_to(&mut buf, ImageFormat::Png).unwrap();
+        let raster =
+            RasterImage::plain(Bytes::new(buf.into_inner()), ExchangeFormat::Png)
+                .unwrap();
+
+        let mut assets = Assets::default();
+        assert_eq!(assets.srcset(&raster, "full.png", 0), None);
+        assert_eq!(assets.srcset(&raster, "full.png", 8), None);
+        assert!(assets.list.is_empty());
+
+        let srcset = assets.srcset(&raster, "full.png", 2).unwrap();
+        let candidates: Vec<_> = srcset.split(", ").collect();
+        assert_eq!(candidates.len(), 3);
+        assert_eq!(candidates[0], format!("{} 1x", assets.list[0].path));
+        assert_eq!(candidates[1], format!("{} 2x", assets.list[1].path));
+        assert_eq!(candidates[2], "full.png 4x");
+
+        let sizes: Vec<_> = assets
+            .list
+            .iter()
+            .map(|asset| {
+                assert!(asset.path.ends_with(".png"));
+                let image = image::load_from_memory(asset.data.as_slice()).unwrap();
+                (image.width(), image.height())
+            })
+            .collect();
+        assert_eq!(sizes, [(2, 1), (4, 2)]);
+
+        // The version for two device pixels per CSS pixel would be larger than
+        // the full image.
+        let srcset = assets.srcset(&raster, "full.png", 5).unwrap();
+        assert_eq!(srcset, eco_format!("{} 1x, full.png 1.6x", assets.list[2].path));
+    }
+
+    #[test]
+    fn test_map_srcset_urls() {
+        let mapped = map_urls(attr::srcset, "a.png 1x, b.png 2x, c.png", |url| {
+            (url != "b.png").then(|| eco_format!("../{url}"))
+        });
+        assert_eq!(mapped, "../a.png 1x, b.png 2x, ../c.png");
+        assert_eq!(map_urls(attr::src, "a b.png", |_| None), "a b.png");
+    }
+}
@@ -1,4 +1,4 @@
-use ecow::{EcoString, EcoVec, eco_vec};
+use ecow::{EcoString, EcoVec, eco_format, eco_vec};
 use typst_library::diag::{SourceResult, warning};
 use typst_library::engine::Engine;
 use typst_library::foundations::{Content, Packed, StyleChain, Target, TargetElem};
@@ -12,7 +12,9 @@ use typst_library::text::{
 use typst_syntax::Span;
 
 use crate::fragment::{html_block_fragment, html_inline_fragment};
-use crate::{FrameElem, HtmlElem, HtmlElement, HtmlFrame, HtmlNode, attr, css, tag};
+use crate::{
+    FrameElem, HtmlElem, HtmlElement, HtmlFrame, HtmlNode, attr, css, stylesheet, tag,
+};
 
 /// What and how to convert.
 pub enum ConversionLevel<'a> {
@@ -102,7 +104,14 @@ fn handle(
         } else {
             elem.text.clone()
         };
-        handle_text(converter, text, elem.span());
+        if let Some(inherited) = styles.get_cloned(HtmlElem::inherited)
+            && let Some(props) =
+                stylesheet::diff(&stylesheet::text_properties(styles), &inherited)
+        {
+            handle_styled_text(converter, text, elem.span(), props);
+        } else {
+            handle_text(converter, text, elem.span());
+        }
     } else if let Some(elem) = child.to_packed::<HElem>()
         && elem.amount.is_zero()
     {
@@ -156,6 +165,20 @@ fn handle_html_elem(
     // elements.
     let role = styles.get_cloned(HtmlElem::role).filter(|_| elem.tag != tag::p);
 
+    // When generating a style sheet, the element receives the text properties
+    // that differ from the enclosing element and its children are compared
+    // against its own properties.
+    let mut props = None;
+    let inherited;
+    let styles = if let Some(parent) = styles.get_cloned(HtmlElem::inherited) {
+        let own = stylesheet::text_properties(styles);
+        props = stylesheet::diff(&own, &parent);
+        inherited = HtmlElem::inherited.set(Some(own)).wrap();
+        styles.chain(&inherited)
+    } else {
+        styles
+    };
+
     let mut children = EcoVec::new();
     if let Some(body) = elem.body.get_ref(styles) {
         let whitespace = if converter.whitespace == Whitespace::Pre
@@ -208,6 +231,13 @@ fn handle_html_elem(
     if let Some(role) = role {
         attrs.push(attr::role, role);
     }
+    if let Some(props) = props {
+        if let Some(style) = attrs.get_mut(attr::style) {
+            *style = eco_format!("{props}; {style}");
+        } else {
+            attrs.push(attr::style, props);
+        }
+    }
 
     converter.push(HtmlElement {
         tag: elem.tag,
@@ -307,6 +337,60 @@ fn handle_text(converter: &mut Converter, text: EcoString, span: Span) {
     }
 }
 
+/// Handles text whose properties differ from those of the enclosing element
+/// when generating a style sheet.
+///
+/// The text is wrapped in a styled span. Consecutive runs of text with the
+/// same properties share one span.
+fn handle_styled_text(
+    converter: &mut Converter,
+    text: EcoString,
+    span: Span,
+    props: EcoString,
+) {
+    let mut inner = Converter {
+        engine: &mut *converter.engine,
+        locator: &mut *converter.locator,
+        quoter: &mut *converter.quoter,
+        whitespace: converter.whitespace,
+        output: EcoVec::new(),
+        trailing: None,
+    };
+    handle_text(&mut inner, text, span);
+    let mut nodes = inner.finish();
+
+    let is_run = |node: &HtmlNode| {
+        matches!(node, HtmlNode::Element(elem)
+            if elem.tag == tag::span
+                && elem.attrs.0.len() == 1
+                && elem.attrs.get(attr::style) == Some(&props))
+    };
+
+    // Merge with a directly preceding run, possibly separated by a space.
+    let output = &converter.output;
+    let len = output.len();
+    if len >= 2
+        && matches!(&output[len - 1], HtmlNode::Text(text, _) if text == " ")
+        && is_run(&output[len - 2])
+    {
+        let space = converter.output.pop().unwrap();
+        converter.trailing = None;
+        nodes.insert(0, space);
+    } else if len == 0 || !is_run(&output[len - 1]) {
+        converter.push(
+            HtmlElement::new(tag::span)
+                .with_attr(attr::style, props)
+                .with_children(nodes)
+                .spanned(span),
+        );
+        return;
+    }
+
+    if let Some(HtmlNode::Element(run)) = converter.output.make_mut().last_mut() {
+        run.children.extend(nodes);
+    }
+}
+
 /// State during conversion.
 struct Converter<'a, 'y, 'z> {
     engine: &'a mut Engine<'y>,
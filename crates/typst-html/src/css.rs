@@ -3,7 +3,7 @@
 use std::fmt::{self, Display, Write};
 
 use ecow::EcoString;
-use typst_library::layout::{Length, Rel};
+use typst_library::layout::{Abs, HAlignment, Length, Rel, Sizing};
 use typst_library::visualize::{Color, Hsl, LinearRgb, Oklab, Oklch, Rgb};
 use typst_utils::Numeric;
 
@@ -58,9 +58,29 @@ pub fn length(length: Length) -> impl Display {
     })
 }
 
+pub fn sizing(sizing: Sizing) -> impl Display {
+    typst_utils::display(move |f| match sizing {
+        Sizing::Auto => f.write_str("auto"),
+        Sizing::Rel(v) => write!(f, "{}", rel(v)),
+        Sizing::Fr(v) => write!(f, "{}fr", v.get()),
+    })
+}
+
+pub fn align(align: HAlignment) -> &'static str {
+    match align {
+        HAlignment::Start => "start",
+        HAlignment::Left => "left",
+        HAlignment::Center => "center",
+        HAlignment::Right => "right",
+        HAlignment::End => "end",
+    }
+}
+
 pub fn color(color: Color) -> impl Display {
     typst_utils::display(move |f| match color {
-        Color::Rgb(_) | Color::Cmyk(_) | Color::Luma(_) => rgb(f, color.to_rgb()),
+        Color::Rgb(_) | Color::Cmyk(_) | Color::Spot(_) | Color::Luma(_) => {
+            rgb(f, color.to_rgb())
+        }
         Color::Oklab(v) => oklab(f, v),
         Color::Oklch(v) => oklch(f, v),
         Color::LinearRgb(v) => linear_rgb(f, v),
@@ -163,10 +183,16 @@ fn percent(ratio: f32) -> impl Display {
 ///
 /// For a number between 0 and 1, four significant digits give us a
 /// precision of 1/10_000, which is more than 12 bits (see `is_very_close`).
-fn number(value: f32) -> impl Display {
+pub fn number(value: f32) -> impl Display {
     typst_utils::round_with_precision(value as f64, 4)
 }
 
+/// Converts a length into whole CSS pixels, as used by the `width` and
+/// `height` attributes of images.
+pub fn pixels(length: Abs) -> u32 {
+    (length.to_pt() * 96.0 / 72.0).round().max(0.0) as u32
+}
+
 /// Whether two component values are close enough that there is no
 /// difference when encoding them with 12-bit. 12 bit is the highest
 /// reasonable color bit depth found in the industry.
@@ -18,7 +18,7 @@ use typst_utils::NonZeroExt;
 
 use crate::convert::{ConversionLevel, Whitespace};
 use crate::rules::FootnoteContainer;
-use crate::{HtmlDocument, HtmlElem, HtmlElement, HtmlNode, attr, tag};
+use crate::{HtmlDocument, HtmlDocumentElem, HtmlElem, HtmlElement, HtmlNode, attr, tag};
 
 /// Produce an HTML document from content.
 ///
@@ -84,10 +84,26 @@ fn html_document_impl(
         styles,
     )?;
 
+    let root_styles = Styles::root(&children, styles);
+    let root_chain = StyleChain::new(&root_styles);
+
+    // When generating a style sheet, the document's text properties become the
+    // `body` rule and top-level elements are compared against them.
+    let body_props = root_chain
+        .get(HtmlDocumentElem::stylesheet)
+        .then(|| crate::stylesheet::text_properties(root_chain));
+    let extract_assets = root_chain.get(HtmlDocumentElem::assets);
+    let inherited = HtmlElem::inherited.set(body_props.clone()).wrap();
+    let chains: Vec<_> = children.iter().map(|&(_, styles)| styles).collect();
+    let root_chain = root_chain.chain(&inherited);
+
     let nodes = crate::convert::convert_to_nodes(
         &mut engine,
         &mut locator,
-        children.iter().copied(),
+        children
+            .iter()
+            .zip(&chains)
+            .map(|(&(child, _), styles)| (child, styles.chain(&inherited))),
         ConversionLevel::Block,
         Whitespace::Normal,
     )?;
@@ -100,7 +116,7 @@ fn html_document_impl(
             &mut engine,
             FootnoteContainer::shared(),
             footnote_locator,
-            StyleChain::new(&Styles::root(&children, styles)),
+            root_chain,
             Whitespace::Normal,
         )?;
         leaves.extend(notes);
@@ -115,7 +131,19 @@ fn html_document_impl(
     let mut root = root_element(output, &info);
     crate::link::identify_link_targets(&mut root, &mut introspector, link_targets);
 
-    Ok(HtmlDocument { info, root, introspector })
+    // This must happen after link targets were identified, since frames that
+    // are jumped to stay inline.
+    let assets = if extract_assets {
+        crate::assets::extract_assets(&mut root)
+    } else {
+        Vec::new()
+    };
+
+    if let Some(props) = &body_props {
+        crate::stylesheet::hoist_styles(&mut root, props);
+    }
+
+    Ok(HtmlDocument { info, root, introspector, assets })
 }
 
 /// Introspects HTML nodes.
@@ -10,7 +10,7 @@ use typst_library::text::TextElem;
 use typst_syntax::Span;
 use typst_utils::{PicoStr, ResolvedPicoStr};
 
-use crate::{attr, charsets, css};
+use crate::{HtmlAsset, attr, charsets, css};
 
 /// An HTML document.
 #[derive(Debug, Clone)]
@@ -21,6 +21,9 @@ pub struct HtmlDocument {
     pub info: DocumentInfo,
     /// Provides the ability to execute queries on the document.
     pub introspector: Introspector,
+    /// Files that the document refers to and that must be written alongside
+    /// it. Only populated when assets are enabled through `html.document`.
+    pub assets: Vec<HtmlAsset>,
 }
 
 /// A child of an HTML element.
@@ -275,6 +278,11 @@ impl HtmlAttrs {
     pub fn get(&self, attr: HtmlAttr) -> Option<&EcoString> {
         self.0.iter().find(|&&(k, _)| k == attr).map(|(_, v)| v)
     }
+
+    /// Finds an attribute value mutably.
+    pub fn get_mut(&mut self, attr: HtmlAttr) -> Option<&mut EcoString> {
+        self.0.make_mut().iter_mut().find(|(k, _)| *k == attr).map(|(_, v)| v)
+    }
 }
 
 cast! {
@@ -7,15 +7,26 @@ use typst_library::introspection::Introspector;
 use typst_syntax::Span;
 
 use crate::{
-    HtmlDocument, HtmlElement, HtmlFrame, HtmlNode, HtmlTag, attr, charsets, tag,
+    HtmlDocument, HtmlElement, HtmlFile, HtmlFrame, HtmlNode, HtmlTag, attr, charsets,
+    tag,
 };
 
 /// Encodes an HTML document into a string.
 pub fn html(document: &HtmlDocument) -> SourceResult<String> {
-    let mut w = Writer::new(&document.introspector, true);
+    encode(&document.root, &document.introspector)
+}
+
+/// Encodes one file of a split HTML document into a string.
+pub fn html_file(document: &HtmlDocument, file: &HtmlFile) -> SourceResult<String> {
+    encode(&file.root, &document.introspector)
+}
+
+/// Encodes a root element into a string.
+fn encode(root: &HtmlElement, introspector: &Introspector) -> SourceResult<String> {
+    let mut w = Writer::new(introspector, true);
     w.buf.push_str("<!DOCTYPE html>");
     write_indent(&mut w);
-    write_element(&mut w, &document.root)?;
+    write_element(&mut w, root)?;
     if w.pretty {
         w.buf.push('\n');
     }
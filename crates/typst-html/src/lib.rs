@@ -1,5 +1,6 @@
 //! Typst's HTML exporter.
 
+mod assets;
 mod attr;
 mod charsets;
 mod convert;
@@ -9,19 +10,26 @@ mod dom;
 mod encode;
 mod fragment;
 mod link;
+mod mathml;
 mod rules;
+mod split;
+mod stylesheet;
 mod tag;
 mod typed;
 
+pub use self::assets::HtmlAsset;
 pub use self::document::html_document;
 pub use self::dom::*;
-pub use self::encode::html;
+pub use self::encode::{html, html_file};
 pub use self::rules::{html_span_filled, register};
+pub use self::split::{HtmlFile, HtmlSplit, html_files};
 
 use ecow::EcoString;
 use typst_library::Category;
-use typst_library::foundations::{Content, Module, Scope};
-use typst_library::introspection::Location;
+use typst_library::diag::{SourceResult, bail};
+use typst_library::engine::Engine;
+use typst_library::foundations::{Args, Construct, Content, Module, Scope};
+use typst_library::introspection::{Locatable, Location};
 use typst_macros::elem;
 
 /// Creates the module with all HTML definitions.
@@ -30,6 +38,8 @@ pub fn module() -> Module {
     html.start_category(Category::Html);
     html.define_elem::<HtmlElem>();
     html.define_elem::<FrameElem>();
+    html.define_elem::<HtmlPageElem>();
+    html.define_elem::<HtmlDocumentElem>();
     crate::typed::define(&mut html);
     Module::new("html", html)
 }
@@ -89,6 +99,15 @@ pub struct HtmlElem {
     #[internal]
     #[ghost]
     pub role: Option<EcoString>,
+
+    /// The text properties of the enclosing element when generating a style
+    /// sheet. Elements and text only receive styles for properties that
+    /// differ from these.
+    ///
+    /// This is `{none}` if no style sheet is generated.
+    #[internal]
+    #[ghost]
+    pub inherited: Option<EcoString>,
 }
 
 impl HtmlElem {
@@ -143,3 +162,101 @@ pub struct FrameElem {
     #[required]
     pub body: Content,
 }
+
+/// Puts content into a separate file when splitting the HTML export into
+/// multiple files.
+///
+/// By default, HTML export produces a single file. When passing
+/// `--html-split` to the CLI, Typst instead writes a directory with one file
+/// per page. Content outside of pages ends up in files of its own. Pages must
+/// be placed at the top level of the document. In single-file HTML export and
+/// in paged export, a page just shows its body.
+///
+/// ```typ
+/// #html.page(path: "intro.html")[
+///   = Introduction
+///   Typst is a markup-based typesetting system.
+/// ]
+/// ```
+#[elem(name = "page", Locatable)]
+pub struct HtmlPageElem {
+    /// The path of the file, relative to the output directory.
+    ///
+    /// If `{none}`, a path is derived from the page's title.
+    pub path: Option<EcoString>,
+
+    /// The page's title, which is used for its `<title>` element and in the
+    /// navigation between pages.
+    ///
+    /// If `{none}`, the text of the first level-1 heading on the page is used.
+    pub title: Option<EcoString>,
+
+    /// The contents of the page.
+    #[required]
+    pub body: Content,
+}
+
+/// HTML-specific settings for the whole document.
+///
+/// Like the [`document`] function, this function can only be used with
+/// [set rules]($styling/#set-rules). Such a set rule must not occur inside of
+/// any layout container.
+///
+/// ```typ
+/// #set html.document(stylesheet: true)
+/// ```
+#[elem(name = "document", Construct)]
+pub struct HtmlDocumentElem {
+    /// Whether to translate set rules into a style sheet.
+    ///
+    /// By default, Typst emits purely semantic markup. When this is enabled,
+    /// the resolved font family, size, weight, style, and color, the
+    /// paragraph leading and justification, the alignment, as well as the
+    /// strokes and fills of table cells are collected into a `<style>` element
+    /// in the document's `<head>`. Elements are styled through generated
+    /// classes, which only contain the properties that differ from the
+    /// surrounding content. Inline styles, like those of underlined text, are
+    /// moved into the style sheet as well.
+    ///
+    /// ```typ
+    /// #set html.document(stylesheet: true)
+    /// #set text(font: "Inria Serif", size: 12pt)
+    /// #set par(leading: 0.8em)
+    ///
+    /// = Introduction
+    /// This text is #text(red)[partially red].
+    /// ```
+    #[ghost]
+    #[default(false)]
+    pub stylesheet: bool,
+
+    /// Whether to write images and frames into separate asset files.
+    ///
+    /// By default, images are embedded into the HTML as data URLs and
+    /// [frames]($html.frame) as inline SVGs. When this is enabled, they are
+    /// instead written into an `assets` directory next to the HTML output.
+    /// The files are named after a hash of their contents, so that identical
+    /// images are only written once and can be cached indefinitely.
+    ///
+    /// Images additionally receive `width` and `height` attributes derived
+    /// from their size in Typst, so that browsers can reserve space for them
+    /// before they are loaded. Raster images with a known width also get a
+    /// `srcset` with downscaled versions for normal and high-density
+    /// displays.
+    ///
+    /// Frames that contain links stay inline.
+    ///
+    /// ```typ
+    /// #set html.document(assets: true)
+    /// #image("tiger.jpg", width: 6cm)
+    /// ```
+    #[ghost]
+    #[default(false)]
+    pub assets: bool,
+}
+
+impl Construct for HtmlDocumentElem {
+    fn construct(_: &mut Engine, args: &mut Args) -> SourceResult<Content> {
+        bail!(args.span, "can only be used in set rules")
+    }
+}
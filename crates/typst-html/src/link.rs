@@ -70,6 +70,88 @@ pub fn identify_link_targets(
     introspector.set_html_ids(work.ids);
 }
 
+/// Rewrites links between the files of a split document.
+///
+/// Links to IDs that ended up in a different file are prefixed with the
+/// relative path to that file.
+pub fn rewrite_links(files: &mut [(EcoString, EcoVec<HtmlNode>)]) {
+    let mut ids = FxHashMap::default();
+    for (path, nodes) in files.iter() {
+        collect_ids(path, nodes, &mut ids);
+    }
+
+    for (path, nodes) in files.iter_mut() {
+        rewrite(path, &ids, nodes);
+    }
+}
+
+/// Determines the relative URL from one file of a split document to another.
+pub fn relative_href(from: &str, to: &str) -> EcoString {
+    let mut from_dirs: Vec<&str> = from.split('/').collect();
+    from_dirs.pop();
+    let mut to_dirs: Vec<&str> = to.split('/').collect();
+    let file = to_dirs.pop().unwrap_or_default();
+
+    let common = from_dirs.iter().zip(&to_dirs).take_while(|(a, b)| a == b).count();
+    let mut href = EcoString::new();
+    for _ in common..from_dirs.len() {
+        href.push_str("../");
+    }
+    for dir in &to_dirs[common..] {
+        href.push_str(dir);
+        href.push('/');
+    }
+    href.push_str(file);
+    href
+}
+
+/// Records in which file each ID is defined.
+fn collect_ids(
+    path: &EcoString,
+    nodes: &[HtmlNode],
+    ids: &mut FxHashMap<EcoString, EcoString>,
+) {
+    for node in nodes {
+        let defined: Vec<&EcoString> = match node {
+            HtmlNode::Element(element) => {
+                collect_ids(path, &element.children, ids);
+                element.attrs.get(attr::id).into_iter().collect()
+            }
+            HtmlNode::Frame(frame) => frame
+                .id
+                .iter()
+                .chain(frame.link_points.iter().map(|(_, id)| id))
+                .collect(),
+            HtmlNode::Tag(_) | HtmlNode::Text(..) => continue,
+        };
+
+        for id in defined {
+            ids.entry(id.clone()).or_insert_with(|| path.clone());
+        }
+    }
+}
+
+/// Rewrites the links within one file.
+fn rewrite(
+    path: &str,
+    ids: &FxHashMap<EcoString, EcoString>,
+    nodes: &mut EcoVec<HtmlNode>,
+) {
+    for node in nodes.make_mut() {
+        let HtmlNode::Element(element) = node else { continue };
+        if element.tag == tag::a
+            && let Some(href) = element.attrs.get_mut(attr::href)
+            && let Some(id) = href.strip_prefix('#')
+            && let Some(target) = ids.get(id)
+            && target != path
+        {
+            let rewritten = eco_format!("{}#{id}", relative_href(path, target));
+            *href = rewritten;
+        }
+        rewrite(path, ids, &mut element.children);
+    }
+}
+
 /// Traverses a list of nodes.
 fn traverse(
     work: &mut Work,
@@ -289,3 +371,17 @@ fn disambiguate(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_href() {
+        assert_eq!(relative_href("index.html", "intro.html"), "intro.html");
+        assert_eq!(relative_href("guide/intro.html", "index.html"), "../index.html");
+        assert_eq!(relative_href("guide/intro.html", "guide/next.html"), "next.html");
+        assert_eq!(relative_href("a/b.html", "c/d.html"), "../c/d.html");
+        assert_eq!(relative_href("index.html", "a/b/c.html"), "a/b/c.html");
+    }
+}
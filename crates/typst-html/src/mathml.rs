@@ -0,0 +1,661 @@
+//! Conversion of equations into MathML.
+
+use codex::styling::{MathStyle, to_style};
+use ecow::{EcoString, eco_format};
+use typst_library::diag::{SourceResult, warning};
+use typst_library::engine::Engine;
+use typst_library::foundations::{
+    Content, NativeElement, Packed, StyleChain, SymbolElem,
+};
+use typst_library::introspection::{SplitLocator, TagElem};
+use typst_library::layout::{HAlignment, HElem, Spacing};
+use typst_library::math::{
+    AccentElem, AlignPointElem, AttachElem, BinomElem, CasesElem, ClassElem,
+    DelimiterPair, EquationElem, FracElem, FracStyle, LimitsElem, LrElem, MatElem,
+    MathSize, MidElem, OpElem, OverbraceElem, OverbracketElem, OverlineElem,
+    OverparenElem, OvershellElem, PrimesElem, RootElem, ScriptsElem, StretchElem,
+    UnderbraceElem, UnderbracketElem, UnderlineElem, UnderparenElem, UndershellElem,
+    VecElem,
+};
+use typst_library::routines::{Arenas, RealizationKind};
+use typst_library::text::{LinebreakElem, SpaceElem, TextElem};
+use typst_syntax::Span;
+use typst_utils::default_math_class;
+use unicode_math_class::MathClass;
+
+use crate::{FrameElem, HtmlAttr, HtmlElem, HtmlTag, attr, css, tag};
+
+/// Converts the body of an equation into MathML elements.
+///
+/// Equations with multiple lines become a table, whose columns are formed by
+/// the alignment points.
+pub fn convert_equation(
+    engine: &mut Engine,
+    locator: &mut SplitLocator,
+    body: &Content,
+    styles: StyleChain,
+) -> SourceResult<Content> {
+    let mut ctx = MathContext { engine, locator };
+    let rows = ctx.convert_rows(body, styles)?;
+    Ok(Content::sequence(arrange(rows)))
+}
+
+/// The rows of a multi-line equation, each consisting of the cells between
+/// alignment points.
+type Rows = Vec<Vec<Vec<Content>>>;
+
+/// The state of the conversion.
+struct MathContext<'a, 'e, 'l> {
+    engine: &'a mut Engine<'e>,
+    locator: &'a mut SplitLocator<'l>,
+}
+
+impl MathContext<'_, '_, '_> {
+    /// Converts math content into rows of cells.
+    fn convert_rows(
+        &mut self,
+        content: &Content,
+        styles: StyleChain,
+    ) -> SourceResult<Rows> {
+        let arenas = Arenas::default();
+        let pairs = (self.engine.routines.realize)(
+            RealizationKind::Math,
+            self.engine,
+            self.locator,
+            &arenas,
+            content,
+            styles,
+        )?;
+
+        let mut rows: Rows = vec![vec![vec![]]];
+        for (elem, styles) in pairs {
+            if elem.is::<LinebreakElem>() {
+                rows.push(vec![vec![]]);
+            } else if elem.is::<AlignPointElem>() {
+                rows.last_mut().unwrap().push(vec![]);
+            } else {
+                let cell = rows.last_mut().unwrap().last_mut().unwrap();
+                self.convert_realized(elem, styles, cell)?;
+            }
+        }
+
+        Ok(rows)
+    }
+
+    /// Converts math content into a list of MathML elements.
+    fn convert(
+        &mut self,
+        content: &Content,
+        styles: StyleChain,
+    ) -> SourceResult<Vec<Content>> {
+        let rows = self.convert_rows(content, styles)?;
+        Ok(arrange(rows))
+    }
+
+    /// Converts math content into a single MathML element.
+    fn convert_row(
+        &mut self,
+        content: &Content,
+        styles: StyleChain,
+    ) -> SourceResult<Content> {
+        let items = self.convert(content, styles)?;
+        Ok(row(items))
+    }
+
+    /// Converts an optional attachment into a single MathML element.
+    fn convert_script(
+        &mut self,
+        content: Option<Content>,
+        styles: StyleChain,
+    ) -> SourceResult<Option<Content>> {
+        content.map(|content| self.convert_row(&content, styles)).transpose()
+    }
+
+    /// Converts a leaf element resulting from realization.
+    fn convert_realized(
+        &mut self,
+        elem: &Content,
+        styles: StyleChain,
+        out: &mut Vec<Content>,
+    ) -> SourceResult<()> {
+        if elem.is::<TagElem>() {
+            out.push(elem.clone());
+        } else if elem.is::<SpaceElem>() {
+            // MathML spaces the elements itself.
+        } else if let Some(elem) = elem.to_packed::<HElem>() {
+            self.convert_h(elem, out);
+        } else if let Some(elem) = elem.to_packed::<TextElem>() {
+            out.push(convert_text(elem, styles));
+        } else if let Some(elem) = elem.to_packed::<SymbolElem>() {
+            out.push(convert_symbol(elem, styles));
+        } else if let Some(elem) = elem.to_packed::<ClassElem>() {
+            let style = EquationElem::class.set(Some(elem.class)).wrap();
+            out.extend(self.convert(&elem.body, styles.chain(&style))?);
+        } else if let Some(elem) = elem.to_packed::<OpElem>() {
+            let text = elem.text.plain_text();
+            out.push(leaf(tag::mi, text, elem.span()));
+        } else if let Some(elem) = elem.to_packed::<AccentElem>() {
+            out.push(self.convert_accent(elem, styles)?);
+        } else if let Some(elem) = elem.to_packed::<AttachElem>() {
+            out.push(self.convert_attach(elem, styles)?);
+        } else if let Some(elem) = elem.to_packed::<PrimesElem>() {
+            out.push(leaf(tag::mo, primes(elem.count), elem.span()));
+        } else if let Some(elem) = elem.to_packed::<ScriptsElem>() {
+            out.extend(self.convert(&elem.body, styles)?);
+        } else if let Some(elem) = elem.to_packed::<LimitsElem>() {
+            out.extend(self.convert(&elem.body, styles)?);
+        } else if let Some(elem) = elem.to_packed::<StretchElem>() {
+            out.extend(self.convert(&elem.body, styles)?);
+        } else if let Some(elem) = elem.to_packed::<FracElem>() {
+            out.push(self.convert_frac(elem, styles)?);
+        } else if let Some(elem) = elem.to_packed::<BinomElem>() {
+            out.push(self.convert_binom(elem, styles)?);
+        } else if let Some(elem) = elem.to_packed::<LrElem>() {
+            out.push(wrap(tag::mrow, self.convert(&elem.body, styles)?));
+        } else if let Some(elem) = elem.to_packed::<MidElem>() {
+            out.extend(self.convert(&elem.body, styles)?);
+        } else if let Some(elem) = elem.to_packed::<RootElem>() {
+            let radicand = self.convert_row(&elem.radicand, styles)?;
+            out.push(match elem.index.get_ref(styles) {
+                Some(index) => {
+                    let index = self.convert_row(index, styles)?;
+                    wrap(tag::mroot, [radicand, index])
+                }
+                None => wrap(tag::msqrt, [radicand]),
+            });
+        } else if let Some(elem) = elem.to_packed::<VecElem>() {
+            let rows = elem.children.iter().map(|child| vec![child]).collect();
+            let delim = elem.delim.get(styles);
+            let align = elem.align.get(styles);
+            out.push(self.convert_matrix(rows, align, delim, (true, true), styles)?);
+        } else if let Some(elem) = elem.to_packed::<MatElem>()
+            && elem.augment.get_ref(styles).is_none()
+        {
+            let rows = elem.rows.iter().map(|row| row.iter().collect()).collect();
+            let delim = elem.delim.get(styles);
+            let align = elem.align.get(styles);
+            out.push(self.convert_matrix(rows, align, delim, (true, true), styles)?);
+        } else if let Some(elem) = elem.to_packed::<CasesElem>() {
+            let rows = elem.children.iter().map(|child| vec![child]).collect();
+            let delim = elem.delim.get(styles);
+            let reverse = elem.reverse.get(styles);
+            let sides = (!reverse, reverse);
+            out.push(self.convert_matrix(
+                rows,
+                HAlignment::Start,
+                delim,
+                sides,
+                styles,
+            )?);
+        } else if let Some(elem) = elem.to_packed::<UnderlineElem>() {
+            out.push(self.convert_underover(&elem.body, None, '_', false, true, styles)?);
+        } else if let Some(elem) = elem.to_packed::<OverlineElem>() {
+            out.push(self.convert_underover(&elem.body, None, '‾', true, true, styles)?);
+        } else if let Some(elem) = elem.to_packed::<UnderbraceElem>() {
+            let annotation = elem.annotation.get_ref(styles).as_ref();
+            out.push(
+                self.convert_underover(
+                    &elem.body, annotation, '⏟', false, false, styles,
+                )?,
+            );
+        } else if let Some(elem) = elem.to_packed::<OverbraceElem>() {
+            let annotation = elem.annotation.get_ref(styles).as_ref();
+            out.push(
+                self.convert_underover(&elem.body, annotation, '⏞', true, false, styles)?,
+            );
+        } else if let Some(elem) = elem.to_packed::<UnderbracketElem>() {
+            let annotation = elem.annotation.get_ref(styles).as_ref();
+            out.push(
+                self.convert_underover(
+                    &elem.body, annotation, '⎵', false, false, styles,
+                )?,
+            );
+        } else if let Some(elem) = elem.to_packed::<OverbracketElem>() {
+            let annotation = elem.annotation.get_ref(styles).as_ref();
+            out.push(
+                self.convert_underover(&elem.body, annotation, '⎴', true, false, styles)?,
+            );
+        } else if let Some(elem) = elem.to_packed::<UnderparenElem>() {
+            let annotation = elem.annotation.get_ref(styles).as_ref();
+            out.push(
+                self.convert_underover(
+                    &elem.body, annotation, '⏝', false, false, styles,
+                )?,
+            );
+        } else if let Some(elem) = elem.to_packed::<OverparenElem>() {
+            let annotation = elem.annotation.get_ref(styles).as_ref();
+            out.push(
+                self.convert_underover(&elem.body, annotation, '⏜', true, false, styles)?,
+            );
+        } else if let Some(elem) = elem.to_packed::<UndershellElem>() {
+            let annotation = elem.annotation.get_ref(styles).as_ref();
+            out.push(
+                self.convert_underover(
+                    &elem.body, annotation, '⏡', false, false, styles,
+                )?,
+            );
+        } else if let Some(elem) = elem.to_packed::<OvershellElem>() {
+            let annotation = elem.annotation.get_ref(styles).as_ref();
+            out.push(
+                self.convert_underover(&elem.body, annotation, '⏠', true, false, styles)?,
+            );
+        } else {
+            // Everything else, such as cancelled content, augmented matrices,
+            // and boxes, cannot be expressed in MathML and is laid out into an
+            // SVG instead.
+            let frame = FrameElem::new(elem.clone()).pack().spanned(elem.span());
+            out.push(HtmlElem::new(tag::mtext).with_body(Some(frame)).pack());
+        }
+
+        Ok(())
+    }
+
+    /// Converts an [`HElem`].
+    ///
+    /// The `width` attribute only accepts lengths, so relative spacing is
+    /// sized through CSS instead. Fractional spacing has nothing to distribute
+    /// within a row and is ignored.
+    fn convert_h(&mut self, elem: &Packed<HElem>, out: &mut Vec<Content>) {
+        let space = match elem.amount {
+            Spacing::Rel(rel) if rel.rel.is_zero() => HtmlElem::new(tag::mspace)
+                .with_attr(attr::width, eco_format!("{}", css::length(rel.abs))),
+            Spacing::Rel(rel) => HtmlElem::new(tag::mspace)
+                .with_styles(css::Properties::new().with("width", css::rel(rel))),
+            Spacing::Fr(_) => {
+                self.engine.sink.warn(warning!(
+                    elem.span(),
+                    "fractional spacing was ignored during MathML export";
+                    hint: "use absolute or relative spacing instead"
+                ));
+                return;
+            }
+        };
+        out.push(space.pack().spanned(elem.span()));
+    }
+
+    /// Converts an [`AccentElem`].
+    fn convert_accent(
+        &mut self,
+        elem: &Packed<AccentElem>,
+        styles: StyleChain,
+    ) -> SourceResult<Content> {
+        let base = self.convert_row(&elem.base, styles)?;
+        let accent = leaf(tag::mo, spacing_accent(elem.accent.0).into(), elem.span());
+        let (tag, attr) = if elem.accent.is_bottom() {
+            (tag::munder, const { HtmlAttr::constant("accentunder") })
+        } else {
+            (tag::mover, const { HtmlAttr::constant("accent") })
+        };
+        Ok(HtmlElem::new(tag)
+            .with_attr(attr, "true")
+            .with_body(Some(base + accent))
+            .pack()
+            .spanned(elem.span()))
+    }
+
+    /// Converts an [`AttachElem`].
+    fn convert_attach(
+        &mut self,
+        elem: &Packed<AttachElem>,
+        styles: StyleChain,
+    ) -> SourceResult<Content> {
+        let merged = elem.merge_base();
+        let elem = merged.as_ref().unwrap_or(elem);
+
+        // Distribute the attachments in the same way as the layout does.
+        let limits = has_limits(&elem.base, styles);
+        let tr = elem.tr.get_cloned(styles);
+        let primed = tr.as_ref().is_some_and(|content| content.is::<PrimesElem>());
+        let (t, tr) = match (elem.t.get_cloned(styles), tr) {
+            (Some(t), Some(tr)) if primed && !limits => (None, Some(tr + t)),
+            (Some(t), None) if !limits => (None, Some(t)),
+            (t, tr) => (t, tr),
+        };
+        let (b, br) = match (elem.b.get_cloned(styles), elem.br.get_cloned(styles)) {
+            (b, None) if !limits => (None, b),
+            (b, br) => (b, br),
+        };
+
+        let base = self.convert_row(&elem.base, styles)?;
+        let over = self.convert_script(t, styles)?;
+        let under = self.convert_script(b, styles)?;
+        let tl = self.convert_script(elem.tl.get_cloned(styles), styles)?;
+        let bl = self.convert_script(elem.bl.get_cloned(styles), styles)?;
+        let sup = self.convert_script(tr, styles)?;
+        let sub = self.convert_script(br, styles)?;
+
+        let base = match (under, over) {
+            (Some(under), Some(over)) => wrap(tag::munderover, [base, under, over]),
+            (Some(under), None) => wrap(tag::munder, [base, under]),
+            (None, Some(over)) => wrap(tag::mover, [base, over]),
+            (None, None) => base,
+        };
+
+        Ok(if tl.is_some() || bl.is_some() {
+            let none = || wrap(tag::mrow, []);
+            wrap(
+                tag::mmultiscripts,
+                [
+                    base,
+                    sub.unwrap_or_else(none),
+                    sup.unwrap_or_else(none),
+                    wrap(tag::mprescripts, []),
+                    bl.unwrap_or_else(none),
+                    tl.unwrap_or_else(none),
+                ],
+            )
+        } else {
+            match (sub, sup) {
+                (Some(sub), Some(sup)) => wrap(tag::msubsup, [base, sub, sup]),
+                (Some(sub), None) => wrap(tag::msub, [base, sub]),
+                (None, Some(sup)) => wrap(tag::msup, [base, sup]),
+                (None, None) => base,
+            }
+        })
+    }
+
+    /// Converts a [`FracElem`].
+    fn convert_frac(
+        &mut self,
+        elem: &Packed<FracElem>,
+        styles: StyleChain,
+    ) -> SourceResult<Content> {
+        let num = self.convert_row(&elem.num, styles)?;
+        let denom = self.convert_row(&elem.denom, styles)?;
+        Ok(match elem.style.get(styles) {
+            FracStyle::Vertical => wrap(tag::mfrac, [num, denom]),
+            FracStyle::Skewed => wrap(tag::mrow, [num, operator("/"), denom]),
+            FracStyle::Horizontal => {
+                // Restore the parentheses that were removed by the parser.
+                let parens = |content, deparenthesized| {
+                    if deparenthesized {
+                        wrap(tag::mrow, [operator("("), content, operator(")")])
+                    } else {
+                        content
+                    }
+                };
+                wrap(
+                    tag::mrow,
+                    [
+                        parens(num, elem.num_deparenthesized.get(styles)),
+                        operator("/"),
+                        parens(denom, elem.denom_deparenthesized.get(styles)),
+                    ],
+                )
+            }
+        })
+    }
+
+    /// Converts a [`BinomElem`].
+    fn convert_binom(
+        &mut self,
+        elem: &Packed<BinomElem>,
+        styles: StyleChain,
+    ) -> SourceResult<Content> {
+        let upper = self.convert_row(&elem.upper, styles)?;
+        let mut lower = vec![];
+        for (i, child) in elem.lower.iter().enumerate() {
+            if i > 0 {
+                lower.push(operator(","));
+            }
+            lower.extend(self.convert(child, styles)?);
+        }
+
+        let frac = HtmlElem::new(tag::mfrac)
+            .with_attr(const { HtmlAttr::constant("linethickness") }, "0")
+            .with_body(Some(upper + row(lower)))
+            .pack();
+        Ok(wrap(tag::mrow, [operator("("), frac, operator(")")]))
+    }
+
+    /// Converts the cells of a vector, matrix, or cases into a table between
+    /// delimiters.
+    fn convert_matrix(
+        &mut self,
+        rows: Vec<Vec<&Content>>,
+        align: HAlignment,
+        delim: DelimiterPair,
+        (open, close): (bool, bool),
+        styles: StyleChain,
+    ) -> SourceResult<Content> {
+        let mut trs = vec![];
+        for cells in rows {
+            let mut tds = vec![];
+            for cell in cells {
+                let mut td = HtmlElem::new(tag::mtd);
+                if align != HAlignment::Center {
+                    td = td.with_styles(
+                        css::Properties::new().with("text-align", text_align(align)),
+                    );
+                }
+                let body = Content::sequence(self.convert(cell, styles)?);
+                tds.push(td.with_body(Some(body)).pack());
+            }
+            trs.push(wrap(tag::mtr, tds));
+        }
+
+        let mut items = vec![];
+        if let Some(c) = delim.open().filter(|_| open) {
+            items.push(operator(&c.to_string()));
+        }
+        items.push(wrap(tag::mtable, trs));
+        if let Some(c) = delim.close().filter(|_| close) {
+            items.push(operator(&c.to_string()));
+        }
+        Ok(wrap(tag::mrow, items))
+    }
+
+    /// Converts a line, brace, bracket, parenthesis, or shell under or over
+    /// content, with an optional annotation.
+    fn convert_underover(
+        &mut self,
+        body: &Content,
+        annotation: Option<&Content>,
+        mark: char,
+        over: bool,
+        accent: bool,
+        styles: StyleChain,
+    ) -> SourceResult<Content> {
+        let tag = if over { tag::mover } else { tag::munder };
+        let body = self.convert_row(body, styles)?;
+        let mut elem =
+            HtmlElem::new(tag).with_body(Some(body + operator(&mark.to_string())));
+        if accent {
+            let attr = if over {
+                const { HtmlAttr::constant("accent") }
+            } else {
+                const { HtmlAttr::constant("accentunder") }
+            };
+            elem = elem.with_attr(attr, "true");
+        }
+
+        let mut realized = elem.pack();
+        if let Some(annotation) = annotation {
+            let annotation = self.convert_row(annotation, styles)?;
+            realized = wrap(tag, [realized, annotation]);
+        }
+
+        Ok(realized)
+    }
+}
+
+/// Converts text in math, which is either a number or a piece of upright
+/// text.
+fn convert_text(elem: &Packed<TextElem>, styles: StyleChain) -> Content {
+    let text = &elem.text;
+    let is_number = text.starts_with(|c: char| c.is_ascii_digit())
+        && text.chars().all(|c| c.is_ascii_digit() || c == '.');
+    let tag = if is_number { tag::mn } else { tag::mtext };
+    let text = styled(text, styles, true).unwrap_or_else(|| text.clone());
+    leaf(tag, text, elem.span())
+}
+
+/// Converts a symbol, which is an identifier or an operator depending on its
+/// math class.
+fn convert_symbol(elem: &Packed<SymbolElem>, styles: StyleChain) -> Content {
+    let text = &elem.text;
+    let single = text.parse::<char>().ok();
+    let class = styles
+        .get(EquationElem::class)
+        .or_else(|| single.and_then(default_math_class));
+
+    let tag = match class {
+        Some(MathClass::Normal | MathClass::Alphabetic) | None => tag::mi,
+        _ => tag::mo,
+    };
+
+    let mut mi = HtmlElem::new(tag);
+    let mut text = text.clone();
+    if tag == tag::mi
+        && let Some(styled) = styled(&text, styles, false)
+    {
+        // The characters are already styled, so MathML shouldn't make single
+        // letters italic anymore.
+        if single.is_some() {
+            mi = mi.with_attr(const { HtmlAttr::constant("mathvariant") }, "normal");
+        }
+        text = styled;
+    }
+
+    mi.with_body(Some(TextElem::packed(text).spanned(elem.span())))
+        .pack()
+        .spanned(elem.span())
+}
+
+/// Applies the math variant, boldness, and italicness to text. Returns `None`
+/// if none of them are set, in which case MathML's defaults apply.
+fn styled(text: &str, styles: StyleChain, upright: bool) -> Option<EcoString> {
+    let variant = styles.get(EquationElem::variant);
+    let bold = styles.get(EquationElem::bold);
+    let italic = styles.get(EquationElem::italic);
+    if variant.is_none() && !bold && italic.is_none() {
+        return None;
+    }
+
+    let italic = italic.or(upright.then_some(false));
+    Some(
+        text.chars()
+            .flat_map(|c| to_style(c, MathStyle::select(c, variant, bold, italic)))
+            .collect(),
+    )
+}
+
+/// Whether the attachments of a base are placed above and below it rather
+/// than next to it.
+fn has_limits(base: &Content, styles: StyleChain) -> bool {
+    let display = styles.get(EquationElem::size) == MathSize::Display;
+    if let Some(elem) = base.to_packed::<LimitsElem>() {
+        display || elem.inline.get(styles)
+    } else if let Some(elem) = base.to_packed::<OpElem>() {
+        display && elem.limits.get(styles)
+    } else if let Some(elem) = base.to_packed::<SymbolElem>()
+        && let Ok(c) = elem.text.parse::<char>()
+    {
+        match default_math_class(c) {
+            Some(MathClass::Large) => display && !is_integral_char(c),
+            Some(MathClass::Relation) => true,
+            _ => false,
+        }
+    } else {
+        false
+    }
+}
+
+/// Determines if the character is one of a variety of integral signs.
+fn is_integral_char(c: char) -> bool {
+    ('∫'..='∳').contains(&c) || ('⨋'..='⨜').contains(&c)
+}
+
+/// Arranges rows of cells into a table if there are multiple rows. The cells
+/// are alternately aligned to the right and to the left, just like in laid
+/// out equations.
+fn arrange(mut rows: Rows) -> Vec<Content> {
+    if rows.len() == 1 {
+        return rows.pop().unwrap().into_iter().flatten().collect();
+    }
+
+    let aligned = rows.iter().any(|cells| cells.len() > 1);
+    let trs = rows.into_iter().map(|cells| {
+        let tds = cells.into_iter().enumerate().map(|(i, cell)| {
+            let mut td = HtmlElem::new(tag::mtd);
+            if aligned {
+                let align = if i % 2 == 0 { "right" } else { "left" };
+                td = td.with_styles(css::Properties::new().with("text-align", align));
+            }
+            td.with_body(Some(Content::sequence(cell))).pack()
+        });
+        wrap(tag::mtr, tds)
+    });
+
+    vec![wrap(tag::mtable, trs)]
+}
+
+/// Turns a list of MathML elements into a single one.
+fn row(mut items: Vec<Content>) -> Content {
+    if items.len() == 1 { items.pop().unwrap() } else { wrap(tag::mrow, items) }
+}
+
+/// Creates a MathML element with the given children.
+fn wrap(tag: HtmlTag, children: impl IntoIterator<Item = Content>) -> Content {
+    HtmlElem::new(tag).with_body(Some(Content::sequence(children))).pack()
+}
+
+/// Creates a MathML element containing text.
+fn leaf(tag: HtmlTag, text: EcoString, span: Span) -> Content {
+    HtmlElem::new(tag)
+        .with_body(Some(TextElem::packed(text).spanned(span)))
+        .pack()
+        .spanned(span)
+}
+
+/// Creates an operator.
+fn operator(text: &str) -> Content {
+    HtmlElem::new(tag::mo).with_body(Some(TextElem::packed(text))).pack()
+}
+
+/// The text of grouped primes.
+fn primes(count: usize) -> EcoString {
+    let mut text = EcoString::new();
+    let mut remaining = count;
+    while remaining > 0 {
+        let n = remaining.min(4);
+        text.push(['′', '″', '‴', '⁗'][n - 1]);
+        remaining -= n;
+    }
+    text
+}
+
+/// Turns a combining accent into its spacing form, which MathML expects.
+fn spacing_accent(c: char) -> char {
+    match c {
+        '\u{0300}' => '`',
+        '\u{0301}' => '´',
+        '\u{0302}' => '^',
+        '\u{0303}' => '~',
+        '\u{0304}' => '¯',
+        '\u{0305}' => '‾',
+        '\u{0306}' => '˘',
+        '\u{0307}' => '˙',
+        '\u{0308}' => '¨',
+        '\u{030a}' => '˚',
+        '\u{030b}' => '˝',
+        '\u{030c}' => 'ˇ',
+        '\u{20d6}' => '←',
+        '\u{20d7}' => '→',
+        '\u{20e1}' => '↔',
+        '\u{20d0}' => '↼',
+        '\u{20d1}' => '⇀',
+        c => c,
+    }
+}
+
+/// The CSS value for a horizontal alignment.
+fn text_align(align: HAlignment) -> &'static str {
+    match align {
+        HAlignment::Start => "start",
+        HAlignment::Left => "left",
+        HAlignment::Center => "center",
+        HAlignment::Right => "right",
+        HAlignment::End => "end",
+    }
+}
@@ -5,29 +5,39 @@ use ecow::{EcoVec, eco_format};
 use typst_library::diag::{At, SourceResult, bail, error, warning};
 use typst_library::engine::Engine;
 use typst_library::foundations::{
-    Content, Context, NativeElement, NativeRuleMap, ShowFn, Smart, StyleChain, Target,
+    Content, Context, NativeElement, NativeRuleMap, SequenceElem, ShowFn, Smart,
+    StyleChain, StyledElem, SymbolElem, Target,
 };
-use typst_library::introspection::Counter;
+use typst_library::introspection::{Counter, Locator};
 use typst_library::layout::resolve::{Cell, CellGrid, Entry};
 use typst_library::layout::{
-    BlockBody, BlockElem, BoxElem, HElem, OuterVAlignment, Sizing,
+    AlignElem, Alignment, BlockBody, BlockElem, BoxElem, ColbreakElem, ColumnsElem, Dir,
+    GridCell, GridElem, HAlignment, HElem, OuterVAlignment, PadElem, PlaceElem, Sizing,
+    Spacing, StackChild, StackElem,
 };
+use typst_library::math::EquationElem;
 use typst_library::model::{
     Attribution, BibliographyElem, CiteElem, CiteGroup, CslIndentElem, CslLightElem,
     Destination, DirectLinkElem, EmphElem, EnumElem, FigureCaption, FigureElem,
-    FootnoteElem, FootnoteEntry, FootnoteMarker, HeadingElem, LinkElem, LinkTarget,
-    ListElem, OutlineElem, OutlineEntry, OutlineNode, ParElem, ParbreakElem, QuoteElem,
-    RefElem, StrongElem, TableCell, TableElem, TermsElem, TitleElem, Works,
+    FootnoteElem, FootnoteEntry, FootnoteMarker, GlossaryElem, HeadingElem, IndexElem,
+    LinkElem, LinkTarget, ListElem, MakeIndexElem, MarginNoteElem, OutlineElem,
+    OutlineEntry, OutlineNode, ParElem, ParbreakElem, QuoteElem, RefElem, StrongElem,
+    TableCell, TableElem, TermsElem, TitleElem, Works,
 };
+use typst_library::pdf::{AnnotationElem, LayerElem};
 use typst_library::text::{
     HighlightElem, LinebreakElem, OverlineElem, RawElem, RawLine, SmallcapsElem,
-    SpaceElem, StrikeElem, SubElem, SuperElem, UnderlineElem,
+    SmartQuoteElem, SpaceElem, StrikeElem, SubElem, SuperElem, TextElem, UnderlineElem,
 };
 use typst_library::visualize::{Color, ImageElem};
 use typst_macros::elem;
-use typst_utils::singleton;
+use typst_utils::{Numeric, singleton};
 
-use crate::{FrameElem, HtmlAttr, HtmlAttrs, HtmlElem, HtmlTag, attr, css, tag};
+use crate::mathml::convert_equation;
+use crate::{
+    FrameElem, HtmlAttr, HtmlAttrs, HtmlDocumentElem, HtmlElem, HtmlPageElem, HtmlTag,
+    attr, css, tag,
+};
 
 /// Registers show rules for the [HTML target](Target::Html).
 pub fn register(rules: &mut NativeRuleMap) {
@@ -51,11 +61,15 @@ pub fn register(rules: &mut NativeRuleMap) {
     rules.register(Html, FOOTNOTE_MARKER_RULE);
     rules.register(Html, FOOTNOTE_CONTAINER_RULE);
     rules.register(Html, FOOTNOTE_ENTRY_RULE);
+    rules.register(Html, MARGIN_NOTE_RULE);
     rules.register(Html, OUTLINE_RULE);
     rules.register(Html, OUTLINE_ENTRY_RULE);
+    rules.register(Html, INDEX_RULE);
+    rules.register(Html, MAKE_INDEX_RULE);
     rules.register(Html, REF_RULE);
     rules.register(Html, CITE_GROUP_RULE);
     rules.register(Html, BIBLIOGRAPHY_RULE);
+    rules.register(Html, GLOSSARY_RULE);
     rules.register(Html, CSL_LIGHT_RULE);
     rules.register(Html, CSL_INDENT_RULE);
     rules.register(Html, TABLE_RULE);
@@ -71,17 +85,36 @@ pub fn register(rules: &mut NativeRuleMap) {
     rules.register(Html, RAW_RULE);
     rules.register(Html, RAW_LINE_RULE);
 
+    // Math.
+    rules.register(Html, EQUATION_RULE);
+
     // Layout.
     rules.register(Html, BLOCK_RULE);
     rules.register(Html, BOX_RULE);
+    rules.register(Html, ALIGN_RULE);
+    rules.register(Html, PAD_RULE);
+    rules.register(Html, PLACE_RULE);
+    rules.register(Html, COLUMNS_RULE);
+    rules.register(Html, COLBREAK_RULE);
+    rules.register(Html, STACK_RULE);
+    rules.register(Html, GRID_RULE);
 
     // Visualize.
     rules.register(Html, IMAGE_RULE);
 
+    // PDF.
+    rules.register(Html, LAYER_RULE);
+    rules.register(Html, ANNOTATION_RULE);
+
     // For the HTML target, `html.frame` is a primitive. In the laid-out target,
     // it should be a no-op so that nested frames don't break (things like `show
     // math.equation: html.frame` can result in nested ones).
     rules.register::<FrameElem>(Paged, |elem, _, _| Ok(elem.body.clone()));
+
+    // Pages only matter when splitting the HTML export into multiple files,
+    // which happens after realization.
+    rules.register::<HtmlPageElem>(Html, |elem, _, _| Ok(elem.body.clone()));
+    rules.register::<HtmlPageElem>(Paged, |elem, _, _| Ok(elem.body.clone()));
 }
 
 const PAR_RULE: ShowFn<ParElem> =
@@ -164,6 +197,7 @@ const LINK_RULE: ShowFn<LinkElem> = |elem, engine, _| {
 
     let href = match dest {
         Destination::Url(url) => Some(url.clone().into_inner()),
+        Destination::File(link) => Some(link.href()),
         Destination::Location(location) => {
             let id = engine
                 .introspector
@@ -408,6 +442,17 @@ const FOOTNOTE_ENTRY_RULE: ShowFn<FootnoteEntry> = |elem, engine, styles| {
     Ok(backlink + body)
 };
 
+// HTML has no page margins, so the note stays inline and is left to the
+// page's style sheet to be floated out of the text.
+const MARGIN_NOTE_RULE: ShowFn<MarginNoteElem> = |elem, _, _| {
+    Ok(HtmlElem::new(tag::span)
+        .with_attr(attr::class, "margin-note")
+        .with_attr(attr::role, "note")
+        .with_body(Some(elem.body.clone()))
+        .pack()
+        .spanned(elem.span()))
+};
+
 const OUTLINE_RULE: ShowFn<OutlineElem> = |elem, engine, styles| {
     fn convert_list(list: Vec<OutlineNode>) -> Content {
         // The Digital Publishing ARIA spec also proposed to add
@@ -471,6 +516,17 @@ const OUTLINE_ENTRY_RULE: ShowFn<OutlineEntry> = |elem, engine, styles| {
     Ok(LinkElem::new(dest.into(), realized).pack())
 };
 
+// The marker is collected by the index through introspection.
+const INDEX_RULE: ShowFn<IndexElem> = |_, _, _| Ok(Content::empty());
+
+// HTML has no pages, so the terms link to their first occurrence instead.
+const MAKE_INDEX_RULE: ShowFn<MakeIndexElem> = |elem, engine, styles| {
+    Ok(HtmlElem::new(tag::section)
+        .with_attr(attr::role, "doc-index")
+        .with_body(Some(elem.realize(engine, styles, false)?))
+        .pack())
+};
+
 const REF_RULE: ShowFn<RefElem> = |elem, engine, styles| elem.realize(engine, styles);
 
 const CITE_GROUP_RULE: ShowFn<CiteGroup> = |elem, engine, _| {
@@ -529,6 +585,12 @@ const BIBLIOGRAPHY_RULE: ShowFn<BibliographyElem> = |elem, engine, styles| {
         .pack())
 };
 
+const GLOSSARY_RULE: ShowFn<GlossaryElem> = |elem, engine, styles| {
+    let title = elem.realize_title(styles);
+    let entries = elem.realize_entries(engine, styles, false)?;
+    Ok(Content::sequence(title.into_iter().chain(Some(entries))))
+};
+
 const CSL_LIGHT_RULE: ShowFn<CslLightElem> = |elem, _, _| {
     Ok(HtmlElem::new(tag::span)
         .with_attr(attr::class, "light")
@@ -619,10 +681,17 @@ fn show_cellgrid(grid: &CellGrid, styles: StyleChain) -> Content {
     }
 
     let content = header.into_iter().chain(core::iter::once(body)).chain(footer);
-    elem(tag::table, Content::sequence(content))
+    let mut table = HtmlElem::new(tag::table).with_body(Some(Content::sequence(content)));
+    if styles.get(HtmlDocumentElem::stylesheet) {
+        // Adjacent cells share their strokes, like in paged export.
+        table =
+            table.with_styles(css::Properties::new().with("border-collapse", "collapse"));
+    }
+    table.pack()
 }
 
 fn show_cell(tag: HtmlTag, cell: &Cell, styles: StyleChain) -> Content {
+    let properties = crate::stylesheet::cell_properties(cell);
     let cell = cell.body.clone();
     let Some(cell) = cell.to_packed::<TableCell>() else { return cell };
     let mut attrs = HtmlAttrs::new();
@@ -633,11 +702,13 @@ fn show_cell(tag: HtmlTag, cell: &Cell, styles: StyleChain) -> Content {
     if let Some(rowspan) = span(cell.rowspan.get(styles)) {
         attrs.push(attr::rowspan, rowspan);
     }
-    HtmlElem::new(tag)
+    let mut elem = HtmlElem::new(tag)
         .with_body(Some(cell.body.clone()))
-        .with_attrs(attrs)
-        .pack()
-        .spanned(cell.span())
+        .with_attrs(attrs);
+    if styles.get(HtmlDocumentElem::stylesheet) {
+        elem = elem.with_styles(properties);
+    }
+    elem.pack().spanned(cell.span())
 }
 
 const SUB_RULE: ShowFn<SubElem> =
@@ -725,6 +796,44 @@ pub fn html_span_filled(content: Content, color: Color) -> Content {
 
 const RAW_LINE_RULE: ShowFn<RawLine> = |elem, _, _| Ok(elem.body.clone());
 
+const EQUATION_RULE: ShowFn<EquationElem> = |elem, engine, styles| {
+    let span = elem.span();
+    let location = elem.location().unwrap();
+    let block = elem.block.get(styles);
+
+    let mut locator = Locator::synthesize(location).split();
+    let body = convert_equation(engine, &mut locator, &elem.body, styles)?;
+    let math = HtmlElem::new(tag::math)
+        .with_optional_attr(
+            const { HtmlAttr::constant("display") },
+            block.then_some("block"),
+        )
+        .with_optional_attr(attr::aria_label, elem.alt.get_cloned(styles))
+        .with_body(Some(body))
+        .pack()
+        .spanned(span);
+
+    if !block {
+        return Ok(math);
+    }
+
+    // A block-level equation is wrapped in a `<div>` so that it doesn't end
+    // up in a paragraph, along with its number, if any.
+    let mut realized = math;
+    if let Some(numbering) = elem.numbering.get_ref(styles) {
+        let numbering = Counter::of(EquationElem::ELEM)
+            .display_at_loc(engine, location, styles, numbering)?
+            .spanned(span);
+        realized += HtmlElem::new(tag::span)
+            .with_attr(attr::class, "number")
+            .with_body(Some(numbering))
+            .pack()
+            .spanned(span);
+    }
+
+    Ok(HtmlElem::new(tag::div).with_body(Some(realized)).pack())
+};
+
 // TODO: This is rather incomplete.
 const BLOCK_RULE: ShowFn<BlockElem> = |elem, _, styles| {
     let body = match elem.body.get_cloned(styles) {
@@ -751,6 +860,217 @@ const BOX_RULE: ShowFn<BoxElem> = |elem, _, styles| {
         .pack())
 };
 
+const ALIGN_RULE: ShowFn<AlignElem> = |elem, _, styles| {
+    let mut props = css::Properties::new();
+    if let Some(x) = elem.alignment.get(styles).x() {
+        props.push("text-align", css::align(x));
+    }
+    Ok(HtmlElem::new(tag::div)
+        .with_body(Some(elem.body.clone()))
+        .with_styles(props)
+        .pack())
+};
+
+const PAD_RULE: ShowFn<PadElem> = |elem, _, styles| {
+    let sides = [
+        elem.top.get(styles),
+        elem.right.get(styles),
+        elem.bottom.get(styles),
+        elem.left.get(styles),
+    ];
+    let mut props = css::Properties::new();
+    if sides.iter().any(|side| !side.is_zero()) {
+        let [top, right, bottom, left] = sides.map(css::rel);
+        props.push("padding", eco_format!("{top} {right} {bottom} {left}"));
+    }
+    Ok(HtmlElem::new(tag::div)
+        .with_body(Some(elem.body.clone()))
+        .with_styles(props)
+        .pack())
+};
+
+// HTML has no way to place content relative to its container without
+// affecting the surrounding content. Placed content thus stays in the flow,
+// but keeps its horizontal alignment and offsets.
+const PLACE_RULE: ShowFn<PlaceElem> = |elem, _, styles| {
+    let mut props = css::Properties::new();
+    let margin = match elem.alignment.get(styles).custom().and_then(Alignment::x) {
+        Some(HAlignment::Center) => Some("margin-inline"),
+        Some(HAlignment::End) => Some("margin-inline-start"),
+        Some(HAlignment::Right) => Some("margin-left"),
+        _ => None,
+    };
+    if let Some(margin) = margin {
+        props.push("width", "fit-content");
+        props.push(margin, "auto");
+    }
+
+    let (dx, dy) = (elem.dx.get(styles), elem.dy.get(styles));
+    if !dx.is_zero() || !dy.is_zero() {
+        props.push("position", "relative");
+    }
+    if !dx.is_zero() {
+        props.push("left", css::rel(dx));
+    }
+    if !dy.is_zero() {
+        props.push("top", css::rel(dy));
+    }
+
+    Ok(HtmlElem::new(tag::div)
+        .with_body(Some(elem.body.clone()))
+        .with_styles(props)
+        .pack())
+};
+
+const COLUMNS_RULE: ShowFn<ColumnsElem> = |elem, _, styles| {
+    Ok(HtmlElem::new(tag::div)
+        .with_body(Some(elem.body.clone()))
+        .with_styles(
+            css::Properties::new()
+                .with("column-count", elem.count.get(styles))
+                .with("column-gap", css::rel(elem.gutter.get(styles))),
+        )
+        .pack())
+};
+
+const COLBREAK_RULE: ShowFn<ColbreakElem> = |_, _, _| {
+    Ok(HtmlElem::new(tag::div)
+        .with_styles(css::Properties::new().with("break-after", "column"))
+        .pack())
+};
+
+const STACK_RULE: ShowFn<StackElem> = |elem, _, styles| {
+    let direction = match elem.dir.get(styles) {
+        Dir::LTR => "row",
+        Dir::RTL => "row-reverse",
+        Dir::TTB => "column",
+        Dir::BTT => "column-reverse",
+    };
+
+    let mut props = css::Properties::new()
+        .with("display", "flex")
+        .with("flex-direction", direction);
+    if let Some(Spacing::Rel(gap)) = elem.spacing.get(styles) {
+        props.push("gap", css::rel(gap));
+    }
+
+    // Elements become flex items on their own. Text, however, would merge
+    // with neighbouring children into a single item, so it is wrapped.
+    let children = elem.children.iter().map(|child| match child {
+        StackChild::Block(body) if is_text(body) => {
+            HtmlElem::new(tag::div).with_body(Some(body.clone())).pack()
+        }
+        StackChild::Block(body) => body.clone(),
+        StackChild::Spacing(Spacing::Rel(rel)) => HtmlElem::new(tag::div)
+            .with_styles(
+                css::Properties::new()
+                    .with("flex", eco_format!("0 0 {}", css::rel(*rel))),
+            )
+            .pack(),
+        StackChild::Spacing(Spacing::Fr(fr)) => HtmlElem::new(tag::div)
+            .with_styles(css::Properties::new().with("flex-grow", fr.get()))
+            .pack(),
+    });
+
+    Ok(HtmlElem::new(tag::div)
+        .with_body(Some(Content::sequence(children)))
+        .with_styles(props)
+        .pack())
+};
+
+/// Whether content is text or a sequence, which do not become a single HTML
+/// element.
+fn is_text(content: &Content) -> bool {
+    let mut content = content;
+    while let Some(styled) = content.to_packed::<StyledElem>() {
+        content = &styled.child;
+    }
+
+    content.is::<SequenceElem>()
+        || content.is::<TextElem>()
+        || content.is::<SpaceElem>()
+        || content.is::<SymbolElem>()
+        || content.is::<SmartQuoteElem>()
+        || content.is::<LinebreakElem>()
+}
+
+const GRID_RULE: ShowFn<GridElem> = |elem, _, styles| {
+    let grid = elem.grid.as_ref().unwrap();
+
+    // The tracks of a resolved grid alternate with gutter tracks.
+    let step = if grid.has_gutter { 2 } else { 1 };
+    let tracks = |sizings: &[Sizing]| {
+        sizings
+            .iter()
+            .step_by(step)
+            .map(|&sizing| css::sizing(sizing).to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let mut props = css::Properties::new()
+        .with("display", "grid")
+        .with("grid-template-columns", tracks(&grid.cols));
+    if grid.rows.iter().step_by(step).any(|&row| row != Sizing::Auto) {
+        props.push("grid-template-rows", tracks(&grid.rows));
+    }
+
+    // CSS only supports uniform gaps, so we use the first gutter track.
+    for (property, gutter) in
+        [("column-gap", grid.cols.get(1)), ("row-gap", grid.rows.get(1))]
+    {
+        if grid.has_gutter
+            && let Some(&Sizing::Rel(gap)) = gutter
+            && !gap.is_zero()
+        {
+            props.push(property, css::rel(gap));
+        }
+    }
+
+    // All positions of a resolved grid are covered by a cell, so CSS's
+    // automatic placement reproduces the grid as long as the spans match.
+    let cells = grid.entries.iter().filter_map(Entry::as_cell).map(|cell| {
+        let Some(cell) = cell.body.to_packed::<GridCell>() else {
+            return cell.body.clone();
+        };
+
+        let mut props = css::Properties::new();
+        let colspan = cell.colspan.get(styles);
+        if colspan != NonZeroUsize::MIN {
+            props.push("grid-column", eco_format!("span {colspan}"));
+        }
+        let rowspan = cell.rowspan.get(styles);
+        if rowspan != NonZeroUsize::MIN {
+            props.push("grid-row", eco_format!("span {rowspan}"));
+        }
+        if let Smart::Custom(inset) = cell.inset.get(styles) {
+            let [top, right, bottom, left] =
+                [inset.top, inset.right, inset.bottom, inset.left]
+                    .map(Option::unwrap_or_default);
+            if [top, right, bottom, left].iter().any(|side| !side.is_zero()) {
+                let [top, right, bottom, left] = [top, right, bottom, left].map(css::rel);
+                props.push("padding", eco_format!("{top} {right} {bottom} {left}"));
+            }
+        }
+        if let Smart::Custom(align) = cell.align.get(styles)
+            && let Some(x) = align.x()
+        {
+            props.push("text-align", css::align(x));
+        }
+
+        HtmlElem::new(tag::div)
+            .with_body(Some(cell.body.clone()))
+            .with_styles(props)
+            .pack()
+            .spanned(cell.span())
+    });
+
+    Ok(HtmlElem::new(tag::div)
+        .with_body(Some(Content::sequence(cells)))
+        .with_styles(props)
+        .pack())
+};
+
 const IMAGE_RULE: ShowFn<ImageElem> = |elem, engine, styles| {
     let image = elem.decode(engine, styles)?;
 
@@ -761,6 +1081,16 @@ const IMAGE_RULE: ShowFn<ImageElem> = |elem, engine, styles| {
         attrs.push(attr::alt, alt);
     }
 
+    // When images are written into separate files, the browser only learns
+    // their size once they are loaded. The attributes let it reserve space
+    // upfront. Asset extraction later derives the `srcset` from the width.
+    if styles.get(HtmlDocumentElem::assets)
+        && let Some(size) = crate::assets::image_size(elem, &image, styles)
+    {
+        attrs.push(attr::width, eco_format!("{}", css::pixels(size.x)));
+        attrs.push(attr::height, eco_format!("{}", css::pixels(size.y)));
+    }
+
     let mut inline = css::Properties::new();
 
     // TODO: Exclude in semantic profile.
@@ -783,3 +1113,10 @@ const IMAGE_RULE: ShowFn<ImageElem> = |elem, engine, styles| {
 
     Ok(HtmlElem::new(tag::img).with_attrs(attrs).with_styles(inline).pack())
 };
+
+const LAYER_RULE: ShowFn<LayerElem> = |elem, _, styles| {
+    Ok(if elem.visible.get(styles) { elem.body.clone() } else { Content::empty() })
+};
+
+const ANNOTATION_RULE: ShowFn<AnnotationElem> =
+    |elem, _, styles| Ok(elem.body.get_cloned(styles).unwrap_or_default());
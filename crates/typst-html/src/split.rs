@@ -0,0 +1,578 @@
+//! Splitting of HTML documents into multiple files.
+
+use ecow::{EcoString, EcoVec, eco_format, eco_vec};
+use rustc_hash::FxHashSet;
+use typst_library::diag::{At, SourceResult, StrResult, bail};
+use typst_library::foundations::{NativeElement, StyleChain};
+use typst_library::introspection::{Location, Tag};
+use typst_syntax::Span;
+
+use crate::link::{relative_href, rewrite_links};
+use crate::{HtmlDocument, HtmlElement, HtmlNode, HtmlPageElem, HtmlTag, attr, tag};
+
+/// Where to split an HTML document into multiple files.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum HtmlSplit {
+    /// Starts a new file for each `html.page`.
+    Page,
+    /// Starts a new file for each `html.page` and each level-1 heading
+    /// outside of pages.
+    Heading,
+}
+
+/// One file of an HTML document that was split into multiple files.
+#[derive(Debug, Clone)]
+pub struct HtmlFile {
+    /// The file's path, relative to the output directory.
+    pub path: EcoString,
+    /// The file's title, if any.
+    pub title: Option<EcoString>,
+    /// The file's root element.
+    pub root: HtmlElement,
+}
+
+/// Splits an HTML document into multiple files.
+///
+/// The first file is called `index.html`, unless it is a page with an explicit
+/// path. Links between the files are rewritten, footnotes move to the file
+/// that references them, and each file receives navigation to the others.
+/// References to the document's assets are adjusted for files in
+/// subdirectories.
+#[typst_macros::time(name = "split html")]
+pub fn html_files(
+    document: &HtmlDocument,
+    split: HtmlSplit,
+) -> SourceResult<Vec<HtmlFile>> {
+    let root = &document.root;
+    let Some(body) = find_child(root, tag::body) else {
+        // Without a body, there is nothing to split.
+        return Ok(vec![HtmlFile {
+            path: "index.html".into(),
+            title: document.info.title.clone(),
+            root: root.clone(),
+        }]);
+    };
+
+    let (nodes, notes) = extract_notes(&body.children);
+    let mut parts = partition(&nodes, split);
+    let paths = assign_paths(&parts)?;
+    if let Some(notes) = notes {
+        distribute_notes(&mut parts, notes);
+    }
+
+    let mut bodies: Vec<_> = paths
+        .iter()
+        .cloned()
+        .zip(parts.iter_mut().map(|part| std::mem::take(&mut part.nodes)))
+        .collect();
+    rewrite_links(&mut bodies);
+    for (path, nodes) in &mut bodies {
+        crate::assets::relocate_assets(path, nodes, &document.assets);
+    }
+
+    let labels: Vec<_> = parts
+        .iter()
+        .zip(&paths)
+        .map(|(part, path)| part.title.clone().unwrap_or_else(|| path.clone()))
+        .collect();
+
+    let mut files = vec![];
+    for (i, ((path, nodes), part)) in bodies.into_iter().zip(&parts).enumerate() {
+        let title = part.title.clone().or_else(|| document.info.title.clone());
+
+        let mut children = EcoVec::new();
+        if paths.len() > 1 {
+            children.push(outline(&paths, &labels, i).into());
+        }
+        children.extend(nodes);
+        if paths.len() > 1 {
+            children.push(pagination(&paths, &labels, i).into());
+        }
+
+        let mut root = root.clone();
+        for node in root.children.make_mut() {
+            let HtmlNode::Element(element) = node else { continue };
+            if element.tag == tag::body {
+                element.children = std::mem::take(&mut children);
+            } else if element.tag == tag::head
+                && let Some(title) = &title
+            {
+                set_title(element, title);
+            }
+        }
+
+        files.push(HtmlFile { path, title, root });
+    }
+
+    Ok(files)
+}
+
+/// A part of the document that becomes its own file.
+#[derive(Default)]
+struct Part {
+    /// The path specified by a page, along with the page's span.
+    path: Option<(EcoString, Span)>,
+    /// The title of the part.
+    title: Option<EcoString>,
+    /// The location of the page this part consists of, if any.
+    page: Option<Location>,
+    /// The part's top-level nodes.
+    nodes: EcoVec<HtmlNode>,
+}
+
+/// Splits the top-level nodes of the body into parts.
+fn partition(nodes: &[HtmlNode], split: HtmlSplit) -> Vec<Part> {
+    let mut parts = vec![Part::default()];
+    for node in nodes {
+        match node {
+            HtmlNode::Tag(Tag::Start(elem, _)) if elem.is::<HtmlPageElem>() => {
+                let page = elem.to_packed::<HtmlPageElem>().unwrap();
+                let styles = StyleChain::default();
+                parts.push(Part {
+                    path: page.path.get_cloned(styles).map(|path| (path, page.span())),
+                    title: page.title.get_cloned(styles),
+                    page: page.location(),
+                    nodes: eco_vec![node.clone()],
+                });
+                continue;
+            }
+            HtmlNode::Tag(Tag::End(loc, _, _))
+                if parts.last().unwrap().page == Some(*loc) =>
+            {
+                parts.last_mut().unwrap().nodes.push(node.clone());
+                parts.push(Part::default());
+                continue;
+            }
+            HtmlNode::Element(element)
+                if split == HtmlSplit::Heading && element.tag == tag::h2 =>
+            {
+                let last = parts.last().unwrap();
+                if last.page.is_none() && has_content(&last.nodes) {
+                    parts.push(Part::default());
+                }
+            }
+            _ => {}
+        }
+
+        let part = parts.last_mut().unwrap();
+        if part.title.is_none()
+            && let HtmlNode::Element(element) = node
+            && element.tag == tag::h2
+        {
+            part.title = Some(text_content(&element.children).trim().into());
+        }
+        part.nodes.push(node.clone());
+    }
+
+    // Pages with an explicit path always produce a file, everything else only
+    // if it is non-empty.
+    parts.retain(|part| part.path.is_some() || has_content(&part.nodes));
+    if parts.is_empty() {
+        parts.push(Part::default());
+    }
+
+    parts
+}
+
+/// Determines the path of each part.
+fn assign_paths(parts: &[Part]) -> SourceResult<Vec<EcoString>> {
+    let mut used = FxHashSet::default();
+    for part in parts {
+        if let Some((path, span)) = &part.path {
+            validate_path(path).at(*span)?;
+            if !used.insert(path.clone()) {
+                bail!(*span, "multiple pages have the path `{path}`");
+            }
+        }
+    }
+
+    let mut paths = vec![];
+    for (i, part) in parts.iter().enumerate() {
+        if let Some((path, _)) = &part.path {
+            paths.push(path.clone());
+            continue;
+        }
+
+        let stem = if i == 0 {
+            "index".into()
+        } else {
+            part.title
+                .as_deref()
+                .map(slugify)
+                .filter(|slug| !slug.is_empty())
+                .unwrap_or_else(|| eco_format!("page-{}", i + 1))
+        };
+
+        let mut path = eco_format!("{stem}.html");
+        let mut n = 1;
+        while used.contains(&path) {
+            n += 1;
+            path = eco_format!("{stem}-{n}.html");
+        }
+
+        used.insert(path.clone());
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
+/// Ensures that a page's path stays within the output directory.
+fn validate_path(path: &str) -> StrResult<()> {
+    if path.contains('\\')
+        || path.split('/').any(|segment| matches!(segment, "" | "." | ".."))
+    {
+        bail!("page path must be a relative path within the output directory");
+    }
+    Ok(())
+}
+
+/// Turns a title into a string that is suitable for a file name.
+fn slugify(title: &str) -> EcoString {
+    let mut slug = EcoString::new();
+    for word in title.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        for c in word.chars().flat_map(char::to_lowercase) {
+            slug.push(c);
+        }
+    }
+    slug
+}
+
+/// The footnote container of a document, which is split up between the
+/// files.
+struct Notes {
+    /// The `<section>` around the list.
+    section: HtmlElement,
+    /// The `<ol>` around the entries.
+    list: HtmlElement,
+    /// The `<li>` elements of the footnotes.
+    entries: Vec<HtmlElement>,
+}
+
+/// Removes the footnote container from the top-level nodes of the body.
+fn extract_notes(nodes: &[HtmlNode]) -> (Vec<HtmlNode>, Option<Notes>) {
+    let is_container = |node: &HtmlNode| {
+        matches!(node, HtmlNode::Element(element)
+            if element.tag == tag::section
+                && element.attrs.get(attr::role).is_some_and(|role| role == "doc-endnotes"))
+    };
+
+    let Some(i) = nodes.iter().rposition(is_container) else {
+        return (nodes.to_vec(), None);
+    };
+
+    let HtmlNode::Element(section) = &nodes[i] else { unreachable!() };
+    let Some(list) = find_child(section, tag::ol) else {
+        return (nodes.to_vec(), None);
+    };
+
+    let entries = list
+        .children
+        .iter()
+        .filter_map(|node| match node {
+            HtmlNode::Element(element) if element.tag == tag::li => Some(element.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let mut rest = nodes.to_vec();
+    rest.remove(i);
+    (
+        rest,
+        Some(Notes {
+            section: section.clone(),
+            list: list.clone(),
+            entries,
+        }),
+    )
+}
+
+/// Adds each footnote to the end of the first part that references it.
+fn distribute_notes(parts: &mut [Part], notes: Notes) {
+    let hrefs: Vec<_> = parts
+        .iter()
+        .map(|part| {
+            let mut hrefs = FxHashSet::default();
+            collect_hrefs(&part.nodes, &mut hrefs);
+            hrefs
+        })
+        .collect();
+
+    let mut entries = vec![vec![]; parts.len()];
+    for entry in notes.entries {
+        let i = entry
+            .attrs
+            .get(attr::id)
+            .and_then(|id| {
+                let href = eco_format!("#{id}");
+                hrefs.iter().position(|hrefs| hrefs.contains(&href))
+            })
+            .unwrap_or(parts.len() - 1);
+        entries[i].push(HtmlNode::Element(entry));
+    }
+
+    for (part, entries) in parts.iter_mut().zip(entries) {
+        if entries.is_empty() {
+            continue;
+        }
+
+        let list = notes.list.clone().with_children(entries.into_iter().collect());
+        let section = notes.section.clone().with_children(eco_vec![list.into()]);
+        part.nodes.push(section.into());
+    }
+}
+
+/// Collects the targets of all links within the nodes.
+fn collect_hrefs(nodes: &[HtmlNode], hrefs: &mut FxHashSet<EcoString>) {
+    for node in nodes {
+        let HtmlNode::Element(element) = node else { continue };
+        if element.tag == tag::a
+            && let Some(href) = element.attrs.get(attr::href)
+        {
+            hrefs.insert(href.clone());
+        }
+        collect_hrefs(&element.children, hrefs);
+    }
+}
+
+/// Creates the list of all files that is placed at the start of each file.
+fn outline(paths: &[EcoString], labels: &[EcoString], current: usize) -> HtmlElement {
+    let items = paths.iter().zip(labels).enumerate().map(|(i, (path, label))| {
+        let mut link = HtmlElement::new(tag::a)
+            .with_attr(attr::href, relative_href(&paths[current], path));
+        if i == current {
+            link = link.with_attr(attr::aria_current, "page");
+        }
+        let link =
+            link.with_children(eco_vec![HtmlNode::text(label.clone(), Span::detached())]);
+        HtmlElement::new(tag::li).with_children(eco_vec![link.into()]).into()
+    });
+
+    HtmlElement::new(tag::nav)
+        .with_attr(attr::class, "pages")
+        .with_children(eco_vec![
+            HtmlElement::new(tag::ol).with_children(items.collect()).into()
+        ])
+}
+
+/// Creates the links to the previous and next file that are placed at the end
+/// of each file.
+fn pagination(paths: &[EcoString], labels: &[EcoString], current: usize) -> HtmlElement {
+    let mut children = EcoVec::new();
+    let mut link = |i: usize, rel: &'static str| {
+        let link = HtmlElement::new(tag::a)
+            .with_attr(attr::rel, rel)
+            .with_attr(attr::href, relative_href(&paths[current], &paths[i]))
+            .with_children(eco_vec![HtmlNode::text(labels[i].clone(), Span::detached())]);
+        children.push(link.into());
+    };
+
+    if current > 0 {
+        link(current - 1, "prev");
+    }
+    if current + 1 < paths.len() {
+        link(current + 1, "next");
+    }
+
+    HtmlElement::new(tag::nav)
+        .with_attr(attr::class, "pagination")
+        .with_children(children)
+}
+
+/// Sets the `<title>` of a `<head>` element.
+fn set_title(head: &mut HtmlElement, title: &EcoString) {
+    let text = eco_vec![HtmlNode::text(title.clone(), Span::detached())];
+    for node in head.children.make_mut() {
+        if let HtmlNode::Element(element) = node
+            && element.tag == tag::title
+        {
+            element.children = text;
+            return;
+        }
+    }
+    head.children
+        .push(HtmlElement::new(tag::title).with_children(text).into());
+}
+
+/// Finds the first child element with the given tag.
+fn find_child(element: &HtmlElement, tag: HtmlTag) -> Option<&HtmlElement> {
+    element.children.iter().find_map(|node| match node {
+        HtmlNode::Element(child) if child.tag == tag => Some(child),
+        _ => None,
+    })
+}
+
+/// Whether the nodes contain anything visible.
+fn has_content(nodes: &[HtmlNode]) -> bool {
+    nodes.iter().any(|node| match node {
+        HtmlNode::Tag(_) => false,
+        HtmlNode::Text(text, _) => !text.trim().is_empty(),
+        HtmlNode::Element(_) | HtmlNode::Frame(_) => true,
+    })
+}
+
+/// The concatenated text within the nodes.
+fn text_content(nodes: &[HtmlNode]) -> EcoString {
+    let mut text = EcoString::new();
+    for node in nodes {
+        match node {
+            HtmlNode::Text(t, _) => text.push_str(t),
+            HtmlNode::Element(element) => text.push_str(&text_content(&element.children)),
+            HtmlNode::Tag(_) | HtmlNode::Frame(_) => {}
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use typst_library::introspection::Introspector;
+    use typst_library::model::DocumentInfo;
+
+    use super::*;
+
+    fn elem(tag: HtmlTag, children: EcoVec<HtmlNode>) -> HtmlNode {
+        HtmlElement::new(tag).with_children(children).into()
+    }
+
+    fn text(text: &str) -> EcoVec<HtmlNode> {
+        eco_vec![HtmlNode::text(text, Span::detached())]
+    }
+
+    #[test]
+    fn test_split_at_headings() {
+        let link = HtmlElement::new(tag::a)
+            .with_attr(attr::href, "#second")
+            .with_children(text("see"));
+        let heading = HtmlElement::new(tag::h2)
+            .with_attr(attr::id, "second")
+            .with_children(text("Second Part"));
+        let body = eco_vec![
+            elem(tag::p, text("Intro")),
+            elem(tag::h2, text("First")),
+            elem(tag::p, eco_vec![link.into()]),
+            heading.into(),
+        ];
+        let root = HtmlElement::new(tag::html).with_children(eco_vec![
+            elem(tag::head, EcoVec::new()),
+            elem(tag::body, body),
+        ]);
+        let document = HtmlDocument {
+            root,
+            info: DocumentInfo::default(),
+            introspector: Introspector::default(),
+            assets: vec![],
+        };
+
+        let files = html_files(&document, HtmlSplit::Heading).unwrap();
+        let paths: Vec<_> = files.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, ["index.html", "first.html", "second-part.html"]);
+        assert_eq!(files[1].title.as_deref(), Some("First"));
+
+        let html = crate::html_file(&document, &files[1]).unwrap();
+        assert!(html.contains(r#"<a href="second-part.html#second">see</a>"#));
+        assert!(html.contains(r#"<a href="first.html" aria-current="page">First</a>"#));
+        assert!(html.contains(r#"<a rel="prev" href="index.html">index.html</a>"#));
+        assert!(html.contains("<title>First</title>"));
+
+        let files = html_files(&document, HtmlSplit::Page).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "index.html");
+    }
+
+    #[test]
+    fn test_validate_path() {
+        assert!(validate_path("index.html").is_ok());
+        assert!(validate_path("guide/intro.html").is_ok());
+        for path in
+            ["", "/abs.html", "../up.html", "a/./b.html", "a//b.html", "a\\b.html"]
+        {
+            let message = validate_path(path).unwrap_err();
+            assert_eq!(
+                message,
+                "page path must be a relative path within the output directory"
+            );
+        }
+    }
+
+    #[test]
+    fn test_assign_paths() {
+        let part = |path: Option<&str>, title: Option<&str>| Part {
+            path: path.map(|path| (path.into(), Span::detached())),
+            title: title.map(Into::into),
+            ..Default::default()
+        };
+
+        let parts = [
+            part(None, Some("Ignored")),
+            part(Some("intro.html"), None),
+            part(None, Some("Intro")),
+            part(None, None),
+        ];
+        let paths = assign_paths(&parts).unwrap();
+        assert_eq!(paths, ["index.html", "intro.html", "intro-2.html", "page-4.html"]);
+
+        let parts = [part(Some("a.html"), None), part(Some("a.html"), None)];
+        let errors = assign_paths(&parts).unwrap_err();
+        assert_eq!(errors[0].message, "multiple pages have the path `a.html`");
+
+        let errors = assign_paths(&[part(Some("../a.html"), None)]).unwrap_err();
+        assert_eq!(
+            errors[0].message,
+            "page path must be a relative path within the output directory"
+        );
+    }
+
+    #[test]
+    fn test_distribute_notes() {
+        let reference = |id: &str| -> HtmlNode {
+            HtmlElement::new(tag::a)
+                .with_attr(attr::href, eco_format!("#{id}"))
+                .with_children(text("1"))
+                .into()
+        };
+        let entry = |id: &str| {
+            HtmlElement::new(tag::li)
+                .with_attr(attr::id, id)
+                .with_children(text(id))
+        };
+        let part = |nodes: EcoVec<HtmlNode>| Part { nodes, ..Default::default() };
+
+        let mut parts = [
+            part(eco_vec![elem(tag::p, text("Intro"))]),
+            part(eco_vec![elem(tag::p, eco_vec![reference("b"), reference("a")])]),
+            part(eco_vec![elem(tag::p, eco_vec![reference("a")])]),
+        ];
+        let notes = Notes {
+            section: HtmlElement::new(tag::section).with_attr(attr::role, "doc-endnotes"),
+            list: HtmlElement::new(tag::ol),
+            entries: vec![entry("a"), entry("b"), entry("orphan")],
+        };
+        distribute_notes(&mut parts, notes);
+
+        // Returns the ids of the footnotes at the end of a part.
+        let ids = |part: &Part| -> Vec<EcoString> {
+            let Some(HtmlNode::Element(section)) = part.nodes.last() else {
+                return vec![];
+            };
+            if section.tag != tag::section {
+                return vec![];
+            }
+            let list = find_child(section, tag::ol).unwrap();
+            list.children
+                .iter()
+                .filter_map(|node| match node {
+                    HtmlNode::Element(li) => li.attrs.get(attr::id).cloned(),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        assert_eq!(parts[0].nodes.len(), 1);
+        assert_eq!(ids(&parts[1]), ["a", "b"]);
+        assert_eq!(ids(&parts[2]), ["orphan"]);
+    }
+}
@@ -0,0 +1,183 @@
+//! Generation of a style sheet from resolved styles.
+
+use std::fmt::Write;
+
+use ecow::{EcoString, eco_format, eco_vec};
+use rustc_hash::FxHashMap;
+use typst_library::foundations::{Smart, StyleChain};
+use typst_library::layout::resolve::Cell;
+use typst_library::layout::{Abs, AlignElem, Length, Sides};
+use typst_library::model::ParElem;
+use typst_library::text::{FontStyle, TextElem};
+use typst_library::visualize::{Color, Paint, Stroke};
+use typst_syntax::Span;
+use typst_utils::Numeric;
+
+use crate::{HtmlElement, HtmlNode, attr, css, tag};
+
+/// Resolves the inherited text properties that are active in the given styles.
+///
+/// Always lists all properties, so that elements can revert to a value of an
+/// ancestor.
+pub fn text_properties(styles: StyleChain) -> EcoString {
+    let mut props = css::Properties::new();
+
+    let mut families = EcoString::new();
+    for family in styles.get_ref(TextElem::font) {
+        if !families.is_empty() {
+            families.push_str(", ");
+        }
+        write!(families, "{:?}", family.as_str()).unwrap();
+    }
+    props.push("font-family", families);
+
+    let size = styles.resolve(TextElem::size);
+    let pt = typst_utils::round_with_precision(size.to_pt(), 2);
+    props.push("font-size", eco_format!("{pt}pt"));
+    props.push("font-weight", styles.get(TextElem::weight).to_number());
+    props.push(
+        "font-style",
+        match styles.get(TextElem::style) {
+            FontStyle::Normal => "normal",
+            FontStyle::Italic => "italic",
+            FontStyle::Oblique => "oblique",
+        },
+    );
+
+    if let Paint::Solid(color) = styles.get_ref(TextElem::fill) {
+        props.push("color", css::color(*color));
+    }
+
+    // In Typst, the leading is the gap between two lines while CSS's line
+    // height also includes the line itself.
+    let leading = styles.resolve(ParElem::leading);
+    if !size.is_zero() {
+        props.push("line-height", css::number(((size + leading) / size) as f32));
+    }
+
+    let align = if styles.get(ParElem::justify) {
+        "justify"
+    } else {
+        css::align(styles.get(AlignElem::alignment).x().unwrap_or_default())
+    };
+    props.push("text-align", align);
+
+    props.into_inline_styles().unwrap_or_default()
+}
+
+/// Determines the properties of `own` that differ from the `inherited` ones.
+pub fn diff(own: &str, inherited: &str) -> Option<EcoString> {
+    let decls: Vec<&str> = own
+        .split("; ")
+        .filter(|decl| !inherited.split("; ").any(|other| other == *decl))
+        .collect();
+    (!decls.is_empty()).then(|| decls.join("; ").into())
+}
+
+/// Translates the fill and strokes of a resolved table cell into CSS
+/// properties.
+pub fn cell_properties(cell: &Cell) -> css::Properties {
+    let mut props = css::Properties::new();
+
+    if let Some(Paint::Solid(color)) = &cell.fill {
+        props.push("background-color", css::color(*color));
+    }
+
+    let Sides { left, top, right, bottom } = &cell.stroke;
+    if left == top && top == right && right == bottom {
+        if let Some(stroke) = top {
+            props.push("border", border(stroke));
+        }
+    } else {
+        for (name, stroke) in [
+            ("border-top", top),
+            ("border-right", right),
+            ("border-bottom", bottom),
+            ("border-left", left),
+        ] {
+            if let Some(stroke) = stroke {
+                props.push(name, border(stroke));
+            }
+        }
+    }
+
+    props
+}
+
+/// Translates a stroke into the value of a CSS `border` property.
+fn border(stroke: &Stroke<Abs>) -> EcoString {
+    let thickness = stroke.thickness.unwrap_or(Abs::pt(1.0));
+    let style = match stroke.dash {
+        Smart::Custom(Some(_)) => "dashed",
+        _ => "solid",
+    };
+    let color = match &stroke.paint {
+        Smart::Custom(Paint::Solid(color)) => *color,
+        _ => Color::BLACK,
+    };
+    eco_format!("{} {style} {}", css::length(Length::from(thickness)), css::color(color))
+}
+
+/// Moves the inline styles of all elements into a `<style>` element in the
+/// `<head>`, replacing them with generated classes.
+///
+/// The `body` properties are the text properties of the whole document.
+pub fn hoist_styles(root: &mut HtmlElement, body: &str) {
+    let mut classes = Classes::default();
+    classes.visit(root);
+
+    let mut sheet = eco_format!("body {{ {body} }}");
+    for (i, props) in classes.list.iter().enumerate() {
+        write!(sheet, "\n.{} {{ {props} }}", class_name(i)).unwrap();
+    }
+
+    let style = HtmlElement::new(tag::style)
+        .with_children(eco_vec![HtmlNode::Text(sheet, Span::detached())]);
+    if let Some(head) = root.children.make_mut().iter_mut().find_map(|node| match node {
+        HtmlNode::Element(elem) if elem.tag == tag::head => Some(elem),
+        _ => None,
+    }) {
+        head.children.push(style.into());
+    }
+}
+
+/// Deduplicates the inline styles of elements into classes.
+#[derive(Default)]
+struct Classes {
+    /// The distinct property lists, in order of first appearance.
+    list: Vec<EcoString>,
+    /// Maps from property lists to indices in `list`.
+    map: FxHashMap<EcoString, usize>,
+}
+
+impl Classes {
+    /// Replaces the `style` attribute of the element and its descendants with
+    /// a class.
+    fn visit(&mut self, elem: &mut HtmlElement) {
+        if let Some(i) = elem.attrs.0.iter().position(|(attr, _)| *attr == attr::style) {
+            let (_, props) = elem.attrs.0.remove(i);
+            let index = *self.map.entry(props.clone()).or_insert_with(|| {
+                self.list.push(props);
+                self.list.len() - 1
+            });
+            let name = class_name(index);
+            if let Some(class) = elem.attrs.get_mut(attr::class) {
+                class.push(' ');
+                class.push_str(&name);
+            } else {
+                elem.attrs.push(attr::class, name);
+            }
+        }
+
+        for node in elem.children.make_mut() {
+            if let HtmlNode::Element(child) = node {
+                self.visit(child);
+            }
+        }
+    }
+}
+
+/// The name of the generated class with the given index.
+fn class_name(index: usize) -> EcoString {
+    eco_format!("typst-{}", index + 1)
+}
@@ -118,6 +118,29 @@ pub const var: HtmlTag = HtmlTag::constant("var");
 pub const video: HtmlTag = HtmlTag::constant("video");
 pub const wbr: HtmlTag = HtmlTag::constant("wbr");
 
+// MathML elements, which can be embedded into HTML.
+pub const math: HtmlTag = HtmlTag::constant("math");
+pub const mfrac: HtmlTag = HtmlTag::constant("mfrac");
+pub const mi: HtmlTag = HtmlTag::constant("mi");
+pub const mmultiscripts: HtmlTag = HtmlTag::constant("mmultiscripts");
+pub const mn: HtmlTag = HtmlTag::constant("mn");
+pub const mo: HtmlTag = HtmlTag::constant("mo");
+pub const mover: HtmlTag = HtmlTag::constant("mover");
+pub const mprescripts: HtmlTag = HtmlTag::constant("mprescripts");
+pub const mroot: HtmlTag = HtmlTag::constant("mroot");
+pub const mrow: HtmlTag = HtmlTag::constant("mrow");
+pub const mspace: HtmlTag = HtmlTag::constant("mspace");
+pub const msqrt: HtmlTag = HtmlTag::constant("msqrt");
+pub const msub: HtmlTag = HtmlTag::constant("msub");
+pub const msubsup: HtmlTag = HtmlTag::constant("msubsup");
+pub const msup: HtmlTag = HtmlTag::constant("msup");
+pub const mtable: HtmlTag = HtmlTag::constant("mtable");
+pub const mtd: HtmlTag = HtmlTag::constant("mtd");
+pub const mtext: HtmlTag = HtmlTag::constant("mtext");
+pub const mtr: HtmlTag = HtmlTag::constant("mtr");
+pub const munder: HtmlTag = HtmlTag::constant("munder");
+pub const munderover: HtmlTag = HtmlTag::constant("munderover");
+
 /// Whether this is a void tag whose associated element may not have
 /// children.
 pub fn is_void(tag: HtmlTag) -> bool {
@@ -249,6 +272,7 @@ pub fn is_inline_by_default(tag: HtmlTag) -> bool {
             | self::sup
             | self::var
             | self::u
+            | self::math
     )
 }
 
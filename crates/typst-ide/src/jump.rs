@@ -45,6 +45,7 @@ pub fn jump_from_click(
                 Destination::Location(loc) => {
                     Jump::Position(document.introspector.position(*loc))
                 }
+                Destination::File(link) => Jump::Url(Url::new(link.href()).ok()?),
             });
         }
     }
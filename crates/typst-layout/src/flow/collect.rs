@@ -13,9 +13,9 @@ use typst_library::introspection::{
     Introspector, Location, Locator, LocatorLink, SplitLocator, Tag, TagElem,
 };
 use typst_library::layout::{
-    Abs, AlignElem, Alignment, Axes, BlockElem, ColbreakElem, FixedAlignment, FlushElem,
-    Fr, Fragment, Frame, FrameParent, Inherit, PagebreakElem, PlaceElem, PlacementScope,
-    Ratio, Region, Regions, Rel, Size, Sizing, Spacing, VElem,
+    Abs, AlignElem, Alignment, Axes, BlockElem, BlockSpan, ColbreakElem, FixedAlignment,
+    FloatPlacement, FlushElem, Fr, Fragment, Frame, FrameParent, Inherit, PagebreakElem,
+    PlaceElem, PlacementScope, Ratio, Region, Regions, Rel, Size, Sizing, Spacing, VElem,
 };
 use typst_library::model::ParElem;
 use typst_library::routines::{Pair, Routines};
@@ -191,19 +191,29 @@ impl<'a> Collector<'a, '_, '_> {
 
         // Determine whether to prevent widow and orphans.
         let len = lines.len();
-        let prevent_orphans =
-            costs.orphan() > Ratio::zero() && len >= 2 && !lines[1].is_empty();
-        let prevent_widows =
-            costs.widow() > Ratio::zero() && len >= 2 && !lines[len - 2].is_empty();
-        let prevent_all = len == 3 && prevent_orphans && prevent_widows;
-
-        // Store the heights of lines at the edges because we'll potentially
-        // need these later when `lines` is already moved.
-        let height_at = |i| lines.get(i).map(Frame::height).unwrap_or_default();
-        let front_1 = height_at(0);
-        let front_2 = height_at(1);
-        let back_2 = height_at(len.saturating_sub(2));
-        let back_1 = height_at(len.saturating_sub(1));
+        let orphans = styles.get(ParElem::orphans).get();
+        let widows = styles.get(ParElem::widows).get();
+        let prevent_orphans = costs.orphan() > Ratio::zero()
+            && orphans >= 2
+            && len >= 2
+            && !lines[1].is_empty();
+        let prevent_widows = costs.widow() > Ratio::zero()
+            && widows >= 2
+            && len >= 2
+            && !lines[len - 2].is_empty();
+
+        // If no split leaves enough lines on both sides, the paragraph is kept
+        // together as a whole. Without prevention, a side can have one line.
+        let first = if prevent_orphans { orphans } else { 1 };
+        let last = if prevent_widows { widows } else { 1 };
+        let prevent_all = (prevent_orphans || prevent_widows) && len < first + last;
+
+        // Store the heights of lines because we'll potentially need these
+        // later when `lines` is already moved.
+        let heights: Vec<Abs> = lines.iter().map(Frame::height).collect();
+        let need_of = |lines: &[Abs]| {
+            lines.iter().sum::<Abs>() + leading * lines.len().saturating_sub(1) as f64
+        };
 
         for (i, frame) in lines.into_iter().enumerate() {
             if i > 0 {
@@ -211,15 +221,15 @@ impl<'a> Collector<'a, '_, '_> {
             }
 
             // To prevent widows and orphans, we require enough space for
-            // - all lines if it's just three
-            // - the first two lines if we're at the first line
-            // - the last two lines if we're at the second to last line
+            // - all lines if the paragraph cannot be split
+            // - the first `orphans` lines if we're at the first line
+            // - the last `widows` lines if we're at the first of them
             let need = if prevent_all && i == 0 {
-                front_1 + leading + front_2 + leading + back_1
+                need_of(&heights)
             } else if prevent_orphans && i == 0 {
-                front_1 + leading + front_2
-            } else if prevent_widows && i >= 2 && i + 2 == len {
-                back_2 + leading + back_1
+                need_of(&heights[..orphans])
+            } else if prevent_widows && i > 0 && i + widows == len {
+                need_of(&heights[i..])
             } else {
                 frame.height()
             };
@@ -230,12 +240,13 @@ impl<'a> Collector<'a, '_, '_> {
     }
 
     /// Collect a block into a [`SingleChild`] or [`MultiChild`] depending on
-    /// whether it is breakable.
+    /// whether it is breakable. Blocks spanning all columns are always
+    /// collected as unbreakable.
     fn block(&mut self, elem: &'a Packed<BlockElem>, styles: StyleChain<'a>) {
         let locator = self.locator.next(&elem.span());
         let align = styles.resolve(AlignElem::alignment);
         let alone = self.children.len() == 1;
-        let sticky = elem.sticky.get(styles);
+        let keep = elem.keep_with_next.get(styles).max(elem.sticky.get(styles).into());
         let breakable = elem.breakable.get(styles);
         let fr = match elem.height.get(styles) {
             Sizing::Fr(fr) => Some(fr),
@@ -251,10 +262,21 @@ impl<'a> Collector<'a, '_, '_> {
 
         self.output.push(spacing(elem.above.get(styles)));
 
-        if !breakable || fr.is_some() {
+        if elem.span.get(styles) == BlockSpan::All {
+            self.output.push(Child::Span(self.boxed(SingleChild {
+                align,
+                keep,
+                alone,
+                fr,
+                elem,
+                styles,
+                locator,
+                cell: CachedCell::new(),
+            })));
+        } else if !breakable || fr.is_some() {
             self.output.push(Child::Single(self.boxed(SingleChild {
                 align,
-                sticky,
+                keep,
                 alone,
                 fr,
                 elem,
@@ -265,7 +287,7 @@ impl<'a> Collector<'a, '_, '_> {
         } else {
             self.output.push(Child::Multi(self.boxed(MultiChild {
                 align,
-                sticky,
+                keep,
                 alone,
                 elem,
                 styles,
@@ -288,9 +310,17 @@ impl<'a> Collector<'a, '_, '_> {
         let align_x = alignment.map_or(FixedAlignment::Center, |align| {
             align.x().unwrap_or_default().resolve(styles)
         });
-        let align_y = alignment.map(|align| align.y().map(|y| y.resolve(styles)));
+        let mut align_y = alignment.map(|align| align.y().map(|y| y.resolve(styles)));
         let scope = elem.scope.get(styles);
         let float = elem.float.get(styles);
+        let placement =
+            if float { elem.placement.get(styles) } else { FloatPlacement::Edge };
+
+        // Floats that stay in the flow or go onto a float page are always
+        // positioned from the top.
+        if placement != FloatPlacement::Edge {
+            align_y = Smart::Custom(Some(FixedAlignment::Start));
+        }
 
         match (float, align_y) {
             (true, Smart::Custom(None | Some(FixedAlignment::Center))) => bail!(
@@ -313,6 +343,14 @@ impl<'a> Collector<'a, '_, '_> {
             );
         }
 
+        if placement == FloatPlacement::Here && scope == PlacementScope::Parent {
+            bail!(
+                elem.span(),
+                "parent-scoped placement is not available for `here` placement";
+                hint: "try using `placement: \"edge\"` or `placement: \"page\"` instead"
+            );
+        }
+
         let locator = self.locator.next(&elem.span());
         let clearance = elem.clearance.resolve(styles);
         let delta = Axes::new(elem.dx.get(styles), elem.dy.get(styles)).resolve(styles);
@@ -321,6 +359,7 @@ impl<'a> Collector<'a, '_, '_> {
             align_y,
             scope,
             float,
+            placement,
             clearance,
             delta,
             elem,
@@ -357,6 +396,8 @@ pub enum Child<'a> {
     Single(BumpBox<'a, SingleChild<'a>>),
     /// A breakable block.
     Multi(BumpBox<'a, MultiChild<'a>>),
+    /// An unbreakable block that spans all columns.
+    Span(BumpBox<'a, SingleChild<'a>>),
     /// An absolutely or floatingly placed element.
     Placed(BumpBox<'a, PlacedChild<'a>>),
     /// A place flush.
@@ -377,7 +418,7 @@ pub struct LineChild {
 #[derive(Debug)]
 pub struct SingleChild<'a> {
     pub align: Axes<FixedAlignment>,
-    pub sticky: bool,
+    pub keep: usize,
     pub alone: bool,
     pub fr: Option<Fr>,
     elem: &'a Packed<BlockElem>,
@@ -443,7 +484,7 @@ fn layout_single_impl(
 #[derive(Debug)]
 pub struct MultiChild<'a> {
     pub align: Axes<FixedAlignment>,
-    pub sticky: bool,
+    pub keep: usize,
     alone: bool,
     elem: &'a Packed<BlockElem>,
     styles: StyleChain<'a>,
@@ -622,6 +663,7 @@ pub struct PlacedChild<'a> {
     pub align_y: Smart<Option<FixedAlignment>>,
     pub scope: PlacementScope,
     pub float: bool,
+    pub placement: FloatPlacement,
     pub clearance: Abs,
     pub delta: Axes<Rel<Abs>>,
     elem: &'a Packed<PlaceElem>,
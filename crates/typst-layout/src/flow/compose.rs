@@ -8,8 +8,9 @@ use typst_library::introspection::{
     SplitLocator, Tag,
 };
 use typst_library::layout::{
-    Abs, Axes, Dir, FixedAlignment, Fragment, Frame, FrameItem, FrameParent, Inherit,
-    OuterHAlignment, PlacementScope, Point, Region, Regions, Rel, Size,
+    Abs, Axes, Dir, FixedAlignment, FloatPlacement, Fragment, Frame, FrameItem,
+    FrameParent, Inherit, OuterHAlignment, PlacementScope, Point, Region, Regions, Rel,
+    Size,
 };
 use typst_library::model::{
     FootnoteElem, FootnoteEntry, LineNumberingScope, Numbering, ParLineMarker,
@@ -18,9 +19,13 @@ use typst_syntax::Span;
 use typst_utils::{NonZeroExt, Numeric};
 
 use super::{
-    Config, FlowMode, FlowResult, LineNumberConfig, PlacedChild, Stop, Work, distribute,
+    Child, Config, FlowMode, FlowResult, LineNumberConfig, PlacedChild, Stop, Work,
+    distribute,
 };
 
+/// The maximum number of layout attempts when balancing columns.
+const MAX_BALANCE_ATTEMPTS: usize = 10;
+
 /// Composes the contents of a single page/region. A region can have multiple
 /// columns/subregions.
 ///
@@ -111,17 +116,200 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
             return self.column(locator, regions);
         }
 
+        // Blocks spanning all columns split the region into multiple parts.
+        if self.config.columns.spans {
+            return self.spanned_columns(locator, regions);
+        }
+
+        if self.config.columns.balance {
+            return self.balanced_columns(locator, regions);
+        }
+
+        self.columns(locator, regions, None)
+    }
+
+    /// Lay out columns that may be interrupted by blocks spanning all of them.
+    ///
+    /// The region is filled from top to bottom with alternating column groups
+    /// and spanning blocks. The columns before a spanning block are always
+    /// balanced so that the block directly follows the content before it. The
+    /// columns after the last spanning block in the region are balanced only
+    /// if configured.
+    fn spanned_columns(
+        &mut self,
+        locator: Locator,
+        regions: Regions,
+    ) -> FlowResult<Frame> {
+        let mut locator = locator.split();
+        let mut output = Frame::hard(Size::new(
+            regions.size.x,
+            if regions.expand.y { regions.size.y } else { Abs::zero() },
+        ));
+        let mut offset = Abs::zero();
+
+        loop {
+            // Restrict the work to the children before the next spanning
+            // block. We can then find out whether they fit through
+            // `Work::done`.
+            let children = self.work.children;
+            let end = children
+                .iter()
+                .position(|child| matches!(child, Child::Span(_)))
+                .unwrap_or(children.len());
+            let last = end == children.len();
+
+            // Lay out the columns before the spanning block, if there is
+            // anything to lay out.
+            if end > 0 || self.work.spill.is_some() || last {
+                self.work.children = &children[..end];
+
+                let mut pod = regions;
+                pod.size.y -= offset;
+                pod.expand.y &= last;
+
+                let result = if !last || self.config.columns.balance {
+                    self.balanced_columns(locator.next(&()), pod)
+                } else {
+                    self.columns(locator.next(&()), pod, None)
+                };
+
+                let done = self.work.done();
+                let rest = self.work.children.len();
+                self.work.children = &children[end - rest..];
+
+                let frame = result?;
+                let height = frame.height();
+                output.push_frame(Point::with_y(offset), frame);
+                offset += height;
+                if !regions.expand.y {
+                    output.size_mut().y.set_max(offset);
+                }
+
+                // If the columns didn't fit, the rest moves to the next region.
+                if last || !done {
+                    break;
+                }
+            }
+
+            // Lay out the spanning block across the full width.
+            let Some(Child::Span(single)) = self.work.head() else { break };
+            let (above, below) = span_spacing(children, end, regions.base().y);
+            let frame = single.layout(
+                self.engine,
+                Region::new(regions.base(), Axes::new(regions.expand.x, false)),
+            )?;
+
+            // If the block doesn't fit below other content, it moves to the
+            // next region. At the top of the region, it is placed regardless
+            // as moving it wouldn't help.
+            let above = if offset.is_zero() { Abs::zero() } else { above };
+            let need = above + frame.height();
+            if !offset.is_zero() && !(regions.size.y - offset).fits(need) {
+                break;
+            }
+
+            offset += above;
+            let height = frame.height();
+            let x = single.align.x.position(regions.size.x - frame.width());
+            output.push_frame(Point::new(x, offset), frame);
+            offset += height;
+            if !regions.expand.y {
+                output.size_mut().y.set_max(offset);
+            }
+            offset += below;
+
+            self.work.advance();
+            if self.work.done() {
+                break;
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Lay out the columns of a container/page such that they end at roughly
+    /// equal heights.
+    ///
+    /// Balancing only happens in the final region of the flow, that is, if
+    /// all remaining work fits into the columns of this region. In that case,
+    /// we search for the smallest column height at which this is still true.
+    /// Since each attempt is a full layout of the region's columns, floats,
+    /// footnotes, sticky and unbreakable blocks are accounted for just like
+    /// in normal column layout. The same holds for explicit column breaks:
+    /// The columns are balanced as a whole, not separately before and after
+    /// each break.
+    fn balanced_columns(
+        &mut self,
+        locator: Locator,
+        regions: Regions,
+    ) -> FlowResult<Frame> {
+        let checkpoint = self.work.clone();
+        let output = self.columns(locator.relayout(), regions, None)?;
+        if !self.work.done() {
+            return Ok(output);
+        }
+
+        // The unbalanced columns are an upper bound for the balanced height.
+        let mut hi = output.height();
+        let mut lo = Abs::zero();
+        let mut best = (output, self.work.clone());
+
+        // Bisect the column height. The tolerance is well below a typical line
+        // height, so the result doesn't visibly differ from the optimum. As
+        // each attempt is a full layout, their number is also capped. This
+        // still reaches the tolerance for columns of up to 1024pt and
+        // otherwise ends with the best height found so far.
+        for _ in 0..MAX_BALANCE_ATTEMPTS {
+            if hi - lo <= Abs::pt(1.0) {
+                break;
+            }
+
+            let mid = (lo + hi) / 2.0;
+            *self.work = checkpoint.clone();
+            let frame = self.columns(locator.relayout(), regions, Some(mid))?;
+            if self.work.done() {
+                hi = mid;
+                best = (frame, self.work.clone());
+            } else {
+                lo = mid;
+            }
+        }
+
+        let (output, work) = best;
+        *self.work = work;
+        Ok(output)
+    }
+
+    /// Lay out the columns of a container/page and stitch them together.
+    ///
+    /// If `balanced` is set, the columns in this region are limited to the
+    /// given height.
+    fn columns(
+        &mut self,
+        locator: Locator,
+        regions: Regions,
+        balanced: Option<Abs>,
+    ) -> FlowResult<Frame> {
         // Create a backlog for multi-column layout.
         let column_height = regions.size.y;
-        let backlog: Vec<_> = std::iter::once(&column_height)
-            .chain(regions.backlog)
-            .flat_map(|&h| std::iter::repeat_n(h, self.config.columns.count))
-            .skip(1)
-            .collect();
+        let backlog: Vec<_> = match balanced {
+            // When trying out a balanced height, we follow the columns with
+            // one full-height region. This way, content that doesn't fit
+            // spills into it instead of being forced into the last column,
+            // which tells us that the height was too small.
+            Some(height) => std::iter::repeat_n(height, self.config.columns.count - 1)
+                .chain(std::iter::once(column_height))
+                .collect(),
+            None => std::iter::once(&column_height)
+                .chain(regions.backlog)
+                .flat_map(|&h| std::iter::repeat_n(h, self.config.columns.count))
+                .skip(1)
+                .collect(),
+        };
 
         // Subregions for column layout.
         let mut inner = Regions {
-            size: Size::new(self.config.columns.width, column_height),
+            size: Size::new(self.config.columns.width, balanced.unwrap_or(column_height)),
             backlog: &backlog,
             expand: Axes::new(true, regions.expand.y),
             ..regions
@@ -234,6 +422,11 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
             self.float(placed, &regions, false, false)?;
         }
 
+        // A region dedicated to floats doesn't receive any flow content.
+        if self.column_insertions.dedicated || self.page_insertions.dedicated {
+            return Ok(Frame::soft(regions.expand.select(regions.size, Size::zero())));
+        }
+
         distribute(self, regions)
     }
 
@@ -268,8 +461,27 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
         }
 
         // If there is already a queued float, queue this one as well. We
-        // don't want to disrupt the order.
-        if !self.work.floats.is_empty() {
+        // don't want to disrupt the order unless reordering is allowed.
+        if !self.work.floats.is_empty() && !self.config.floats.reorder {
+            self.work.floats.push(placed);
+            return Ok(());
+        }
+
+        // Check whether the insertion area can take the float at all. A float
+        // page only hosts page floats and is only started in an otherwise
+        // empty region. Beyond that, the number of floats may be limited.
+        let dedicated = placed.placement == FloatPlacement::Page;
+        let area = match placed.scope {
+            PlacementScope::Column => &self.column_insertions,
+            PlacementScope::Parent => &self.page_insertions,
+        };
+        let count = area.top_floats.len() + area.bottom_floats.len();
+        let occupied = clearance
+            || (placed.scope == PlacementScope::Parent && self.column > 0)
+            || (count > 0 && !area.dedicated);
+        let rejected = (if dedicated { occupied } else { area.dedicated })
+            || self.config.floats.limit.is_some_and(|limit| count >= limit);
+        if rejected && regions.may_progress() {
             self.work.floats.push(placed);
             return Ok(());
         }
@@ -331,6 +543,7 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
         // Put the float there.
         area.push_float(placed, frame, align_y);
         area.skips.push(loc);
+        area.dedicated |= dedicated;
 
         // Trigger relayout.
         Err(Stop::Relayout(placed.scope))
@@ -564,7 +777,7 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
 
     /// Checks whether an insertion was already processed and doesn't need to be
     /// handled again.
-    fn skipped(&self, loc: Location) -> bool {
+    pub fn skipped(&self, loc: Location) -> bool {
         self.work.skips.contains(&loc)
             || self.page_insertions.skips.contains(&loc)
             || self.column_insertions.skips.contains(&loc)
@@ -576,6 +789,34 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
     }
 }
 
+/// Determines the spacing above and below the spanning block at index `i`.
+///
+/// The spacing is given by the weak spacing surrounding the block. It would
+/// otherwise be collapsed at the boundaries of the adjacent columns.
+fn span_spacing(children: &[Child], i: usize, base: Abs) -> (Abs, Abs) {
+    (
+        weak_spacing(children[..i].iter().rev(), base),
+        weak_spacing(children[i + 1..].iter(), base),
+    )
+}
+
+/// The largest weak spacing at the start of the given children.
+fn weak_spacing<'a, 'c>(children: impl Iterator<Item = &'c Child<'a>>, base: Abs) -> Abs
+where
+    'a: 'c,
+{
+    children
+        .map_while(|child| match *child {
+            Child::Rel(amount, weakness) => {
+                Some((weakness > 0).then(|| amount.relative_to(base)))
+            }
+            Child::Tag(_) => Some(None),
+            _ => None,
+        })
+        .flatten()
+        .fold(Abs::zero(), Abs::max)
+}
+
 /// Lay out the footnote separator, typically a line.
 fn layout_footnote_separator(
     engine: &mut Engine,
@@ -630,6 +871,8 @@ struct Insertions<'a, 'b> {
     bottom_size: Abs,
     width: Abs,
     skips: Vec<Location>,
+    /// Whether the area is a float page that takes no flow content.
+    dedicated: bool,
 }
 
 impl<'a, 'b> Insertions<'a, 'b> {
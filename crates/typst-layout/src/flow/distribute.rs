@@ -1,6 +1,7 @@
 use typst_library::introspection::Tag;
 use typst_library::layout::{
-    Abs, Axes, FixedAlignment, Fr, Frame, FrameItem, Point, Region, Regions, Rel, Size,
+    Abs, Axes, FixedAlignment, FloatPlacement, Fr, Frame, FrameItem, Point, Region,
+    Regions, Rel, Size,
 };
 use typst_utils::Numeric;
 
@@ -18,6 +19,7 @@ pub fn distribute(composer: &mut Composer, regions: Regions) -> FlowResult<Frame
         items: vec![],
         sticky: None,
         stickable: None,
+        keep: 0,
     };
     let init = distributor.snapshot();
     let forced = match distributor.run() {
@@ -62,6 +64,11 @@ struct Distributor<'a, 'b, 'x, 'y, 'z> {
     /// blocks are supposed to always be in the same page as the subsequent
     /// frame, but that is impossible in that case, which is thus pathological.
     stickable: Option<bool>,
+    /// How many more non-sticky lines or blocks must follow the current group
+    /// of sticky blocks before its snapshot can be forgotten. This is the
+    /// `keep` amount of the last sticky block and counts down with every
+    /// non-empty frame.
+    keep: usize,
 }
 
 /// A snapshot of the distribution state.
@@ -133,7 +140,9 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
             Child::Rel(amount, weakness) => self.rel(*amount, *weakness),
             Child::Fr(fr) => self.fr(*fr),
             Child::Line(line) => self.line(line)?,
-            Child::Single(single) => self.single(single)?,
+            // Spanning blocks only reach distribution if there is just one
+            // column, so they behave like normal blocks here.
+            Child::Single(single) | Child::Span(single) => self.single(single)?,
             Child::Multi(multi) => self.multi(multi)?,
             Child::Placed(placed) => self.placed(placed)?,
             Child::Flush => self.flush()?,
@@ -244,7 +253,7 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
             return Err(Stop::Finish(false));
         }
 
-        self.frame(line.frame.clone(), line.align, false, false)
+        self.frame(line.frame.clone(), line.align, 0, false)
     }
 
     /// Processes an unbreakable block.
@@ -270,7 +279,7 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
             return Err(Stop::Finish(false));
         }
 
-        self.frame(frame, single.align, single.sticky, false)
+        self.frame(frame, single.align, single.keep, false)
     }
 
     /// Processes a breakable block.
@@ -293,7 +302,7 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
             return Err(Stop::Finish(false));
         }
 
-        self.frame(frame, multi.align, multi.sticky, true)?;
+        self.frame(frame, multi.align, multi.keep, true)?;
 
         // If the block didn't fully fit into the current region, save it into
         // the `spill` and finish the region.
@@ -317,7 +326,7 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
         // Lay out the spilled remains.
         let align = spill.align();
         let (frame, spill) = spill.layout(self.composer.engine, self.regions)?;
-        self.frame(frame, align, false, true)?;
+        self.frame(frame, align, 0, true)?;
 
         // If there's still more, save it into the `spill` and finish the
         // region.
@@ -334,10 +343,10 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
         &mut self,
         frame: Frame,
         align: Axes<FixedAlignment>,
-        sticky: bool,
+        keep: usize,
         breakable: bool,
     ) -> FlowResult<()> {
-        if sticky {
+        if keep > 0 {
             // If the frame is sticky and we haven't remembered a preceding
             // sticky element, make a checkpoint which we can restore should we
             // end on this sticky element.
@@ -360,12 +369,21 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
             {
                 self.sticky = Some(self.snapshot());
             }
+
+            // The last sticky block of a group determines how much content
+            // must follow the group.
+            self.keep = keep;
+        } else if !frame.is_empty() && self.keep > 1 {
+            // If enough content is not yet attached to the sticky blocks, keep
+            // the snapshot around.
+            self.keep -= 1;
         } else if !frame.is_empty() {
             // If the frame isn't sticky, we can forget a previous snapshot. We
             // interrupt a group of sticky blocks, if there was one, so we reset
             // the saved stickable check for the next group of sticky blocks.
             self.sticky = None;
             self.stickable = None;
+            self.keep = 0;
         }
 
         // Handle footnotes.
@@ -386,7 +404,9 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
 
     /// Processes an absolutely or floatingly placed child.
     fn placed(&mut self, placed: &'b PlacedChild<'a>) -> FlowResult<()> {
-        if placed.float {
+        if placed.float && placed.placement == FloatPlacement::Here {
+            self.here(placed)?;
+        } else if placed.float {
             // If the element is floatingly placed, let the composer handle it.
             // It might require relayout because the area available for
             // distribution shrinks. We make the spacing occupied by weak
@@ -411,6 +431,41 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
         Ok(())
     }
 
+    /// Processes a float that should stay in the flow if it fits.
+    fn here(&mut self, placed: &'b PlacedChild<'a>) -> FlowResult<()> {
+        // If the float was already placed as an insertion, skip it.
+        if self.composer.skipped(placed.location()) {
+            return Ok(());
+        }
+
+        // If earlier floats are still pending, don't overtake them unless
+        // reordering is allowed. The float is then placed at the top of a
+        // later region, like a queued float with top alignment.
+        if !self.composer.work.floats.is_empty() && !self.composer.config.floats.reorder {
+            self.composer.work.floats.push(placed);
+            return Ok(());
+        }
+
+        // Lay out the float and queue it if it doesn't fit and a followup
+        // region may improve things.
+        let frame = placed.layout(self.composer.engine, self.regions.base())?;
+        let extra = (placed.clearance - self.weak_spacing()).max(Abs::zero());
+        let need = frame.height() + extra;
+        if !self.regions.size.y.fits(need) && self.regions.may_progress() {
+            self.composer.work.floats.push(placed);
+            return Ok(());
+        }
+
+        // Otherwise, add it in-flow surrounded by weak spacing that collapses
+        // with the spacing of neighbouring blocks.
+        let clearance = Rel::from(placed.clearance);
+        let align = Axes::new(placed.align_x, FixedAlignment::Start);
+        self.rel(clearance, 4);
+        self.frame(frame, align, 0, false)?;
+        self.rel(clearance, 4);
+        Ok(())
+    }
+
     /// Processes a float flush.
     fn flush(&mut self) -> FlowResult<()> {
         // If there are still pending floats, finish the region instead of
@@ -22,8 +22,8 @@ use typst_library::introspection::{
     Introspector, Location, Locator, LocatorLink, SplitLocator, Tag,
 };
 use typst_library::layout::{
-    Abs, ColumnsElem, Dir, Em, Fragment, Frame, PageElem, PlacementScope, Region,
-    Regions, Rel, Size,
+    Abs, ColumnsElem, Dir, Em, Fragment, Frame, PageElem, PlaceElem, PlacementScope,
+    Region, Regions, Rel, Size,
 };
 use typst_library::model::{FootnoteElem, FootnoteEntry, LineNumberingScope, ParLine};
 use typst_library::pdf::ArtifactKind;
@@ -73,6 +73,7 @@ pub fn layout_fragment(
         regions,
         NonZeroUsize::ONE,
         Rel::zero(),
+        false,
     )
 }
 
@@ -101,6 +102,7 @@ pub fn layout_columns(
         regions,
         elem.count.get(styles),
         elem.gutter.resolve(styles),
+        elem.balance.get(styles),
     )
 }
 
@@ -120,6 +122,7 @@ fn layout_fragment_impl(
     regions: Regions,
     columns: NonZeroUsize,
     column_gutter: Rel<Abs>,
+    balance: bool,
 ) -> SourceResult<Fragment> {
     if !regions.size.x.is_finite() && regions.expand.x {
         bail!(content.span(), "cannot expand into infinite width");
@@ -160,6 +163,7 @@ fn layout_fragment_impl(
         regions,
         columns,
         column_gutter,
+        balance,
         kind.into(),
     )
 }
@@ -195,10 +199,12 @@ pub fn layout_flow<'a>(
     mut regions: Regions,
     columns: NonZeroUsize,
     column_gutter: Rel<Abs>,
+    balance: bool,
     mode: FlowMode,
 ) -> SourceResult<Fragment> {
     // Prepare configuration that is shared across the whole flow.
-    let config = configuration(shared, regions, columns, column_gutter, mode);
+    let mut config =
+        configuration(shared, regions, columns, column_gutter, balance, mode);
 
     // Collect the elements into pre-processed children. These are much easier
     // to handle than the raw elements.
@@ -213,6 +219,9 @@ pub fn layout_flow<'a>(
         mode,
     )?;
 
+    // Blocks spanning all columns need special handling during composition.
+    config.columns.spans = children.iter().any(|child| matches!(child, Child::Span(_)));
+
     let mut work = Work::new(&children);
     let mut finished = vec![];
 
@@ -239,6 +248,7 @@ fn configuration<'x>(
     regions: Regions,
     columns: NonZeroUsize,
     column_gutter: Rel<Abs>,
+    balance: bool,
     mode: FlowMode,
 ) -> Config<'x> {
     Config {
@@ -253,7 +263,11 @@ fn configuration<'x>(
             let gutter = column_gutter.relative_to(regions.base().x);
             let width = (regions.size.x - gutter * (count - 1) as f64) / count as f64;
             let dir = shared.resolve(TextElem::dir);
-            ColumnConfig { count, width, gutter, dir }
+            ColumnConfig { count, width, gutter, dir, balance, spans: false }
+        },
+        floats: FloatConfig {
+            limit: shared.get(PlaceElem::float_limit),
+            reorder: shared.get(PlaceElem::reorder),
         },
         footnote: FootnoteConfig {
             separator: shared
@@ -361,12 +375,22 @@ struct Config<'x> {
     shared: StyleChain<'x>,
     /// Settings for columns.
     columns: ColumnConfig,
+    /// Settings for floats.
+    floats: FloatConfig,
     /// Settings for footnotes.
     footnote: FootnoteConfig,
     /// Settings for line numbers.
     line_numbers: Option<LineNumberConfig>,
 }
 
+/// Configuration of floats.
+struct FloatConfig {
+    /// The maximum number of floats per insertion area.
+    limit: Option<usize>,
+    /// Whether later floats may overtake deferred ones.
+    reorder: bool,
+}
+
 /// Configuration of footnotes.
 struct FootnoteConfig {
     /// The separator between flow content and footnotes. Typically a line.
@@ -390,6 +414,10 @@ struct ColumnConfig {
     /// The horizontal direction in which columns progress. Defined by
     /// `text.dir`.
     dir: Dir,
+    /// Whether the columns of the final region should be balanced.
+    balance: bool,
+    /// Whether the flow contains blocks that span all columns.
+    spans: bool,
 }
 
 /// Configuration of line numbers.
@@ -1,6 +1,8 @@
-use typst_library::foundations::StyleChain;
+use typst_library::foundations::{Packed, StyleChain, Styles};
+use typst_library::introspection::Location;
 use typst_library::layout::{Abs, Fragment, Frame, FrameItem, HideElem, Point, Sides};
 use typst_library::model::{Destination, LinkElem, ParElem};
+use typst_library::pdf::{FormFieldElem, LayerElem};
 
 /// Frame-level modifications resulting from styles that do not impose any
 /// layout structure.
@@ -17,10 +19,16 @@ use typst_library::model::{Destination, LinkElem, ParElem};
 /// Currently existing frame modifiers are:
 /// - `HideElem::hidden`
 /// - `LinkElem::dests`
+/// - `LayerElem::current`
+/// - `FormFieldElem::widget`
 #[derive(Debug, Clone)]
 pub struct FrameModifiers {
     /// A destination to link to.
     dest: Option<Destination>,
+    /// A PDF layer to put the contents into.
+    layer: Option<Packed<LayerElem>>,
+    /// A PDF form field whose widget the contents are.
+    form_field: Option<Location>,
     /// Whether the contents of the frame should be hidden.
     hidden: bool,
 }
@@ -30,6 +38,8 @@ impl FrameModifiers {
     pub fn get_in(styles: StyleChain) -> Self {
         Self {
             dest: styles.get_cloned(LinkElem::current),
+            layer: styles.get_cloned(LayerElem::current),
+            form_field: styles.get(FormFieldElem::widget),
             hidden: styles.get(HideElem::hidden),
         }
     }
@@ -94,6 +104,10 @@ fn modify_frame(
     modifiers: &FrameModifiers,
     link_box_outset: Option<Sides<Abs>>,
 ) {
+    if let Some(location) = modifiers.form_field {
+        frame.set_form_field(location);
+    }
+
     if let Some(dest) = &modifiers.dest {
         let mut pos = Point::zero();
         let mut size = frame.size();
@@ -105,6 +119,10 @@ fn modify_frame(
         frame.push(pos, FrameItem::Link(dest.clone(), size));
     }
 
+    if let Some(layer) = &modifiers.layer {
+        frame.set_layer(layer.clone());
+    }
+
     if modifiers.hidden {
         frame.hide();
     }
@@ -123,16 +141,20 @@ where
 {
     let modifiers = FrameModifiers::get_in(styles);
 
-    // Disable the current link internally since it's already applied at this
-    // level of layout. This means we don't generate redundant nested links,
-    // which may bloat the output considerably.
-    let reset;
-    let outer = styles;
-    let mut styles = styles;
+    // Disable the current link, layer, and form field internally since they're
+    // already applied at this level of layout. This means we don't generate
+    // redundant nested links and layers, which may bloat the output
+    // considerably, and that only the outermost frame becomes a widget.
+    let mut reset = Styles::new();
     if modifiers.dest.is_some() {
-        reset = LinkElem::current.set(None).wrap();
-        styles = outer.chain(&reset);
+        reset.set(LinkElem::current, None);
+    }
+    if modifiers.layer.is_some() {
+        reset.set(LayerElem::current, None);
+    }
+    if modifiers.form_field.is_some() {
+        reset.set(FormFieldElem::widget, None);
     }
 
-    layout(styles).modified(&modifiers)
+    layout(styles.chain(&reset)).modified(&modifiers)
 }
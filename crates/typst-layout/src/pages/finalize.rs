@@ -1,9 +1,13 @@
 use typst_library::diag::SourceResult;
 use typst_library::engine::Engine;
+use typst_library::foundations::Smart;
 use typst_library::introspection::{ManualPageCounter, Tag};
-use typst_library::layout::{Frame, FrameItem, Page, Point};
+use typst_library::layout::{Abs, Binding, Frame, FrameItem, Page, Point, Size};
+use typst_library::visualize::{Color, Curve, FixedStroke, Geometry};
+use typst_syntax::Span;
 
 use super::LayoutedPage;
+use super::notes::MarginNote;
 
 /// Piece together the inner page frame and the marginals. We can only do this
 /// at the very end because inside/outside margins require knowledge of the
@@ -24,12 +28,18 @@ pub fn finalize(
         fill,
         numbering,
         supplement,
+        bleed,
+        marks,
+        notes,
+        transition,
+        duration,
     }: LayoutedPage,
 ) -> SourceResult<Page> {
     // If two sided, left becomes inside and right becomes outside.
     // Thus, for left-bound pages, we want to swap on even pages and
     // for right-bound pages, we want to swap on odd pages.
-    if two_sided && binding.swap(counter.physical()) {
+    let swap = two_sided && binding.swap(counter.physical());
+    if swap {
         std::mem::swap(&mut margin.left, &mut margin.right);
     }
 
@@ -45,15 +55,27 @@ pub fn finalize(
     // important as it affects the relative ordering of introspectable elements
     // and thus how counters resolve.
     if let Some(background) = background {
-        frame.push_frame(Point::zero(), background);
+        frame.push_frame(Point::splat(-bleed), background);
     }
     if let Some(header) = header {
         frame.push_frame(Point::with_x(margin.left), header);
     }
 
     // Add the inner contents.
+    let inner_width = inner.width();
     frame.push_frame(Point::new(margin.left, margin.top), inner);
 
+    // Add the margin notes to the outer margin.
+    let right = if two_sided { !swap } else { binding == Binding::Left };
+    for MarginNote { y, gap, frame: note } in notes {
+        let x = if right {
+            margin.left + inner_width + gap
+        } else {
+            margin.left - gap - note.width()
+        };
+        frame.push_frame(Point::new(x, margin.top + y), note);
+    }
+
     // Add the "after" marginals.
     if let Some(footer) = footer {
         let y = frame.height() - footer.height();
@@ -63,6 +85,31 @@ pub fn finalize(
         frame.push_frame(Point::zero(), foreground);
     }
 
+    // Extend the page by the bleed and the area for printer marks.
+    let slug = if marks { mark_gap() + mark_length() } else { Abs::zero() };
+    let mut fill = fill;
+    if bleed > Abs::zero() || marks {
+        let trim = frame.size();
+        let outer = slug + bleed;
+        let mut extended = Frame::hard(trim + Size::splat(2.0 * outer));
+
+        // The fill only extends into the bleed, the marks are drawn onto the
+        // blank sheet.
+        if marks && let Smart::Custom(Some(paint)) = &fill {
+            let area =
+                Geometry::Rect(trim + Size::splat(2.0 * bleed)).filled(paint.clone());
+            extended.push(Point::splat(slug), FrameItem::Shape(area, Span::detached()));
+            fill = Smart::Auto;
+        }
+
+        extended.push_frame(Point::splat(outer), frame);
+        if marks {
+            layout_marks(&mut extended, trim, bleed);
+        }
+
+        frame = extended;
+    }
+
     // Apply counter updates from within the page to the manual page counter.
     counter.visit(engine, &frame)?;
 
@@ -70,5 +117,107 @@ pub fn finalize(
     let number = counter.logical();
     counter.step();
 
-    Ok(Page { frame, fill, numbering, supplement, number })
+    Ok(Page {
+        frame,
+        fill,
+        numbering,
+        supplement,
+        number,
+        bleed,
+        slug,
+        transition,
+        duration,
+    })
+}
+
+/// The distance between the bleed and the printer marks.
+fn mark_gap() -> Abs {
+    Abs::pt(3.0)
+}
+
+/// The length of crop and registration marks.
+fn mark_length() -> Abs {
+    Abs::pt(15.0)
+}
+
+/// Draws crop marks at the corners of the trimmed page and registration marks
+/// centered on each of its sides into the area outside of the bleed.
+fn layout_marks(frame: &mut Frame, trim: Size, bleed: Abs) {
+    let stroke = FixedStroke::from_pair(Color::BLACK, Abs::pt(0.25));
+    let length = mark_length();
+    let size = frame.size();
+    let outer = (size.x - trim.x) / 2.0;
+    let mut push = |pos: Point, geometry: Geometry| {
+        let shape = geometry.stroked(stroke.clone());
+        frame.push(pos, FrameItem::Shape(shape, Span::detached()));
+    };
+
+    // Crop marks extend the edges of the trimmed page outwards.
+    let distance = bleed + mark_gap();
+    for (sx, x) in [(-1.0, outer), (1.0, outer + trim.x)] {
+        for (sy, y) in [(-1.0, outer), (1.0, outer + trim.y)] {
+            push(
+                Point::new(x + sx * distance, y),
+                Geometry::Line(Point::with_x(sx * length)),
+            );
+            push(
+                Point::new(x, y + sy * distance),
+                Geometry::Line(Point::with_y(sy * length)),
+            );
+        }
+    }
+
+    // Registration marks are circles with a crosshair.
+    let half = length / 2.0;
+    let radius = length / 4.0;
+    let centers = [
+        Point::new(size.x / 2.0, half),
+        Point::new(size.x / 2.0, size.y - half),
+        Point::new(half, size.y / 2.0),
+        Point::new(size.x - half, size.y / 2.0),
+    ];
+    for center in centers {
+        push(
+            center - Point::splat(radius),
+            Geometry::Curve(Curve::ellipse(Size::splat(2.0 * radius))),
+        );
+        push(center - Point::with_x(half), Geometry::Line(Point::with_x(length)));
+        push(center - Point::with_y(half), Geometry::Line(Point::with_y(length)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marks_stay_outside_of_bleed() {
+        let trim = Size::new(Abs::pt(100.0), Abs::pt(50.0));
+        let bleed = Abs::pt(5.0);
+        let slug = mark_gap() + mark_length();
+        let size = trim + Size::splat(2.0 * (slug + bleed));
+        let mut frame = Frame::hard(size);
+        layout_marks(&mut frame, trim, bleed);
+
+        // Eight crop marks and four registration marks with three parts each.
+        assert_eq!(frame.items().count(), 20);
+
+        for (pos, item) in frame.items() {
+            let FrameItem::Shape(shape, _) = item else {
+                panic!("expected a shape, found {item:?}");
+            };
+            let bbox = shape.geometry.bbox();
+            let (min, max) = (*pos + bbox.min, *pos + bbox.max);
+
+            // The marks are on the sheet, but don't reach into the bleed.
+            assert!(min.x >= Abs::zero() && min.y >= Abs::zero());
+            assert!(max.x <= size.x && max.y <= size.y);
+            assert!(
+                max.x <= slug
+                    || min.x >= size.x - slug
+                    || max.y <= slug
+                    || min.y >= size.y - slug
+            );
+        }
+    }
 }
@@ -2,6 +2,7 @@
 
 mod collect;
 mod finalize;
+mod notes;
 mod run;
 
 use std::num::NonZeroUsize;
@@ -0,0 +1,117 @@
+use typst_library::diag::SourceResult;
+use typst_library::engine::Engine;
+use typst_library::foundations::{Packed, Resolve, StyleChain};
+use typst_library::introspection::{SplitLocator, Tag};
+use typst_library::layout::{Abs, Axes, Frame, FrameItem, Region, Size};
+use typst_library::model::MarginNoteElem;
+
+/// A margin note that is laid out, but not yet placed on a specific side of
+/// the page.
+#[derive(Clone)]
+pub struct MarginNote {
+    /// The vertical position of the note's top edge, relative to the inner
+    /// page frame.
+    pub y: Abs,
+    /// The horizontal distance between the note and the text area.
+    pub gap: Abs,
+    /// The note's contents.
+    pub frame: Frame,
+}
+
+/// Lays out the margin notes anchored in the inner page frame and arranges
+/// them so that they don't overlap.
+///
+/// The `outer` margin is the width of the margin the notes are placed in.
+pub fn layout_margin_notes(
+    engine: &mut Engine,
+    locator: &mut SplitLocator,
+    styles: StyleChain,
+    inner: &Frame,
+    outer: Abs,
+) -> SourceResult<Vec<MarginNote>> {
+    let mut elems = vec![];
+    find_notes(&mut elems, inner, Abs::zero());
+
+    let mut entries = Vec::with_capacity(elems.len());
+    for (anchor, elem) in elems {
+        let gap = elem.gap.resolve(styles);
+        let width = (outer - 2.0 * gap).max(Abs::zero());
+        let frame = crate::layout_frame(
+            engine,
+            &elem.body,
+            locator.next(&elem.span()),
+            styles,
+            Region::new(Size::new(width, Abs::inf()), Axes::new(true, false)),
+        )?;
+
+        // Align the first baseline of the note with the anchor's baseline.
+        let y = anchor - first_baseline(&frame).unwrap_or_default();
+        entries.push((MarginNote { y, gap, frame }, elem.spacing.resolve(styles)));
+    }
+
+    // The notes might not be in visual order, e.g. with multiple columns.
+    entries.sort_by_key(|(note, _)| note.y);
+    let (mut notes, spacings): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+
+    stack(&mut notes, &spacings, inner.height());
+    Ok(notes)
+}
+
+/// Moves sorted notes apart so that they don't overlap and, if possible, stay
+/// within the given height.
+fn stack(notes: &mut [MarginNote], spacings: &[Abs], height: Abs) {
+    // Push notes down below their predecessors.
+    let push_down = |notes: &mut [MarginNote]| {
+        for i in 1..notes.len() {
+            let min = notes[i - 1].y + notes[i - 1].frame.height() + spacings[i];
+            notes[i].y.set_max(min);
+        }
+    };
+
+    push_down(notes);
+
+    // Pull notes that run off the bottom back up.
+    let mut limit = height;
+    for (note, &spacing) in notes.iter_mut().zip(spacings).rev() {
+        note.y.set_min(limit - note.frame.height());
+        limit = note.y - spacing;
+    }
+
+    // If the notes don't fit at all, rather let them run off the bottom than
+    // the top.
+    if let Some(first) = notes.first_mut()
+        && first.y < Abs::zero()
+    {
+        first.y = Abs::zero();
+        push_down(notes);
+    }
+}
+
+/// Collects the margin notes in a frame along with their vertical positions.
+fn find_notes(
+    output: &mut Vec<(Abs, Packed<MarginNoteElem>)>,
+    frame: &Frame,
+    y_offset: Abs,
+) {
+    for (pos, item) in frame.items() {
+        let y = y_offset + pos.y;
+        match item {
+            FrameItem::Group(group) => find_notes(output, &group.frame, y),
+            FrameItem::Tag(Tag::Start(elem, _)) => {
+                if let Some(elem) = elem.to_packed::<MarginNoteElem>() {
+                    output.push((y, elem.clone()));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The baseline of the first text in a frame.
+fn first_baseline(frame: &Frame) -> Option<Abs> {
+    frame.items().find_map(|(pos, item)| match item {
+        FrameItem::Group(group) => first_baseline(&group.frame).map(|y| pos.y + y),
+        FrameItem::Text(_) => Some(pos.y),
+        _ => None,
+    })
+}
@@ -3,15 +3,15 @@ use typst_library::World;
 use typst_library::diag::SourceResult;
 use typst_library::engine::{Engine, Route, Sink, Traced};
 use typst_library::foundations::{
-    Content, NativeElement, Resolve, Smart, StyleChain, Styles,
+    Content, Duration, NativeElement, Resolve, Smart, StyleChain, Styles,
 };
 use typst_library::introspection::{
     Counter, CounterDisplayElem, CounterKey, Introspector, Locator, LocatorLink,
 };
 use typst_library::layout::{
     Abs, AlignElem, Alignment, Axes, Binding, ColumnsElem, Dir, Frame, HAlignment,
-    Length, OuterVAlignment, PageElem, Paper, Region, Regions, Rel, Sides, Size,
-    VAlignment,
+    Length, OuterVAlignment, PageElem, PageTransition, Paper, Region, Regions, Rel,
+    Sides, Size, VAlignment,
 };
 use typst_library::model::Numbering;
 use typst_library::pdf::ArtifactKind;
@@ -20,6 +20,7 @@ use typst_library::text::{LocalName, TextElem};
 use typst_library::visualize::Paint;
 use typst_utils::Numeric;
 
+use super::notes::{MarginNote, layout_margin_notes};
 use crate::flow::{FlowMode, layout_flow};
 
 /// A mostly finished layout for one page. Needs only knowledge of its exact
@@ -38,6 +39,11 @@ pub struct LayoutedPage {
     pub fill: Smart<Option<Paint>>,
     pub numbering: Option<Numbering>,
     pub supplement: Content,
+    pub bleed: Abs,
+    pub marks: bool,
+    pub notes: Vec<MarginNote>,
+    pub transition: Option<PageTransition>,
+    pub duration: Option<Duration>,
 }
 
 /// Layout a single page suitable  for parity adjustment.
@@ -125,6 +131,10 @@ fn layout_page_run_impl(
         .relative_to(size);
 
     let fill = styles.get_cloned(PageElem::fill);
+    let bleed = styles.resolve(PageElem::bleed);
+    let marks = styles.get(PageElem::marks);
+    let transition = styles.get(PageElem::transition);
+    let duration = styles.get(PageElem::duration);
     let foreground = styles.get_ref(PageElem::foreground);
     let background = styles.get_ref(PageElem::background);
     let header_ascent = styles.resolve(PageElem::header_ascent).relative_to(margin.top);
@@ -184,9 +194,19 @@ fn layout_page_run_impl(
         Regions::repeat(area, area.map(Abs::is_finite)),
         styles.get(PageElem::columns),
         styles.get(ColumnsElem::gutter).resolve(styles),
+        styles.get(ColumnsElem::balance),
         FlowMode::Root,
     )?;
 
+    // Layout margin notes. They go into the outer margin, which is the right
+    // one for two-sided pages before a potential swap during finalization.
+    let outer =
+        if two_sided || binding == Binding::Left { margin.right } else { margin.left };
+    let notes = fragment
+        .iter()
+        .map(|inner| layout_margin_notes(&mut engine, &mut locator, styles, inner, outer))
+        .collect::<SourceResult<Vec<_>>>()?;
+
     // Layouts a single marginal.
     let mut layout_marginal = |content: &Option<Content>, area, align| {
         let Some(content) = content else { return Ok(None) };
@@ -208,10 +228,11 @@ fn layout_page_run_impl(
     let footer = footer.clone().map(|f| f.artifact(ArtifactKind::Footer));
     let background = background.clone().map(|b| b.artifact(ArtifactKind::Page));
 
-    for inner in fragment {
+    for (inner, notes) in fragment.into_iter().zip(notes) {
         let header_size = Size::new(inner.width(), margin.top - header_ascent);
         let footer_size = Size::new(inner.width(), margin.bottom - footer_descent);
         let full_size = inner.size() + margin.sum_by_axis();
+        let bleed_size = full_size + Size::splat(2.0 * bleed);
         let mid = HAlignment::Center + VAlignment::Horizon;
         layouted.push(LayoutedPage {
             inner,
@@ -220,11 +241,16 @@ fn layout_page_run_impl(
             supplement: supplement.clone(),
             header: layout_marginal(&header, header_size, Alignment::BOTTOM)?,
             footer: layout_marginal(&footer, footer_size, Alignment::TOP)?,
-            background: layout_marginal(&background, full_size, mid)?,
+            background: layout_marginal(&background, bleed_size, mid)?,
             foreground: layout_marginal(foreground, full_size, mid)?,
             margin,
             binding,
             two_sided,
+            bleed,
+            marks,
+            notes,
+            transition,
+            duration,
         });
     }
 
@@ -8,21 +8,26 @@ use typst_library::foundations::{
 };
 use typst_library::introspection::{Counter, Locator, LocatorLink};
 use typst_library::layout::{
-    Abs, AlignElem, Alignment, Axes, BlockBody, BlockElem, ColumnsElem, Em,
-    FixedAlignment, GridCell, GridChild, GridElem, GridItem, HAlignment, HElem, HideElem,
-    InlineElem, LayoutElem, Length, MoveElem, OuterVAlignment, PadElem, PageElem,
-    PlaceElem, PlacementScope, Region, Rel, RepeatElem, RotateElem, ScaleElem, Sides,
-    Size, Sizing, SkewElem, Spacing, StackChild, StackElem, TrackSizings, VElem,
+    Abs, AlignElem, Alignment, Axes, BlockBody, BlockElem, BoxElem, ColumnsElem, Corners,
+    Em, FixedAlignment, FloatPlacement, GridCell, GridChild, GridElem, GridItem,
+    HAlignment, HElem, HideElem, InlineElem, LayoutElem, Length, MoveElem,
+    OuterVAlignment, PadElem, PageElem, PlaceElem, PlacementScope, Ratio, Region, Rel,
+    RepeatElem, RotateElem, ScaleElem, Sides, Size, Sizing, SkewElem, Spacing,
+    StackChild, StackElem, TrackSizings, VAlignment, VElem,
 };
 use typst_library::math::EquationElem;
 use typst_library::model::{
     Attribution, BibliographyElem, CiteElem, CiteGroup, CslIndentElem, CslLightElem,
     Destination, DirectLinkElem, EmphElem, EnumElem, FigureCaption, FigureElem,
-    FootnoteElem, FootnoteEntry, HeadingElem, LinkElem, LinkMarker, ListElem,
-    OutlineElem, OutlineEntry, ParElem, ParbreakElem, QuoteElem, RefElem, StrongElem,
-    TableCell, TableElem, TermsElem, TitleElem, Works,
+    FigurePlacement, FootnoteElem, FootnoteEntry, GlossaryElem, HeadingElem, IndexElem,
+    LinkElem, LinkMarker, ListElem, MakeIndexElem, MarginNoteElem, OutlineElem,
+    OutlineEntry, ParElem, ParbreakElem, QuoteElem, RefElem, StrongElem, TableCell,
+    TableElem, TermsElem, TitleElem, Works,
+};
+use typst_library::pdf::{
+    AnnotationElem, ArtifactElem, ArtifactKind, AttachElem, FormFieldElem, FormFieldKind,
+    LayerElem, PdfMarkerTag,
 };
-use typst_library::pdf::{ArtifactElem, ArtifactKind, AttachElem, PdfMarkerTag};
 use typst_library::text::{
     DecoLine, Decoration, HighlightElem, ItalicToggle, LinebreakElem, LocalName,
     OverlineElem, RawElem, RawLine, ScriptKind, ShiftSettings, Smallcaps, SmallcapsElem,
@@ -30,8 +35,8 @@ use typst_library::text::{
     TextSize, UnderlineElem, WeightDelta,
 };
 use typst_library::visualize::{
-    CircleElem, CurveElem, EllipseElem, ImageElem, LineElem, PathElem, PolygonElem,
-    RectElem, SquareElem, Stroke,
+    CircleElem, Color, CurveElem, EllipseElem, ImageElem, LineElem, PathElem,
+    PolygonElem, RectElem, SquareElem, Stroke,
 };
 use typst_utils::{Get, Numeric};
 
@@ -55,11 +60,15 @@ pub fn register(rules: &mut NativeRuleMap) {
     rules.register(Paged, QUOTE_RULE);
     rules.register(Paged, FOOTNOTE_RULE);
     rules.register(Paged, FOOTNOTE_ENTRY_RULE);
+    rules.register(Paged, MARGIN_NOTE_RULE);
     rules.register(Paged, OUTLINE_RULE);
     rules.register(Paged, OUTLINE_ENTRY_RULE);
+    rules.register(Paged, INDEX_RULE);
+    rules.register(Paged, MAKE_INDEX_RULE);
     rules.register(Paged, REF_RULE);
     rules.register(Paged, CITE_GROUP_RULE);
     rules.register(Paged, BIBLIOGRAPHY_RULE);
+    rules.register(Paged, GLOSSARY_RULE);
     rules.register(Paged, CSL_LIGHT_RULE);
     rules.register(Paged, CSL_INDENT_RULE);
     rules.register(Paged, TABLE_RULE);
@@ -108,6 +117,9 @@ pub fn register(rules: &mut NativeRuleMap) {
     // PDF.
     rules.register(Paged, ATTACH_RULE);
     rules.register(Paged, ARTIFACT_RULE);
+    rules.register(Paged, FORM_FIELD_RULE);
+    rules.register(Paged, LAYER_RULE);
+    rules.register(Paged, ANNOTATION_RULE);
     rules.register(Paged, PDF_MARKER_TAG_RULE);
 }
 
@@ -329,11 +341,22 @@ const FIGURE_RULE: ShowFn<FigureElem> = |elem, _, styles| {
         .spanned(span);
 
     // Wrap in a float.
-    if let Some(align) = elem.placement.get(styles) {
+    if let Some(placement) = elem.placement.get(styles) {
+        let (align, placement) = match placement {
+            FigurePlacement::Auto => (Smart::Auto, FloatPlacement::Edge),
+            FigurePlacement::Align(align) => (Smart::Custom(align), FloatPlacement::Edge),
+            FigurePlacement::Here => {
+                (Smart::Custom(VAlignment::Top), FloatPlacement::Here)
+            }
+            FigurePlacement::Page => {
+                (Smart::Custom(VAlignment::Top), FloatPlacement::Page)
+            }
+        };
         realized = PlaceElem::new(realized)
             .with_alignment(align.map(|align| HAlignment::Center + align))
             .with_scope(elem.scope.get(styles))
             .with_float(true)
+            .with_placement(placement)
             .pack()
             .spanned(span);
     } else if elem.scope.get(styles) == PlacementScope::Parent {
@@ -413,6 +436,10 @@ const FOOTNOTE_ENTRY_RULE: ShowFn<FootnoteEntry> = |elem, engine, styles| {
     ]))
 };
 
+// The note is laid out into the page margin by the page layouter, which finds
+// it through its introspection tags.
+const MARGIN_NOTE_RULE: ShowFn<MarginNoteElem> = |_, _, _| Ok(Content::empty());
+
 const OUTLINE_RULE: ShowFn<OutlineElem> = |elem, engine, styles| {
     let title = elem.realize_title(styles);
     let entries = elem.realize_flat(engine, styles)?;
@@ -460,6 +487,12 @@ const OUTLINE_ENTRY_RULE: ShowFn<OutlineEntry> = |elem, engine, styles| {
     Ok(block.linked(Destination::Location(loc), Some(alt)))
 };
 
+// The marker is collected by the index through introspection.
+const INDEX_RULE: ShowFn<IndexElem> = |_, _, _| Ok(Content::empty());
+
+const MAKE_INDEX_RULE: ShowFn<MakeIndexElem> =
+    |elem, engine, styles| elem.realize(engine, styles, true);
+
 const REF_RULE: ShowFn<RefElem> = |elem, engine, styles| elem.realize(engine, styles);
 
 const CITE_GROUP_RULE: ShowFn<CiteGroup> = |elem, engine, _| elem.realize(engine);
@@ -529,6 +562,12 @@ const BIBLIOGRAPHY_RULE: ShowFn<BibliographyElem> = |elem, engine, styles| {
     Ok(Content::sequence(seq))
 };
 
+const GLOSSARY_RULE: ShowFn<GlossaryElem> = |elem, engine, styles| {
+    let title = elem.realize_title(styles);
+    let entries = elem.realize_entries(engine, styles, true)?;
+    Ok(Content::sequence(title.into_iter().chain(Some(entries))))
+};
+
 const CSL_LIGHT_RULE: ShowFn<CslLightElem> =
     |elem, _, _| Ok(elem.body.clone().set(TextElem::delta, WeightDelta(-100)));
 
@@ -834,4 +873,62 @@ const ATTACH_RULE: ShowFn<AttachElem> = |_, _, _| Ok(Content::empty());
 
 const ARTIFACT_RULE: ShowFn<ArtifactElem> = |elem, _, _| Ok(elem.body.clone());
 
+const FORM_FIELD_RULE: ShowFn<FormFieldElem> = |elem, _, styles| {
+    let kind = elem.kind.get(styles);
+    let (width, height) = match kind {
+        FormFieldKind::Checkbox | FormFieldKind::Radio => (1.0, 1.0),
+        FormFieldKind::Signature => (12.0, 3.0),
+        _ if elem.multiline.get(styles) => (8.0, 3.0),
+        _ => (8.0, 1.0),
+    };
+
+    let width = elem.width.get(styles).unwrap_or(Em::new(width).into());
+    let height = elem.height.get(styles).unwrap_or(Em::new(height).into());
+    let radius = match kind {
+        FormFieldKind::Radio => Ratio::new(0.5).into(),
+        _ => Rel::zero(),
+    };
+
+    // Show the field's default state for printing and other exports. The PDF
+    // export additionally turns the box into an interactive widget.
+    let (body, inset) = match kind {
+        FormFieldKind::Text | FormFieldKind::Choice => (
+            elem.value.get_cloned(styles).map(TextElem::packed),
+            Sides::new(
+                Em::new(0.2).into(),
+                Rel::zero(),
+                Em::new(0.2).into(),
+                Rel::zero(),
+            ),
+        ),
+        FormFieldKind::Checkbox | FormFieldKind::Radio if elem.checked.get(styles) => {
+            let mark = BoxElem::new()
+                .with_width(Sizing::Rel(Rel::one()))
+                .with_height(Smart::Custom(Rel::one()))
+                .with_fill(Some(Color::BLACK.into()))
+                .with_radius(Corners::splat(Some(radius)));
+            (Some(mark.pack()), Sides::splat(Em::new(0.2).into()))
+        }
+        _ => (None, Sides::splat(Rel::zero())),
+    };
+
+    let stroke = Stroke::from_pair(Color::GRAY, Abs::pt(0.5).into());
+    Ok(BoxElem::new()
+        .with_width(Sizing::Rel(width))
+        .with_height(Smart::Custom(height))
+        .with_stroke(Sides::splat(Some(Some(stroke))))
+        .with_radius(Corners::splat(Some(radius)))
+        .with_inset(inset.map(Some))
+        .with_clip(true)
+        .with_body(body)
+        .pack()
+        .set(FormFieldElem::widget, elem.location()))
+};
+
+const LAYER_RULE: ShowFn<LayerElem> =
+    |elem, _, _| Ok(elem.body.clone().set(LayerElem::current, Some(elem.clone())));
+
+const ANNOTATION_RULE: ShowFn<AnnotationElem> =
+    |elem, _, styles| Ok(elem.body.get_cloned(styles).unwrap_or_default());
+
 const PDF_MARKER_TAG_RULE: ShowFn<PdfMarkerTag> = |elem, _, _| Ok(elem.body.clone());
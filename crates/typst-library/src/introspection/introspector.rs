@@ -11,7 +11,7 @@ use typst_utils::NonZeroExt;
 
 use crate::diag::{StrResult, bail};
 use crate::foundations::{Content, Label, Repr, Selector};
-use crate::introspection::{Location, Tag};
+use crate::introspection::{Location, MarkKind, Tag};
 use crate::layout::{Frame, FrameItem, Point, Position, Transform};
 use crate::model::Numbering;
 
@@ -255,6 +255,40 @@ impl Introspector {
         }
     }
 
+    /// Finds the element matching the selector that is relevant for the given
+    /// page as determined by the mark `kind`.
+    pub fn query_mark(
+        &self,
+        selector: &Selector,
+        page: NonZeroUsize,
+        kind: MarkKind,
+    ) -> Option<Content> {
+        let mut top = None;
+        let mut first = None;
+        let mut last = None;
+
+        // Don't rely on document order matching page order as floats and
+        // other insertions can move elements to later pages.
+        for elem in self.query(selector) {
+            let Some(loc) = elem.location() else { continue };
+            let current = self.page(loc);
+            if current < page {
+                top = Some(elem);
+            } else if current == page {
+                if first.is_none() {
+                    first = Some(elem.clone());
+                }
+                last = Some(elem);
+            }
+        }
+
+        match kind {
+            MarkKind::First => first.or(top),
+            MarkKind::Last => last.or(top),
+            MarkKind::Top => top,
+        }
+    }
+
     /// The total number pages.
     pub fn pages(&self) -> NonZeroUsize {
         NonZeroUsize::new(self.pages).unwrap_or(NonZeroUsize::ONE)
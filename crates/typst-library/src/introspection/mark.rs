@@ -0,0 +1,75 @@
+use comemo::Tracked;
+
+use crate::diag::HintedStrResult;
+use crate::engine::Engine;
+use crate::foundations::{Cast, Content, Context, LocatableSelector, func};
+
+/// Finds the element that a running header or footer should refer to.
+///
+/// Running headers typically show the chapter or section the reader is
+/// currently looking at. The `mark` function finds it by looking at the
+/// elements matching a selector relative to the page of the current
+/// [location]($here). To use it, you first need to ensure that [context] is
+/// available.
+///
+/// Depending on the [`kind`]($mark.kind), it yields the first or last
+/// matching element that starts on the current page. If there is none, it
+/// falls back to the last matching element before the page, that is, the one
+/// that is carried over from a previous page. If no element matches at all,
+/// it yields `{none}`.
+///
+/// # Example
+/// ```example
+/// >>> #set page(width: 200pt, height: 120pt, margin: (top: 30pt))
+/// #set page(header: context {
+///   let chapter = mark(heading.where(level: 1))
+///   let section = mark(heading.where(level: 2), kind: "last")
+///   if chapter != none [
+///     _#chapter.body _
+///   ]
+///   h(1fr)
+///   if section != none [
+///     #section.body
+///   ]
+/// })
+///
+/// = Introduction
+/// == Motivation
+/// #lorem(15)
+/// == Overview
+/// #lorem(30)
+/// ```
+#[func(contextual)]
+pub fn mark(
+    engine: &mut Engine,
+    context: Tracked<Context>,
+    /// The elements to consider. Can be an element function like a
+    /// `heading`, a `{<label>}`, or a more complex selector like
+    /// `{heading.where(level: 1)}`.
+    ///
+    /// Only [locatable]($location/#locatable) element functions are supported.
+    target: LocatableSelector,
+    /// Which of the matching elements to retrieve.
+    #[named]
+    #[default]
+    kind: MarkKind,
+) -> HintedStrResult<Option<Content>> {
+    let loc = context.location()?;
+    let page = engine.introspector.page(loc);
+    Ok(engine.introspector.query_mark(&target.0, page, kind))
+}
+
+/// Which matching element a [`mark`] yields.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum MarkKind {
+    /// The first element starting on the page or, if there is none, the last
+    /// one before it.
+    #[default]
+    First,
+    /// The last element starting on the page or, if there is none, the last
+    /// one before it.
+    Last,
+    /// The last element before the page, i.e. the one that is still in effect
+    /// at the top of the page.
+    Top,
+}
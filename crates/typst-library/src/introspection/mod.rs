@@ -8,6 +8,8 @@ mod introspector;
 mod locate_;
 mod location;
 mod locator;
+#[path = "mark.rs"]
+mod mark_;
 mod metadata;
 #[path = "query.rs"]
 mod query_;
@@ -20,6 +22,7 @@ pub use self::introspector::*;
 pub use self::locate_::*;
 pub use self::location::*;
 pub use self::locator::*;
+pub use self::mark_::*;
 pub use self::metadata::*;
 pub use self::query_::*;
 pub use self::state::*;
@@ -37,5 +40,6 @@ pub fn define(global: &mut Scope) {
     global.define_func::<here>();
     global.define_func::<query>();
     global.define_func::<locate>();
+    global.define_func::<mark>();
     global.reset_category();
 }
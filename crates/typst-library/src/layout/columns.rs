@@ -6,10 +6,10 @@ use crate::layout::{Length, Ratio, Rel};
 /// Separates a region into multiple equally sized columns.
 ///
 /// The `column` function lets you separate the interior of any container into
-/// multiple columns. It will currently not balance the height of the columns.
-/// Instead, the columns will take up the height of their container or the
-/// remaining height on the page. Support for balanced columns is planned for
-/// the future.
+/// multiple columns. By default, the columns are filled one after another and
+/// take up the height of their container or the remaining height on the page.
+/// With [`balance`]($columns.balance), the columns of the final region are
+/// instead made to end at roughly equal heights.
 ///
 /// # Page-level columns { #page-level }
 /// If you need to insert columns across your whole document, use the `{page}`
@@ -50,6 +50,28 @@ pub struct ColumnsElem {
     #[default(Ratio::new(0.04).into())]
     pub gutter: Rel<Length>,
 
+    /// Whether to balance the height of the columns.
+    ///
+    /// When enabled, the columns of the final region (the last page or the
+    /// end of the container) are shortened as much as possible while all
+    /// remaining content, including floats and footnotes, still fits. This
+    /// avoids ending with one full and one nearly empty column. Regions which
+    /// are followed by more content are filled as usual. Explicit
+    /// [column breaks]($colbreak) are kept, and the columns are balanced as a
+    /// whole rather than separately before and after each break.
+    ///
+    /// This also applies to page-level columns, which can be balanced with
+    /// `{set columns(balance: true)}`.
+    ///
+    /// ```example
+    /// #set page(height: 120pt)
+    /// #columns(2, balance: true)[
+    ///   #lorem(30)
+    /// ]
+    /// ```
+    #[default(false)]
+    pub balance: bool,
+
     /// The content that should be layouted into the columns.
     #[required]
     pub body: Content,
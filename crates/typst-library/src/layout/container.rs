@@ -1,8 +1,8 @@
 use crate::diag::{SourceResult, bail};
 use crate::engine::Engine;
 use crate::foundations::{
-    Args, AutoValue, Construct, Content, NativeElement, Packed, Smart, StyleChain, Value,
-    cast, elem,
+    Args, AutoValue, Cast, Construct, Content, NativeElement, Packed, Smart, StyleChain,
+    Value, cast, elem,
 };
 use crate::introspection::Locator;
 use crate::layout::{
@@ -362,6 +362,51 @@ pub struct BlockElem {
     #[default(false)]
     pub sticky: bool,
 
+    /// How many lines or blocks following this block must end up in the same
+    /// region as it.
+    ///
+    /// If the block and the given amount of following content don't fit into
+    /// the remaining space, the block moves to the next page or column along
+    /// with them. This generalizes [`sticky`]($block.sticky), which keeps the
+    /// block only with the very next line or block. A value of zero disables
+    /// this unless the block is sticky.
+    ///
+    /// ```example
+    /// >>> #set page(height: 140pt)
+    /// // Keep headings with at least three lines.
+    /// #show heading: set block(keep-with-next: 3)
+    /// #lorem(22)
+    ///
+    /// = Chapter
+    /// #lorem(20)
+    /// ```
+    #[default(0)]
+    pub keep_with_next: usize,
+
+    /// Which columns the block spans in a multi-column layout.
+    ///
+    /// By default, a block is laid out in the current column. When set to
+    /// `{"all"}`, the block interrupts the columns of the enclosing page or
+    /// [`columns`] container: The content before it is balanced into the
+    /// columns above the block, the block itself takes the full width, and
+    /// the columns resume below it. This is useful for titles, abstracts, and
+    /// wide figures in the middle of a multi-column document.
+    ///
+    /// A spanning block is never broken across pages. Outside of a
+    /// multi-column layout, this property has no effect.
+    ///
+    /// ```example
+    /// #set page(columns: 2, height: 150pt)
+    /// #lorem(20)
+    ///
+    /// #block(span: "all", fill: luma(230), inset: 6pt, width: 100%)[
+    ///   *Abstract.* #lorem(10)
+    /// ]
+    ///
+    /// #lorem(20)
+    /// ```
+    pub span: BlockSpan,
+
     /// The contents of the block.
     #[positional]
     pub body: Option<BlockBody>,
@@ -406,6 +451,16 @@ impl BlockElem {
     }
 }
 
+/// Which columns a block spans in a multi-column layout.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum BlockSpan {
+    /// The block is laid out in the current column.
+    #[default]
+    Column,
+    /// The block interrupts the columns and spans across all of them.
+    All,
+}
+
 /// The contents of a block.
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub enum BlockBody {
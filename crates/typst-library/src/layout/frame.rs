@@ -7,10 +7,11 @@ use std::sync::Arc;
 use typst_syntax::Span;
 use typst_utils::{LazyHash, Numeric};
 
-use crate::foundations::{Dict, Label, Value, cast, dict};
+use crate::foundations::{Dict, Label, Packed, StyleChain, Value, cast, dict};
 use crate::introspection::{Location, Tag};
 use crate::layout::{Abs, Axes, FixedAlignment, Length, Point, Size, Transform};
 use crate::model::Destination;
+use crate::pdf::LayerElem;
 use crate::text::TextItem;
 use crate::visualize::{Color, Curve, FixedStroke, Geometry, Image, Paint, Shape};
 
@@ -352,6 +353,21 @@ impl Frame {
         }
     }
 
+    /// Put the frame's contents into a PDF layer.
+    pub fn set_layer(&mut self, layer: Packed<LayerElem>) {
+        if !self.is_empty() {
+            self.group(|g| g.layer = Some(layer));
+        }
+    }
+
+    /// Mark the frame's contents as the widget of the PDF form field at the
+    /// given location.
+    pub fn set_form_field(&mut self, location: Location) {
+        if !self.is_empty() {
+            self.group(|g| g.form_field = Some(location));
+        }
+    }
+
     /// Wrap the frame's contents in a group and modify that group with `f`.
     fn group<F>(&mut self, f: F)
     where
@@ -507,6 +523,10 @@ pub struct GroupItem {
     /// thought of as inserting the elements at the end but still inside of the
     /// parent.
     pub parent: Option<FrameParent>,
+    /// The PDF layer the group belongs to.
+    pub layer: Option<Packed<LayerElem>>,
+    /// The location of the PDF form field whose widget the group is.
+    pub form_field: Option<Location>,
 }
 
 impl GroupItem {
@@ -518,8 +538,18 @@ impl GroupItem {
             clip: None,
             label: None,
             parent: None,
+            layer: None,
+            form_field: None,
         }
     }
+
+    /// Whether the group is hidden by default because it belongs to an
+    /// invisible PDF layer.
+    pub fn in_hidden_layer(&self) -> bool {
+        self.layer
+            .as_ref()
+            .is_some_and(|layer| !layer.visible.get(StyleChain::default()))
+    }
 }
 
 impl Debug for GroupItem {
@@ -7,8 +7,8 @@ use typst_utils::{NonZeroExt, Scalar, singleton};
 use crate::diag::{SourceResult, bail};
 use crate::engine::Engine;
 use crate::foundations::{
-    Args, AutoValue, Cast, Construct, Content, Dict, Fold, NativeElement, Set, Smart,
-    Value, cast, elem,
+    Args, AutoValue, Cast, Construct, Content, Dict, Duration, Fold, NativeElement, Set,
+    Smart, Value, cast, elem,
 };
 use crate::introspection::Introspector;
 use crate::layout::{
@@ -169,7 +169,10 @@ pub struct PageElem {
     /// How many columns the page has.
     ///
     /// If you need to insert columns into a page or other container, you can
-    /// also use the [`columns` function]($columns).
+    /// also use the [`columns` function]($columns). The gutter between columns
+    /// and whether the columns on the final page are
+    /// [balanced]($columns.balance) are configured through set rules on
+    /// `columns`.
     ///
     /// ```example:single
     /// #set page(columns: 2, height: 4.8cm)
@@ -210,6 +213,74 @@ pub struct PageElem {
     #[ghost]
     pub fill: Smart<Option<Paint>>,
 
+    /// How far the page extends beyond its trim size on each side.
+    ///
+    /// Documents that are printed to the edge of the paper are printed on
+    /// larger sheets and cut to size afterwards. To avoid white slivers at the
+    /// edges when the cut is slightly off, the page's [`fill`]($page.fill) and
+    /// [`background`]($page.background) extend into this _bleed_ area.
+    /// Everything else, including the margins, is still measured from the
+    /// edge of the trimmed page.
+    ///
+    /// In PDF export, the trimmed page and the page with bleed are recorded
+    /// as the page's trim box and bleed box. A bleed of 3mm is a common
+    /// requirement of print shops.
+    ///
+    /// ```example
+    /// #set page(
+    ///   width: 120pt,
+    ///   height: 80pt,
+    ///   bleed: 6pt,
+    ///   fill: aqua,
+    /// )
+    ///
+    /// Printed to the edge.
+    /// ```
+    #[ghost]
+    pub bleed: Length,
+
+    /// Whether to add printer marks around the page.
+    ///
+    /// When enabled, the page is extended beyond its [bleed]($page.bleed) by
+    /// an area that contains crop marks at the corners of the trimmed page and
+    /// registration marks at the middle of each side. This area is not filled
+    /// with the page's [`fill`]($page.fill).
+    ///
+    /// ```example
+    /// #set page(
+    ///   width: 120pt,
+    ///   height: 80pt,
+    ///   bleed: 6pt,
+    ///   marks: true,
+    ///   fill: aqua,
+    /// )
+    ///
+    /// Ready for the press.
+    /// ```
+    #[default(false)]
+    #[ghost]
+    pub marks: bool,
+
+    /// The effect with which a PDF reader reveals the page when presenting the
+    /// document as a slide show.
+    ///
+    /// ```typ
+    /// #set page(transition: "dissolve")
+    /// ```
+    #[ghost]
+    pub transition: Option<PageTransition>,
+
+    /// How long a PDF reader in presentation mode shows the page before
+    /// automatically advancing to the next one.
+    ///
+    /// When set to `{none}`, the reader waits for the viewer to advance.
+    ///
+    /// ```typ
+    /// #set page(transition: "fade", duration: duration(seconds: 5))
+    /// ```
+    #[ghost]
+    pub duration: Option<Duration>,
+
     /// How to number the pages. You can refer to the Page Setup Guide for
     /// [customizing page numbers]($guides/page-setup/#page-numbers).
     ///
@@ -295,6 +366,23 @@ pub struct PageElem {
     ///
     /// #lorem(19)
     /// ```
+    ///
+    /// To show the current chapter or section in the header, use the
+    /// [`mark`] function. It finds the first or last matching element
+    /// starting on the page or the one carried over from a previous page.
+    ///
+    /// ```example
+    /// >>> #set page(width: 200pt, height: 100pt, margin: (top: 28pt))
+    /// #set page(header: context {
+    ///   let section = mark(heading)
+    ///   if section != none {
+    ///     emph(section.body)
+    ///   }
+    /// })
+    ///
+    /// = Introduction
+    /// #lorem(30)
+    /// ```
     #[ghost]
     pub header: Smart<Option<Content>>,
 
@@ -505,6 +593,16 @@ pub struct Page {
     /// The logical page number (controlled by `counter(page)` and may thus not
     /// match the physical number).
     pub number: u64,
+    /// How far the page's fill and background extend beyond the trimmed page
+    /// on each side.
+    pub bleed: Abs,
+    /// The size of the area around the bleed that hosts printer marks. Zero
+    /// if the page has no marks.
+    pub slug: Abs,
+    /// The effect with which the page is revealed in presentation mode.
+    pub transition: Option<PageTransition>,
+    /// How long the page is shown in presentation mode.
+    pub duration: Option<Duration>,
 }
 
 impl Page {
@@ -521,6 +619,17 @@ impl Page {
     pub fn fill_or_white(&self) -> Option<Paint> {
         self.fill.clone().unwrap_or_else(|| Some(Color::WHITE.into()))
     }
+
+    /// The distance from the edges of the page's frame to its bleed box.
+    pub fn bleed_inset(&self) -> Abs {
+        self.slug
+    }
+
+    /// The distance from the edges of the page's frame to its trim box, that
+    /// is, the edges of the finished page.
+    pub fn trim_inset(&self) -> Abs {
+        self.slug + self.bleed
+    }
 }
 
 /// Specification of the page's margins.
@@ -632,6 +741,33 @@ cast! {
     }
 }
 
+/// An effect with which a page is revealed in presentation mode.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum PageTransition {
+    /// The page is revealed by two lines sweeping across the screen.
+    Split,
+    /// Multiple lines sweep across the screen.
+    Blinds,
+    /// A rectangular box sweeps inward from the edges.
+    Box,
+    /// A single line sweeps across the screen.
+    Wipe,
+    /// The old page dissolves gradually.
+    Dissolve,
+    /// Like dissolve, but the effect sweeps across the page.
+    Glitter,
+    /// The new page flies in.
+    Fly,
+    /// The old page is pushed off the screen.
+    Push,
+    /// The new page slides on, covering the old one.
+    Cover,
+    /// The old page slides off, uncovering the new one.
+    Uncover,
+    /// The new page fades in.
+    Fade,
+}
+
 /// Specification of the page's binding.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Binding {
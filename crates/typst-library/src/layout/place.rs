@@ -134,6 +134,71 @@ pub struct PlaceElem {
     /// ```
     pub float: bool,
 
+    /// Where a floating element may end up.
+    ///
+    /// - `{"edge"}`: At the top or bottom of the container, as determined by
+    ///   the [`alignment`]($place.alignment).
+    /// - `{"here"}`: Exactly where it occurs in the flow if it fits there.
+    ///   Otherwise, the element floats to the top of the next column or page.
+    /// - `{"page"}`: On a dedicated page (or column, for column-scoped
+    ///   floats) that contains nothing but such floats. This is useful for
+    ///   large figures that would otherwise leave little room for text.
+    ///
+    /// Has no effect if `float` is `{false}`.
+    ///
+    /// ```example
+    /// #set page(height: 150pt)
+    /// #lorem(10)
+    /// #place(
+    ///   float: true,
+    ///   placement: "here",
+    ///   rect(width: 100%, height: 30pt),
+    /// )
+    /// #lorem(20)
+    /// ```
+    pub placement: FloatPlacement,
+
+    /// The maximum number of floats at the top and bottom of a single column
+    /// or page.
+    ///
+    /// Additional floats are deferred to the following columns or pages. When
+    /// set to `{none}`, the number of floats is not limited.
+    ///
+    /// This applies to a whole flow and thus needs to be set for the whole
+    /// page or container, for instance with a set rule at the start of the
+    /// document.
+    ///
+    /// ```example
+    /// #set page(height: 150pt)
+    /// #set place(float-limit: 1)
+    /// #let note(body) = place(
+    ///   top,
+    ///   float: true,
+    ///   rect(body),
+    /// )
+    ///
+    /// #note[One]
+    /// #note[Two]
+    /// #lorem(20)
+    /// ```
+    #[ghost]
+    pub float_limit: Option<usize>,
+
+    /// Whether later floats may be placed before earlier floats that had to
+    /// be deferred.
+    ///
+    /// By default, floats always appear in the order in which they occur in
+    /// the document. Thus, a large float that doesn't fit on the current page
+    /// also holds back all following floats. When this is enabled, deferred
+    /// floats are kept in a queue, and later floats that fit are placed
+    /// right away. The deferred floats keep their relative order.
+    ///
+    /// Like [`float-limit`]($place.float-limit), this needs to be set for the
+    /// whole page or container.
+    #[ghost]
+    #[default(false)]
+    pub reorder: bool,
+
     /// The spacing between the placed element and other elements in a floating
     /// layout.
     ///
@@ -184,6 +249,18 @@ pub enum PlacementScope {
     Parent,
 }
 
+/// Where a floating element may end up.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum FloatPlacement {
+    /// At the top or bottom of the container.
+    #[default]
+    Edge,
+    /// In the flow if it fits, otherwise at the top of the next region.
+    Here,
+    /// On a dedicated page or column with only floats.
+    Page,
+}
+
 /// Asks the layout algorithm to place pending floating elements before
 /// continuing with the content.
 ///
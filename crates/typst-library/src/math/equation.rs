@@ -40,6 +40,11 @@ use crate::text::{FontFamily, FontList, FontWeight, LocalName, Locale, TextElem}
 /// By default, block-level equations will not break across pages. This can be
 /// changed through `{show math.equation: set block(breakable: true)}`.
 ///
+/// # HTML export
+/// In HTML export, equations are converted into MathML. Parts of an equation
+/// that MathML cannot express, like cancelled content or augmented matrices,
+/// are embedded as SVGs instead.
+///
 /// # Syntax
 /// This function also has dedicated syntax: Write mathematical markup within
 /// dollar signs to create an equation. Starting and ending the equation with
@@ -1,11 +1,14 @@
+use std::num::NonZeroUsize;
+
 use ecow::EcoString;
 
 use crate::diag::{HintedStrResult, SourceResult, bail};
 use crate::engine::Engine;
 use crate::foundations::{
-    Args, Array, Construct, Content, Datetime, OneOrMultiple, Smart, StyleChain, Styles,
-    Value, cast, elem,
+    Args, Array, Cast, Construct, Content, Datetime, IntoValue, OneOrMultiple, Smart,
+    StyleChain, Styles, Value, cast, elem,
 };
+use crate::layout::Ratio;
 use crate::text::{Locale, TextElem};
 
 /// The root element of a document and its metadata.
@@ -63,6 +66,57 @@ pub struct DocumentElem {
     /// something other than `{auto}`.
     #[ghost]
     pub date: Smart<Option<Datetime>>,
+
+    /// How PDF readers arrange the pages when the document is opened.
+    ///
+    /// When set to `{auto}`, the reader's default is used.
+    ///
+    /// ```typ
+    /// #set document(page-layout: "two-page-right")
+    /// ```
+    #[ghost]
+    pub page_layout: Smart<PageLayout>,
+
+    /// Which panel PDF readers show when the document is opened, or whether
+    /// they present it in full screen.
+    ///
+    /// When set to `{auto}`, the reader's default is used.
+    ///
+    /// ```typ
+    /// #set document(page-mode: "full-screen")
+    /// ```
+    #[ghost]
+    pub page_mode: Smart<PageMode>,
+
+    /// Whether PDF readers show the document's [title]($document.title)
+    /// instead of its file name in the title bar of their window.
+    ///
+    /// When set to `{auto}`, the title is shown if the document has one. When
+    /// exporting to PDF/UA, the title must be shown.
+    #[ghost]
+    pub display_title: Smart<bool>,
+
+    /// The page PDF readers show when the document is opened.
+    ///
+    /// This is the physical page number, starting at one. When set to
+    /// `{auto}`, readers open the first page.
+    #[ghost]
+    pub initial_page: Smart<NonZeroUsize>,
+
+    /// The zoom level at which PDF readers show the document when it is
+    /// opened.
+    ///
+    /// Either a ratio or one of:
+    /// - `{"fit"}`: The whole page is visible.
+    /// - `{"fit-width"}`: The page fills the width of the window.
+    ///
+    /// When set to `{auto}`, the reader's default is used.
+    ///
+    /// ```typ
+    /// #set document(initial-page: 2, initial-zoom: "fit")
+    /// ```
+    #[ghost]
+    pub initial_zoom: Smart<Zoom>,
 }
 
 impl Construct for DocumentElem {
@@ -93,6 +147,81 @@ cast! {
     v: Array => Self(v.into_iter().map(Value::cast).collect::<HintedStrResult<_>>()?),
 }
 
+/// How PDF readers arrange the pages of a document.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum PageLayout {
+    /// One page at a time.
+    SinglePage,
+    /// The pages in one continuous column.
+    OneColumn,
+    /// The pages in two continuous columns, with odd-numbered pages on the
+    /// left.
+    TwoColumnLeft,
+    /// The pages in two continuous columns, with odd-numbered pages on the
+    /// right.
+    TwoColumnRight,
+    /// Two pages at a time, with odd-numbered pages on the left.
+    TwoPageLeft,
+    /// Two pages at a time, with odd-numbered pages on the right.
+    TwoPageRight,
+}
+
+/// Which panel PDF readers show alongside a document.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum PageMode {
+    /// No panel.
+    Plain,
+    /// The document outline.
+    Outline,
+    /// Thumbnails of the pages.
+    Thumbnails,
+    /// The attached files.
+    Attachments,
+    /// The document's [layers]($pdf.layer). Falls back to no panel if the
+    /// PDF has no optional content.
+    Layers,
+    /// No panel, with the document in full screen.
+    FullScreen,
+}
+
+/// The zoom level at which a document is opened.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Zoom {
+    /// A fixed zoom level.
+    Ratio(Ratio),
+    /// The whole page is visible.
+    Fit,
+    /// The page fills the width of the window.
+    FitWidth,
+}
+
+cast! {
+    Zoom,
+    self => match self {
+        Self::Ratio(v) => v.into_value(),
+        Self::Fit => "fit".into_value(),
+        Self::FitWidth => "fit-width".into_value(),
+    },
+    v: Ratio => Self::Ratio(v),
+    "fit" => Self::Fit,
+    "fit-width" => Self::FitWidth,
+}
+
+/// Settings for how PDF readers present a document.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct ViewerPreferences {
+    /// How pages are arranged.
+    pub page_layout: Smart<PageLayout>,
+    /// Which panel is shown.
+    pub page_mode: Smart<PageMode>,
+    /// Whether the title is shown instead of the file name.
+    pub display_title: Smart<bool>,
+    /// The physical page the document is opened on.
+    pub initial_page: Smart<NonZeroUsize>,
+    /// The zoom level the document is opened with.
+    pub initial_zoom: Smart<Zoom>,
+}
+
 /// Details about the document.
 #[derive(Debug, Default, Clone, PartialEq, Hash)]
 pub struct DocumentInfo {
@@ -112,6 +241,8 @@ pub struct DocumentInfo {
     /// set text(lang: "...", region: "...")
     /// ```
     pub locale: Smart<Locale>,
+    /// How PDF readers should present the document.
+    pub viewer: ViewerPreferences,
 }
 
 impl DocumentInfo {
@@ -141,6 +272,21 @@ impl DocumentInfo {
         if styles.has(DocumentElem::date) {
             self.date = chain.get(DocumentElem::date);
         }
+        if styles.has(DocumentElem::page_layout) {
+            self.viewer.page_layout = chain.get(DocumentElem::page_layout);
+        }
+        if styles.has(DocumentElem::page_mode) {
+            self.viewer.page_mode = chain.get(DocumentElem::page_mode);
+        }
+        if styles.has(DocumentElem::display_title) {
+            self.viewer.display_title = chain.get(DocumentElem::display_title);
+        }
+        if styles.has(DocumentElem::initial_page) {
+            self.viewer.initial_page = chain.get(DocumentElem::initial_page);
+        }
+        if styles.has(DocumentElem::initial_zoom) {
+            self.viewer.initial_zoom = chain.get(DocumentElem::initial_zoom);
+        }
     }
 
     /// Populate this document info with locale details from the given styles.
@@ -8,8 +8,8 @@ use typst_utils::NonZeroExt;
 use crate::diag::{SourceResult, bail};
 use crate::engine::Engine;
 use crate::foundations::{
-    Content, Element, NativeElement, Packed, Selector, ShowSet, Smart, StyleChain,
-    Styles, Synthesize, cast, elem, scope, select_where,
+    AutoValue, Content, Element, IntoValue, NativeElement, Packed, Selector, ShowSet,
+    Smart, StyleChain, Styles, Synthesize, Value, cast, elem, scope, select_where,
 };
 use crate::introspection::{
     Count, Counter, CounterKey, CounterUpdate, Locatable, Location, Tagged,
@@ -148,6 +148,13 @@ pub struct FigureElem {
     ///   is closer.
     /// - `{top}`: The figure floats to the top of the page.
     /// - `{bottom}`: The figure floats to the bottom of the page.
+    /// - `{"here"}`: The figure stays where it was specified if it fits there
+    ///   and floats to the top of the next page otherwise.
+    /// - `{"page"}`: The figure is placed on a dedicated page of floats.
+    ///
+    /// See the [`place`] function's [`placement`]($place.placement),
+    /// [`float-limit`]($place.float-limit), and [`reorder`]($place.reorder)
+    /// parameters for more details and further configuration.
     ///
     /// The gap between the main flow content and the floating figure is
     /// controlled by the [`clearance`]($place.clearance) argument on the
@@ -167,7 +174,7 @@ pub struct FigureElem {
     /// )
     /// #lorem(60)
     /// ```
-    pub placement: Option<Smart<VAlignment>>,
+    pub placement: Option<FigurePlacement>,
 
     /// Relative to which containing scope the figure is placed.
     ///
@@ -469,11 +476,45 @@ impl Outlinable for Packed<FigureElem> {
         self.caption
             .get_ref(StyleChain::default())
             .as_ref()
-            .map(|caption| caption.body.clone())
+            .map(|caption| {
+                caption
+                    .short
+                    .get_cloned(StyleChain::default())
+                    .unwrap_or_else(|| caption.body.clone())
+            })
             .unwrap_or_default()
     }
 }
 
+/// The placement of a figure.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FigurePlacement {
+    /// Float at the top or bottom, whichever is closer.
+    Auto,
+    /// Float at the given vertical edge.
+    Align(VAlignment),
+    /// Stay in the flow if possible.
+    Here,
+    /// Float onto a dedicated page.
+    Page,
+}
+
+cast! {
+    FigurePlacement,
+    self => match self {
+        Self::Auto => Value::Auto,
+        Self::Align(align) => align.into_value(),
+        Self::Here => "here".into_value(),
+        Self::Page => "page".into_value(),
+    },
+    _: AutoValue => Self::Auto,
+    align: VAlignment => Self::Align(align),
+    /// Stay in the flow if possible.
+    "here" => Self::Here,
+    /// Float onto a dedicated page.
+    "page" => Self::Page,
+}
+
 /// The caption of a figure. This element can be used in set and show rules to
 /// customize the appearance of captions for all figures or figures of a
 /// specific kind.
@@ -536,6 +577,25 @@ pub struct FigureCaption {
     /// ```
     pub separator: Smart<Content>,
 
+    /// A short version of the caption for use in [outlines]($outline), such
+    /// as a list of figures.
+    ///
+    /// When this is `{none}`, outlines show the full caption body.
+    ///
+    /// ```example
+    /// #outline(target: figure)
+    ///
+    /// #figure(
+    ///   rect[Hello],
+    ///   caption: figure.caption(
+    ///     short: [A rectangle],
+    ///     [A rectangle with the text "Hello" in it, which is used to
+    ///      demonstrate short captions.],
+    ///   ),
+    /// )
+    /// ```
+    pub short: Option<Content>,
+
     /// The caption's body.
     ///
     /// Can be used alongside `kind`, `supplement`, `counter`, `numbering`, and
@@ -0,0 +1,271 @@
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use comemo::Tracked;
+use ecow::EcoString;
+use rustc_hash::FxHashMap;
+use typst_utils::{NonZeroExt, PicoStr};
+
+use crate::diag::{SourceResult, bail};
+use crate::engine::Engine;
+use crate::foundations::{
+    Content, Dict, IntoValue, Label, NativeElement, Packed, Selector, ShowSet, Smart,
+    StyleChain, Styles, Value, cast, dict, elem, select_where,
+};
+use crate::introspection::{Introspector, Locatable};
+use crate::model::{
+    DirectLinkElem, HeadingElem, ParElem, RefElem, RefForm, StrongElem, TermItem,
+    TermsElem, page_ranges,
+};
+use crate::text::{LocalName, TextElem};
+
+/// A glossary of terms and acronyms.
+///
+/// The entries of a glossary can be referenced with the regular
+/// [reference syntax]($ref): Writing `[@pdf]` refers to the entry with the
+/// key `{"pdf"}`. The first reference to an entry shows its long form followed
+/// by the short form in parentheses. All later references only show the
+/// short form. Each reference links to the glossary.
+///
+/// The glossary itself lists its entries as a [term list]($terms) along with
+/// the pages on which they are referenced. By default, only entries that are
+/// referenced in the document are listed.
+///
+/// # Example
+/// ```example
+/// >>> #set page(width: 200pt)
+/// The @pdf format is widely used.
+/// A @pdf file can be embedded into
+/// another @pdf.
+///
+/// #glossary((
+///   (
+///     key: "pdf",
+///     short: [PDF],
+///     long: [Portable Document Format],
+///   ),
+///   (key: "svg", short: [SVG]),
+/// ))
+/// ```
+#[elem(Locatable, ShowSet, LocalName)]
+pub struct GlossaryElem {
+    /// The title of the glossary.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the
+    ///   [text language]($text.lang) will be used.
+    /// - When set to `{none}`, the glossary will not have a title.
+    /// - A custom title can be set by passing content.
+    pub title: Smart<Option<Content>>,
+
+    /// Whether to list all entries, even those that weren't referenced in the
+    /// document.
+    #[default(false)]
+    pub full: bool,
+
+    /// The entries of the glossary.
+    ///
+    /// Each entry is a dictionary with the following keys:
+    /// - `key`: The string by which the entry is referenced. Required.
+    /// - `short`: The short form of the term, e.g. an acronym. Required.
+    /// - `long`: The long form of the term. Optional.
+    /// - `description`: A description that is shown in the glossary.
+    ///   Optional.
+    #[required]
+    pub entries: Vec<GlossaryEntry>,
+}
+
+impl GlossaryElem {
+    /// Finds the glossary entry with the given key in the document.
+    pub fn find(engine: &Engine, key: Label) -> Option<(Packed<Self>, GlossaryEntry)> {
+        glossary_keys(engine.introspector).get(&key).cloned()
+    }
+}
+
+/// Maps the keys of all glossary entries in the document to their glossary
+/// and entry.
+///
+/// This is memoized since every reference in the document looks up its
+/// target here.
+#[comemo::memoize]
+fn glossary_keys(
+    introspector: Tracked<Introspector>,
+) -> Arc<FxHashMap<Label, (Packed<GlossaryElem>, GlossaryEntry)>> {
+    let mut keys = FxHashMap::default();
+    for elem in introspector.query(&GlossaryElem::ELEM.select()).iter() {
+        let glossary = elem.to_packed::<GlossaryElem>().unwrap();
+        for entry in &glossary.entries {
+            keys.entry(entry.key)
+                .or_insert_with(|| (glossary.clone(), entry.clone()));
+        }
+    }
+    Arc::new(keys)
+}
+
+impl Packed<GlossaryElem> {
+    /// Produces the heading for the glossary, if any.
+    pub fn realize_title(&self, styles: StyleChain) -> Option<Content> {
+        self.title
+            .get_cloned(styles)
+            .unwrap_or_else(|| {
+                Some(TextElem::packed(Packed::<GlossaryElem>::local_name_in(styles)))
+            })
+            .map(|title| {
+                HeadingElem::new(title)
+                    .with_depth(NonZeroUsize::ONE)
+                    .pack()
+                    .spanned(self.span())
+            })
+    }
+
+    /// Realizes the glossary's entries as a term list.
+    ///
+    /// If `pages` is true, the pages on which an entry is referenced are
+    /// listed after its description.
+    pub fn realize_entries(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        pages: bool,
+    ) -> SourceResult<Content> {
+        let span = self.span();
+        let full = self.full.get(styles);
+
+        let mut items = vec![];
+        for entry in &self.entries {
+            let locs: Vec<_> = engine
+                .introspector
+                .query(&GlossaryEntry::uses(entry.key))
+                .iter()
+                .filter_map(|elem| elem.location())
+                .collect();
+
+            if locs.is_empty() && !full {
+                continue;
+            }
+
+            let mut description = vec![];
+            description.extend(entry.long.clone());
+            if let Some(text) = &entry.description {
+                if !description.is_empty() {
+                    description.push(TextElem::packed(". "));
+                }
+                description.push(text.clone());
+            }
+
+            if pages {
+                for (i, range) in
+                    page_ranges(engine, styles, &locs)?.into_iter().enumerate()
+                {
+                    if i > 0 || !description.is_empty() {
+                        description.push(TextElem::packed(", "));
+                    }
+                    description.push(range);
+                }
+            }
+
+            let term = StrongElem::new(entry.short.clone()).pack();
+            let item = TermItem::new(term, Content::sequence(description));
+            items.push(Packed::new(item).spanned(span));
+        }
+
+        Ok(TermsElem::new(items).pack().spanned(span))
+    }
+}
+
+impl ShowSet for Packed<GlossaryElem> {
+    fn show_set(&self, _: StyleChain) -> Styles {
+        let mut out = Styles::new();
+        out.set(HeadingElem::numbering, None);
+        out.set(ParElem::justify, false);
+        out
+    }
+}
+
+impl LocalName for Packed<GlossaryElem> {
+    const KEY: &'static str = "glossary";
+}
+
+/// An entry in a glossary.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct GlossaryEntry {
+    /// The key by which the entry is referenced.
+    pub key: Label,
+    /// The short form of the term.
+    pub short: Content,
+    /// The long form of the term.
+    pub long: Option<Content>,
+    /// A description of the term.
+    pub description: Option<Content>,
+}
+
+impl GlossaryEntry {
+    /// A selector for the references to this entry.
+    fn uses(key: Label) -> Selector {
+        select_where!(RefElem, target => key, form => RefForm::Normal)
+    }
+
+    /// Realizes a reference to this entry.
+    ///
+    /// The first reference shows the long form along with the short form, all
+    /// later ones only show the short form.
+    pub fn realize_ref(
+        &self,
+        reference: &Packed<RefElem>,
+        engine: &mut Engine,
+        glossary: &Packed<GlossaryElem>,
+    ) -> SourceResult<Content> {
+        let span = reference.span();
+        let Some(loc) = reference.location() else {
+            bail!(span, "reference to glossary entry has no location");
+        };
+
+        let first =
+            engine.introspector.query_count_before(&Self::uses(self.key), loc) <= 1;
+
+        let content = match &self.long {
+            Some(long) if first => {
+                long.clone()
+                    + TextElem::packed(" (")
+                    + self.short.clone()
+                    + TextElem::packed(")")
+            }
+            _ => self.short.clone(),
+        };
+
+        let alt = content.plain_text();
+        let target = glossary.location().unwrap();
+        Ok(DirectLinkElem::new(target, content.spanned(span), Some(alt)).pack())
+    }
+}
+
+cast! {
+    GlossaryEntry,
+    self => Value::Dict(self.into()),
+    mut dict: Dict => {
+        let key: EcoString = dict.take("key")?.cast()?;
+        let Some(key) = Label::new(PicoStr::intern(&key)) else {
+            bail!("glossary key must not be empty");
+        };
+        let short = dict.take("short")?.cast()?;
+        let long = dict.take("long").ok().map(|v| v.cast()).transpose()?;
+        let description = dict.take("description").ok().map(|v| v.cast()).transpose()?;
+        dict.finish(&["key", "short", "long", "description"])?;
+        Self { key, short, long, description }
+    },
+}
+
+impl From<GlossaryEntry> for Dict {
+    fn from(entry: GlossaryEntry) -> Self {
+        let mut dict = dict! {
+            "key" => entry.key.resolve().as_str(),
+            "short" => entry.short,
+        };
+        if let Some(long) = entry.long {
+            dict.insert("long".into(), long.into_value());
+        }
+        if let Some(description) = entry.description {
+            dict.insert("description".into(), description.into_value());
+        }
+        dict
+    }
+}
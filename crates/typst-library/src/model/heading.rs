@@ -204,6 +204,23 @@ pub struct HeadingElem {
     #[default(Smart::Auto)]
     pub bookmarked: Smart<bool>,
 
+    /// A short version of the heading's title.
+    ///
+    /// When set, it is used instead of the full title in the [outline] and in
+    /// the exported PDF's bookmarks. It is also available as a field for
+    /// running heads in a page [header]($page.header). When this is `{none}`,
+    /// the full title is used everywhere.
+    ///
+    /// ```example
+    /// #outline()
+    ///
+    /// #heading(short: [Introduction])[
+    ///   An Introduction to the Many
+    ///   Wonders of Typesetting
+    /// ]
+    /// ```
+    pub short: Option<Content>,
+
     /// The indent all but the first line of a heading should have.
     ///
     /// The default value of `{auto}` uses the width of the numbering as indent
@@ -226,6 +243,14 @@ pub struct HeadingElem {
 }
 
 impl HeadingElem {
+    /// The short title if there is one and the full title otherwise.
+    pub fn short_or_body(&self) -> &Content {
+        self.short
+            .get_ref(StyleChain::default())
+            .as_ref()
+            .unwrap_or(&self.body)
+    }
+
     pub fn resolve_level(&self, styles: StyleChain) -> NonZeroUsize {
         self.level.get(styles).unwrap_or_else(|| {
             NonZeroUsize::new(self.offset.get(styles) + self.depth.get(styles).get())
@@ -330,7 +355,7 @@ impl Outlinable for Packed<HeadingElem> {
     }
 
     fn body(&self) -> Content {
-        self.body.clone()
+        self.short_or_body().clone()
     }
 }
 
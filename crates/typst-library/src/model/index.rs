@@ -0,0 +1,443 @@
+use std::cmp::Ordering;
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+
+use ecow::EcoString;
+use smallvec::{SmallVec, smallvec};
+use typst_syntax::Span;
+use typst_utils::NonZeroExt;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{
+    Content, NativeElement, Packed, ShowSet, Smart, StyleChain, Styles, elem,
+};
+use crate::introspection::{Counter, CounterKey, Locatable, Location};
+use crate::layout::{BlockBody, BlockElem, Em, Length, Sides};
+use crate::model::{
+    Destination, DirectLinkElem, EmphElem, HeadingElem, NumberingPattern, ParElem,
+    StrongElem,
+};
+use crate::text::{Lang, LocalName, TextElem, localized_str};
+
+/// Marks a term for the back-of-book index.
+///
+/// The marker itself is invisible. It records the term at its position in
+/// the document so that the [`make-index`] element can list the pages on
+/// which the term occurs.
+///
+/// # Example
+/// ```example
+/// >>> #set page(width: 200pt)
+/// = Layout
+/// Typst lays out text in
+/// paragraphs. #index("Paragraph")
+///
+/// Lines are broken with the Knuth-Plass
+/// algorithm. #index("Line breaking",
+///   sub: "Knuth-Plass",
+///   see-also: ("Paragraph",),
+/// )
+///
+/// #make-index()
+/// ```
+#[elem(Locatable)]
+pub struct IndexElem {
+    /// The term under which the marker's page is listed.
+    #[required]
+    pub term: EcoString,
+
+    /// A sub-entry of the term. If set, the page is listed below the term
+    /// under this sub-entry instead of next to the term itself.
+    pub sub: Option<EcoString>,
+
+    /// Other terms the reader could also look up. They are listed after the
+    /// pages of the entry.
+    pub see_also: Vec<EcoString>,
+
+    /// The key by which the term is sorted. Defaults to the term itself.
+    ///
+    /// This is useful for terms that should be sorted differently than they
+    /// are written, for example `{index("∞", key: "infinity")}`.
+    pub key: Option<EcoString>,
+}
+
+/// A back-of-book index of all terms marked with [`index`].
+///
+/// The terms are sorted alphabetically according to the conventions of the
+/// [text language]($text.lang) and grouped by their initial letter.
+/// Occurrences of a term on consecutive pages are merged into a page range.
+/// Each page number links to the first marker of the term on that page.
+///
+/// # Example
+/// ```example
+/// >>> #set page(width: 200pt)
+/// #index("Typst")
+/// #index("Markup", sub: "Headings")
+/// #index("Markup", sub: "Lists")
+///
+/// #make-index(title: [Terms])
+/// ```
+#[elem(ShowSet, LocalName, Locatable)]
+pub struct MakeIndexElem {
+    /// The title of the index.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the
+    ///   [text language]($text.lang) will be used.
+    /// - When set to `{none}`, the index will not have a title.
+    /// - A custom title can be set by passing content.
+    pub title: Smart<Option<Content>>,
+
+    /// How far sub-entries are indented.
+    #[default(Em::new(1.0).into())]
+    pub indent: Length,
+}
+
+impl Packed<MakeIndexElem> {
+    /// Produces the heading for the index, if any.
+    pub fn realize_title(&self, styles: StyleChain) -> Option<Content> {
+        let span = self.span();
+        self.title
+            .get_cloned(styles)
+            .unwrap_or_else(|| {
+                Some(
+                    TextElem::packed(Packed::<MakeIndexElem>::local_name_in(styles))
+                        .spanned(span),
+                )
+            })
+            .map(|title| {
+                HeadingElem::new(title)
+                    .with_depth(NonZeroUsize::ONE)
+                    .pack()
+                    .spanned(span)
+            })
+    }
+
+    /// Realizes the index with its title.
+    ///
+    /// If `pages` is true, the pages on which a term occurs are listed after
+    /// it. Otherwise, the term itself links to its first occurrence.
+    pub fn realize(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        pages: bool,
+    ) -> SourceResult<Content> {
+        let span = self.span();
+        let lang = styles.get(TextElem::lang);
+        let indent = self.indent.get(styles);
+
+        let mut seq = vec![];
+        seq.extend(self.realize_title(styles));
+
+        let mut group = None;
+        for entry in IndexEntry::collect(engine, lang) {
+            let initial = initial(lang, &entry.key);
+            if initial != group {
+                group = initial;
+                if let Some(c) = initial {
+                    let letter = StrongElem::new(TextElem::packed(c)).pack();
+                    seq.push(block(letter, None, styles, span));
+                }
+            }
+
+            seq.push(entry.realize(engine, styles, span, None, pages)?);
+            for sub in &entry.subs {
+                seq.push(sub.realize(engine, styles, span, Some(indent), pages)?);
+            }
+        }
+
+        Ok(Content::sequence(seq))
+    }
+}
+
+impl ShowSet for Packed<MakeIndexElem> {
+    fn show_set(&self, styles: StyleChain) -> Styles {
+        let mut out = Styles::new();
+        out.set(HeadingElem::outlined, false);
+        out.set(HeadingElem::numbering, None);
+        out.set(ParElem::justify, false);
+        out.set(BlockElem::above, Smart::Custom(styles.get(ParElem::leading).into()));
+        out
+    }
+}
+
+impl LocalName for Packed<MakeIndexElem> {
+    const KEY: &'static str = "index";
+}
+
+/// A term in the index along with the places where it occurs.
+#[derive(Debug)]
+struct IndexEntry {
+    /// The term as it is displayed.
+    term: EcoString,
+    /// The key by which the term is sorted.
+    key: EcoString,
+    /// The locations of the term's markers in document order.
+    locs: Vec<Location>,
+    /// Terms the reader could also look up.
+    see_also: Vec<EcoString>,
+    /// The sub-entries of the term.
+    subs: Vec<IndexEntry>,
+}
+
+impl IndexEntry {
+    /// Collects all index markers into sorted entries.
+    fn collect(engine: &mut Engine, lang: Lang) -> Vec<Self> {
+        let mut entries = vec![];
+        for marker in engine.introspector.query(&IndexElem::ELEM.select()) {
+            let Some(loc) = marker.location() else { continue };
+            let marker = marker.to_packed::<IndexElem>().unwrap();
+            let styles = StyleChain::default();
+
+            let mut entry = Self::find(&mut entries, &marker.term);
+            if let Some(key) = marker.key.get_ref(styles) {
+                entry.key = key.clone();
+            }
+
+            if let Some(sub) = marker.sub.get_ref(styles) {
+                entry = Self::find(&mut entry.subs, sub);
+            }
+
+            entry.locs.push(loc);
+            for term in marker.see_also.get_ref(styles) {
+                if !entry.see_also.contains(term) {
+                    entry.see_also.push(term.clone());
+                }
+            }
+        }
+
+        Self::sort(&mut entries, lang);
+        entries
+    }
+
+    /// Finds the entry for a term or creates it.
+    fn find<'a>(entries: &'a mut Vec<Self>, term: &EcoString) -> &'a mut Self {
+        let i = match entries.iter().position(|entry| entry.term == *term) {
+            Some(i) => i,
+            None => {
+                entries.push(Self {
+                    term: term.clone(),
+                    key: term.clone(),
+                    locs: vec![],
+                    see_also: vec![],
+                    subs: vec![],
+                });
+                entries.len() - 1
+            }
+        };
+        &mut entries[i]
+    }
+
+    /// Sorts the entries and their sub-entries.
+    fn sort(entries: &mut [Self], lang: Lang) {
+        entries.sort_by(|a, b| {
+            collate(lang, &a.key, &b.key).then_with(|| a.term.cmp(&b.term))
+        });
+        for entry in entries {
+            Self::sort(&mut entry.subs, lang);
+        }
+    }
+
+    /// Produces the block for the entry, without its sub-entries.
+    fn realize(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        span: Span,
+        indent: Option<Length>,
+        pages: bool,
+    ) -> SourceResult<Content> {
+        let mut term = TextElem::packed(self.term.clone());
+        if !pages && let Some(&loc) = self.locs.first() {
+            term = DirectLinkElem::new(loc, term, None).pack();
+        }
+
+        let mut seq = vec![term];
+        if pages {
+            for range in page_ranges(engine, styles, &self.locs)? {
+                seq.push(TextElem::packed(", "));
+                seq.push(range);
+            }
+        }
+
+        if !self.see_also.is_empty() {
+            let see_also = localized_str(
+                styles.get(TextElem::lang),
+                styles.get(TextElem::region),
+                "see-also",
+            );
+            seq.push(TextElem::packed("; "));
+            seq.push(EmphElem::new(TextElem::packed(see_also)).pack());
+            seq.push(TextElem::packed(" "));
+            seq.push(TextElem::packed(self.see_also.join(", ")));
+        }
+
+        Ok(block(Content::sequence(seq), indent, styles, span))
+    }
+}
+
+/// Produces the linked page numbers of the given locations, merging
+/// consecutive pages into ranges.
+pub(crate) fn page_ranges(
+    engine: &mut Engine,
+    styles: StyleChain,
+    locs: &[Location],
+) -> SourceResult<Vec<Content>> {
+    // Keep only the first location on each page. The sort is stable, so this
+    // is the first location in document order.
+    let mut pages: Vec<_> =
+        locs.iter().map(|&loc| (engine.introspector.page(loc), loc)).collect();
+    pages.sort_by_key(|&(page, _)| page);
+    pages.dedup_by_key(|&mut (page, _)| page);
+
+    let numbers: Vec<_> = pages.iter().map(|(page, _)| page.get()).collect();
+    let mut out = vec![];
+    for (i, j) in runs(&numbers) {
+        let mut range = page_number(engine, styles, pages[i].1)?;
+        if j > i {
+            range += TextElem::packed("–");
+            range += page_number(engine, styles, pages[j].1)?;
+        }
+        out.push(range);
+    }
+
+    Ok(out)
+}
+
+/// Splits sorted, distinct page numbers into runs of consecutive pages and
+/// returns the indices of the first and last page of each run.
+fn runs(pages: &[usize]) -> Vec<(usize, usize)> {
+    let mut out = vec![];
+    let mut i = 0;
+    while i < pages.len() {
+        let mut j = i;
+        while j + 1 < pages.len() && pages[j + 1] == pages[j] + 1 {
+            j += 1;
+        }
+        out.push((i, j));
+        i = j + 1;
+    }
+    out
+}
+
+/// Displays the number of the page a location is on, linked to the location.
+fn page_number(
+    engine: &mut Engine,
+    styles: StyleChain,
+    loc: Location,
+) -> SourceResult<Content> {
+    let numbering = engine
+        .introspector
+        .page_numbering(loc)
+        .cloned()
+        .unwrap_or_else(|| NumberingPattern::from_str("1").unwrap().into());
+    let number =
+        Counter::new(CounterKey::Page).display_at_loc(engine, loc, styles, &numbering)?;
+    Ok(number.linked(Destination::Location(loc), None))
+}
+
+/// Wraps a line of the index into a block, optionally indented.
+fn block(
+    body: Content,
+    indent: Option<Length>,
+    styles: StyleChain,
+    span: Span,
+) -> Content {
+    let mut block = BlockElem::new().with_body(Some(BlockBody::Content(body)));
+    if let Some(indent) = indent {
+        let inset = Sides::default()
+            .with(styles.resolve(TextElem::dir).start(), Some(indent.into()));
+        block = block.with_inset(inset);
+    }
+    block.pack().spanned(span)
+}
+
+/// Compares two sort keys in the given language.
+///
+/// Letters are compared without regard to case and diacritics, except for
+/// letters that the language treats as separate letters sorting after `z`.
+/// Keys that are equal in this regard are ordered by their exact text.
+fn collate(lang: Lang, a: &str, b: &str) -> Ordering {
+    collation_key(lang, a)
+        .cmp(collation_key(lang, b))
+        .then_with(|| a.cmp(b))
+}
+
+/// Maps a sort key to a sequence of comparable weights.
+fn collation_key(lang: Lang, text: &str) -> impl Iterator<Item = u32> + '_ {
+    text.chars().flat_map(move |c| {
+        let weights: SmallVec<[u32; 2]> = match tailoring(lang, c) {
+            Some(weight) => smallvec![weight],
+            None => c
+                .to_lowercase()
+                .nfd()
+                .filter(|&c| !is_combining_mark(c))
+                .map(u32::from)
+                .collect(),
+        };
+        weights
+    })
+}
+
+/// Returns a weight after `z` for letters that the language sorts at the end
+/// of the alphabet.
+fn tailoring(lang: Lang, c: char) -> Option<u32> {
+    let letters: &[&[char]] = match lang {
+        Lang::SWEDISH | Lang::FINNISH => {
+            &[&['å', 'Å'], &['ä', 'Ä', 'æ', 'Æ'], &['ö', 'Ö', 'ø', 'Ø']]
+        }
+        Lang::DANISH
+        | Lang::NORWEGIAN
+        | Lang::NORWEGIAN_BOKMAL
+        | Lang::NORWEGIAN_NYNORSK => {
+            &[&['æ', 'Æ', 'ä', 'Ä'], &['ø', 'Ø', 'ö', 'Ö'], &['å', 'Å']]
+        }
+        _ => return None,
+    };
+
+    letters
+        .iter()
+        .position(|group| group.contains(&c))
+        .map(|i| u32::from('z') + 1 + i as u32)
+}
+
+/// The letter under which a term is grouped, if its sort key starts with a
+/// letter.
+fn initial(lang: Lang, key: &str) -> Option<char> {
+    let c = key.chars().next().filter(|c| c.is_alphabetic())?;
+    if tailoring(lang, c).is_some() {
+        return c.to_uppercase().next();
+    }
+    c.nfd().next()?.to_uppercase().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_runs() {
+        assert_eq!(runs(&[]), vec![]);
+        assert_eq!(runs(&[4]), vec![(0, 0)]);
+        assert_eq!(runs(&[1, 2, 3, 5, 7, 8]), vec![(0, 2), (3, 3), (4, 5)]);
+    }
+
+    #[test]
+    fn test_collate() {
+        assert_eq!(collate(Lang::ENGLISH, "Äpfel", "apple"), Ordering::Less);
+        assert_eq!(collate(Lang::ENGLISH, "apple", "Apple"), Ordering::Greater);
+        assert_eq!(collate(Lang::ENGLISH, "Äpfel", "Zebra"), Ordering::Less);
+        assert_eq!(collate(Lang::SWEDISH, "Äpfel", "Zebra"), Ordering::Greater);
+        assert_eq!(collate(Lang::SWEDISH, "Äpfel", "Öl"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_initial() {
+        assert_eq!(initial(Lang::ENGLISH, "äpfel"), Some('A'));
+        assert_eq!(initial(Lang::SWEDISH, "äpfel"), Some('Ä'));
+        assert_eq!(initial(Lang::ENGLISH, "∞"), None);
+    }
+}
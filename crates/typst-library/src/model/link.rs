@@ -1,14 +1,16 @@
+use std::num::NonZeroUsize;
 use std::ops::Deref;
 use std::str::FromStr;
 
 use comemo::Tracked;
 use ecow::{EcoString, eco_format};
+use typst_syntax::Spanned;
 
-use crate::diag::{SourceResult, StrResult, bail};
+use crate::diag::{At, SourceResult, StrResult, bail};
 use crate::engine::Engine;
 use crate::foundations::{
-    Args, Construct, Content, Label, Packed, Repr, Selector, ShowSet, Smart, StyleChain,
-    Styles, cast, elem,
+    Args, Construct, Content, Dict, IntoValue, Label, Packed, Repr, Selector, ShowSet,
+    Smart, StyleChain, Styles, Value, cast, elem,
 };
 use crate::introspection::{
     Counter, CounterKey, Introspector, Locatable, Location, Tagged,
@@ -49,6 +51,24 @@ use crate::text::{LocalName, TextElem};
 /// automatically generate a tooltip description for links based on their
 /// destination. For links to URLs, the URL itself will be used as the tooltip.
 ///
+/// # Links to other files
+/// In PDF export, a link can also lead into another PDF file, for example to
+/// cross-reference the volumes of a manual. Pass the file's path as `file` and,
+/// optionally, where to go in it as `dest`: either a [label], which Typst
+/// exports as a named destination, the name of a destination as a string, or
+/// a page number starting at one.
+///
+/// ```typ
+/// See the #link(file: "volume-2.pdf", dest: <setup>)[setup chapter] in
+/// volume 2.
+/// ```
+///
+/// If the file is [attached]($pdf.attach) to the document under the same path,
+/// the link opens the attached file. Otherwise, the path is interpreted
+/// relative to the exported PDF. In HTML and SVG export, the link becomes a
+/// regular hyperlink to the file, with the destination expressed as a URL
+/// fragment that browsers' PDF viewers understand.
+///
 /// # Links in HTML export
 /// In HTML export, a link to a [label] or [location] will be turned into a
 /// fragment link to a named anchor point. To support this, targets without an
@@ -127,7 +147,17 @@ pub struct LinkElem {
     /// ```
     #[required]
     #[parse(
-        let dest = args.expect::<LinkTarget>("destination")?;
+        let dest = match args.named::<Spanned<EcoString>>("file")? {
+            Some(Spanned { v: path, span }) => {
+                let target = match args.named("dest")? {
+                    Some(target) => Some(target),
+                    None => args.find()?,
+                };
+                let link = FileLink::new(path, target).at(span)?;
+                LinkTarget::Dest(Destination::File(link))
+            }
+            None => args.expect::<LinkTarget>("destination")?,
+        };
         dest.clone()
     )]
     pub dest: LinkTarget,
@@ -146,6 +176,11 @@ pub struct LinkElem {
     })]
     pub body: Content,
 
+    /// The path of a PDF file to link into. See the section on [links to
+    /// other files](#links-to-other-files) for details.
+    #[external]
+    pub file: EcoString,
+
     /// A destination style that should be applied to elements.
     #[internal]
     #[ghost]
@@ -218,6 +253,8 @@ pub enum Destination {
     Position(Position),
     /// An unresolved link to a location in the document.
     Location(Location),
+    /// A link into another file.
+    File(FileLink),
 }
 
 impl Destination {
@@ -276,6 +313,7 @@ impl Destination {
 
                 fallback(engine)
             }
+            Destination::File(link) => Ok(link.path.clone()),
         }
     }
 }
@@ -292,12 +330,72 @@ cast! {
         Self::Url(v) => v.into_value(),
         Self::Position(v) => v.into_value(),
         Self::Location(v) => v.into_value(),
+        Self::File(v) => v.into_value(),
     },
     v: Url => Self::Url(v),
     v: Position => Self::Position(v),
     v: Location => Self::Location(v),
 }
 
+/// A link into another file.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FileLink {
+    /// The path of the file.
+    pub path: EcoString,
+    /// Where to go in the file. If `None`, the file is opened at its start.
+    pub target: Option<FileTarget>,
+}
+
+impl FileLink {
+    /// Create a link into a file, checking that the path is not empty.
+    pub fn new(path: EcoString, target: Option<FileTarget>) -> StrResult<Self> {
+        if path.is_empty() {
+            bail!("file path must not be empty");
+        }
+        Ok(Self { path, target })
+    }
+
+    /// A URL for the link, expressing the target as a PDF open parameter.
+    pub fn href(&self) -> EcoString {
+        match &self.target {
+            Some(FileTarget::Name(name)) => eco_format!("{}#nameddest={name}", self.path),
+            Some(FileTarget::Page(page)) => eco_format!("{}#page={page}", self.path),
+            None => self.path.clone(),
+        }
+    }
+}
+
+impl IntoValue for FileLink {
+    fn into_value(self) -> Value {
+        let mut dict = Dict::new();
+        dict.insert("file".into(), self.path.into_value());
+        if let Some(target) = self.target {
+            dict.insert("dest".into(), target.into_value());
+        }
+        Value::Dict(dict)
+    }
+}
+
+/// A place in another file.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum FileTarget {
+    /// A named destination.
+    Name(EcoString),
+    /// A page, starting at one.
+    Page(NonZeroUsize),
+}
+
+cast! {
+    FileTarget,
+    self => match self {
+        Self::Name(v) => v.into_value(),
+        Self::Page(v) => v.into_value(),
+    },
+    v: Label => Self::Name(v.resolve().as_str().into()),
+    v: NonZeroUsize => Self::Page(v),
+    v: EcoString => Self::Name(v),
+}
+
 /// A uniform resource locator with a maximum length.
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Url(EcoString);
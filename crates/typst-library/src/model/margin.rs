@@ -0,0 +1,54 @@
+use crate::foundations::{Content, elem};
+use crate::introspection::Locatable;
+use crate::layout::{Em, Length};
+
+/// A note in the page margin.
+///
+/// The note is displayed in the outer margin of the page, next to the line in
+/// which it occurs. Which margin is the outer one depends on the page's
+/// [binding]($page.binding): For left-bound documents, it is the right margin.
+/// With [two-sided]($page.margin) margins, the outer margin alternates
+/// between the right margin on odd pages and the left margin on even pages.
+///
+/// When multiple notes on the same page would overlap, they are stacked below
+/// each other. If the stack would run off the bottom of the page's text area,
+/// the notes are moved up as far as necessary.
+///
+/// # Example
+/// ```example
+/// >>> #set page(width: 220pt, margin: (right: 80pt))
+/// Typst is a markup-based typesetting
+/// system. #margin-note[Like LaTeX.]
+/// It is designed to be as powerful
+/// as the tools it replaces while
+/// being much easier to learn and use.
+/// ```
+///
+/// Margin notes are only displayed for content in the page's main text area.
+/// In particular, notes in headers, footers, and page backgrounds are ignored.
+///
+/// _Note:_ Like for [footnotes]($footnote), set and show rules in the scope
+/// where `margin-note` is called may not apply to the note's content. Styles
+/// that are active for the whole page apply.
+#[elem(Locatable)]
+pub struct MarginNoteElem {
+    /// The horizontal distance between the note and the text area. The same
+    /// distance is also kept to the edge of the page.
+    ///
+    /// ```example
+    /// >>> #set page(width: 220pt, margin: (right: 80pt))
+    /// #set margin-note(gap: 4pt)
+    /// Notes can be placed closer
+    /// to the text. #margin-note[Near.]
+    /// ```
+    #[default(Em::new(1.0).into())]
+    pub gap: Length,
+
+    /// The minimum vertical spacing between two notes.
+    #[default(Em::new(0.65).into())]
+    pub spacing: Length,
+
+    /// The content of the note.
+    #[required]
+    pub body: Content,
+}
@@ -8,9 +8,12 @@ mod emph;
 mod enum_;
 mod figure;
 mod footnote;
+mod glossary;
 mod heading;
+mod index;
 mod link;
 mod list;
+mod margin;
 #[path = "numbering.rs"]
 mod numbering_;
 mod outline;
@@ -29,9 +32,12 @@ pub use self::emph::*;
 pub use self::enum_::*;
 pub use self::figure::*;
 pub use self::footnote::*;
+pub use self::glossary::*;
 pub use self::heading::*;
+pub use self::index::*;
 pub use self::link::*;
 pub use self::list::*;
+pub use self::margin::*;
 pub use self::numbering_::*;
 pub use self::outline::*;
 pub use self::par::*;
@@ -61,10 +67,14 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<FigureElem>();
     global.define_elem::<QuoteElem>();
     global.define_elem::<FootnoteElem>();
+    global.define_elem::<MarginNoteElem>();
     global.define_elem::<OutlineElem>();
+    global.define_elem::<IndexElem>();
+    global.define_elem::<MakeIndexElem>();
     global.define_elem::<RefElem>();
     global.define_elem::<CiteElem>();
     global.define_elem::<BibliographyElem>();
+    global.define_elem::<GlossaryElem>();
     global.define_elem::<TableElem>();
     global.define_func::<numbering>();
     global.reset_category();
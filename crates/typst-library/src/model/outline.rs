@@ -658,8 +658,9 @@ impl OutlineEntry {
 
     /// The content which is displayed in place of the referred element at its
     /// entry in the outline. For a heading, this is its
-    /// [`body`]($heading.body); for a figure a caption and for equations, it is
-    /// empty.
+    /// [`short`]($heading.short) title or, if there is none, its
+    /// [`body`]($heading.body); for a figure the short or full caption and for
+    /// equations, it is empty.
     #[func]
     pub fn body(&self) -> StrResult<Content> {
         Ok(self.outlinable()?.body())
@@ -1,3 +1,5 @@
+use std::num::NonZeroUsize;
+
 use ecow::eco_format;
 use typst_utils::singleton;
 
@@ -423,6 +425,39 @@ pub struct ParElem {
     /// ```
     pub hanging_indent: Length,
 
+    /// The minimum number of lines of a paragraph that must stay together at
+    /// the bottom of a page or column.
+    ///
+    /// If fewer lines would fit before a break, they are moved to the next
+    /// page or column along with the rest of the paragraph. This only has an
+    /// effect if the [`orphan` cost]($text.costs) is non-zero.
+    ///
+    /// ```example
+    /// >>> #set page(height: 80pt)
+    /// #set par(orphans: 3)
+    /// #block(height: 30pt)
+    /// #lorem(20)
+    /// ```
+    #[default(NonZeroUsize::new(2).unwrap())]
+    pub orphans: NonZeroUsize,
+
+    /// The minimum number of lines of a paragraph that must stay together at
+    /// the top of a page or column.
+    ///
+    /// If fewer lines would be left over after a break, the break happens
+    /// earlier. A paragraph that is too short to leave enough lines on both
+    /// sides of a break is moved as a whole. This only has an effect if the
+    /// [`widow` cost]($text.costs) is non-zero.
+    ///
+    /// ```example
+    /// >>> #set page(height: 80pt)
+    /// #set par(widows: 3)
+    /// #block(height: 20pt)
+    /// #lorem(20)
+    /// ```
+    #[default(NonZeroUsize::new(2).unwrap())]
+    pub widows: NonZeroUsize,
+
     /// The contents of the paragraph.
     #[required]
     pub body: Content,
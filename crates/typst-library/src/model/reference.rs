@@ -10,7 +10,8 @@ use crate::foundations::{
 use crate::introspection::{Counter, CounterKey, Locatable, Tagged};
 use crate::math::EquationElem;
 use crate::model::{
-    BibliographyElem, CiteElem, DirectLinkElem, Figurable, FootnoteElem, Numbering,
+    BibliographyElem, CiteElem, DirectLinkElem, Figurable, FootnoteElem, GlossaryElem,
+    Numbering,
 };
 use crate::text::TextElem;
 
@@ -140,7 +141,7 @@ pub struct RefElem {
     ///
     /// Can be a label that is defined in the document or, if the
     /// [`form`]($ref.form) is set to `["normal"]`, an entry from the
-    /// [`bibliography`].
+    /// [`bibliography`] or the [`glossary`].
     #[required]
     pub target: Label,
 
@@ -273,6 +274,21 @@ impl Packed<RefElem> {
             return Ok(to_citation(self, engine, styles)?.pack().spanned(span));
         }
 
+        if let Some((glossary, entry)) = GlossaryElem::find(engine, self.target) {
+            if let Ok(elem) = elem {
+                bail!(
+                    span,
+                    "label `{}` occurs both in the document and its glossary",
+                    self.target.repr();
+                    hint: "change either the {}'s label or the \
+                           glossary key to resolve the ambiguity",
+                    elem.func().name(),
+                );
+            }
+
+            return entry.realize_ref(self, engine, &glossary);
+        }
+
         let elem = elem.at(span)?;
 
         if let Some(footnote) = elem.to_packed::<FootnoteElem>() {
@@ -0,0 +1,79 @@
+use ecow::EcoString;
+
+use crate::foundations::{Cast, Content, Datetime, elem};
+use crate::introspection::Locatable;
+use crate::visualize::Color;
+
+/// A comment in the PDF, as used in review workflows.
+///
+/// Annotations are shown by PDF readers on top of the page, typically along
+/// with a list of all comments in the document. They do not affect the layout
+/// and are not visible in other export formats.
+///
+/// # Example
+/// ```typ
+/// #pdf.annotation(
+///   "Should we cite the original study here?",
+///   author: "Reviewer 2",
+/// )
+/// The experiment was
+/// #pdf.annotation(
+///   "Not quite, see table 3.",
+///   kind: "highlight",
+///   color: rgb("#fbbf24"),
+/// )[highly successful].
+/// ```
+///
+/// # Review tooling
+/// Annotations can be listed with `typst query`, for example to track open
+/// review comments:
+///
+/// ```sh
+/// $ typst query example.typ "pdf.annotation" --field contents
+/// ```
+///
+/// # Notes
+/// - Annotations are currently not supported with PDF/A, PDF/UA, and PDF/X.
+/// - The annotations use a standard font. PDF readers may not be able to
+///   display all characters in it.
+#[elem(Locatable)]
+pub struct AnnotationElem {
+    /// The text of the comment.
+    #[required]
+    pub contents: EcoString,
+
+    /// What kind of annotation to create.
+    #[default(AnnotationKind::Note)]
+    pub kind: AnnotationKind,
+
+    /// The author of the comment.
+    pub author: Option<EcoString>,
+
+    /// The date of the comment.
+    pub date: Option<Datetime>,
+
+    /// The color of the annotation.
+    #[default(Color::from_u8(0xff, 0xeb, 0x3b, 0xff))]
+    pub color: Color,
+
+    /// The content the annotation refers to.
+    ///
+    /// Notes are placed at the start of the content, highlights cover it, and
+    /// free text annotations are shown in a box over it. Without content, the
+    /// annotation is placed where the element is and free text annotations
+    /// get a box of default size.
+    #[positional]
+    pub body: Option<Content>,
+}
+
+/// The kind of an annotation.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum AnnotationKind {
+    /// A sticky note, shown as an icon that reveals the comment when clicked.
+    #[default]
+    Note,
+    /// A highlight over the annotation's body with the comment attached.
+    Highlight,
+    /// A comment that is shown directly on the page.
+    FreeText,
+}
@@ -0,0 +1,122 @@
+use ecow::EcoString;
+
+use crate::foundations::{Cast, Smart, elem};
+use crate::introspection::{Locatable, Location};
+use crate::layout::{Length, Rel};
+
+/// An interactive form field in the PDF.
+///
+/// The field is laid out as a box within the text. When exporting to PDF, it
+/// becomes a fillable widget that PDF readers let you type into, check, or
+/// pick an option from. In other export formats, only the box with the
+/// field's default value is shown.
+///
+/// # Example
+/// ```typ
+/// Name: #pdf.form-field("name", tooltip: "Your full name") \
+/// Newsletter: #pdf.form-field("newsletter", kind: "checkbox", checked: true) \
+/// Plan:
+/// #pdf.form-field("plan", kind: "radio", value: "basic", checked: true) Basic
+/// #pdf.form-field("plan", kind: "radio", value: "pro") Pro \
+/// Country: #pdf.form-field(
+///   "country",
+///   kind: "choice",
+///   options: ("Germany", "France", "Italy"),
+///   value: "France",
+/// )
+/// ```
+///
+/// # Names
+/// Each field is identified by its name. Fields with the same name share
+/// their value: Filling one of them fills all of them. This is how the buttons
+/// of a radio group are tied together. All fields with the same name must be
+/// of the same kind.
+///
+/// # Tab order
+/// By default, PDF readers visit the fields of a page in the order in which
+/// they appear in the document. Fields with a [`tab-index`]($pdf.form-field.tab-index)
+/// are visited first, in ascending order of their index.
+///
+/// # Notes
+/// - Form fields are currently not supported with PDF/A, PDF/UA, and PDF/X.
+/// - The fields use a standard font. PDF readers may not be able to display
+///   all characters in it.
+#[elem(Locatable)]
+pub struct FormFieldElem {
+    /// The name of the field.
+    #[required]
+    pub name: EcoString,
+
+    /// What kind of field to create.
+    #[default(FormFieldKind::Text)]
+    pub kind: FormFieldKind,
+
+    /// The width of the field.
+    ///
+    /// When set to `{auto}`, check boxes and radio buttons are `{1em}` wide,
+    /// signature fields `{12em}`, and all other fields `{8em}`.
+    pub width: Smart<Rel<Length>>,
+
+    /// The height of the field.
+    ///
+    /// When set to `{auto}`, signature fields are `{3em}` high and all other
+    /// fields `{1em}`, or `{3em}` for multiline text fields and list boxes.
+    pub height: Smart<Rel<Length>>,
+
+    /// The value of the field.
+    ///
+    /// - For text fields, this is the text the field is prefilled with.
+    /// - For choice fields, this is the option that is selected by default.
+    /// - For check boxes and radio buttons, this is the value the field takes
+    ///   when the button is checked. It must be unique within a radio group.
+    pub value: Option<EcoString>,
+
+    /// Whether a check box or radio button is checked by default.
+    #[default(false)]
+    pub checked: bool,
+
+    /// The options a choice field offers.
+    pub options: Vec<EcoString>,
+
+    /// Whether a text field accepts multiple lines of text. For choice fields,
+    /// this shows the options in a list box instead of a dropdown.
+    #[default(false)]
+    pub multiline: bool,
+
+    /// A description of the field, shown as a tooltip by PDF readers.
+    pub tooltip: Option<EcoString>,
+
+    /// The position of the field in the tab order of its page.
+    pub tab_index: Option<usize>,
+
+    /// Whether the field must be filled in before the form can be submitted.
+    #[default(false)]
+    pub required: bool,
+
+    /// Whether the field's value cannot be changed.
+    #[default(false)]
+    pub read_only: bool,
+
+    /// The location of the form field whose widget the content is.
+    #[internal]
+    #[ghost]
+    pub widget: Option<Location>,
+}
+
+/// The kind of a form field.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum FormFieldKind {
+    /// A text input.
+    #[default]
+    Text,
+    /// A check box that can be on or off.
+    Checkbox,
+    /// A radio button. Radio buttons with the same name form a group of which
+    /// only one can be checked.
+    Radio,
+    /// A dropdown or list of options to choose from.
+    Choice,
+    /// A placeholder for a digital signature. When the PDF is signed during
+    /// export, the signature is stored in the first signature field.
+    Signature,
+}
@@ -0,0 +1,54 @@
+use ecow::EcoString;
+
+use crate::foundations::{Content, Packed, Smart, elem};
+use crate::introspection::Locatable;
+
+/// A layer of content whose visibility can be toggled in a PDF reader.
+///
+/// Layers are useful for content that readers should be able to show or hide,
+/// such as the solutions of a worksheet or the annotations of a drawing. The
+/// content of a layer takes up space whether it is visible or not.
+///
+/// # Example
+/// ```typ
+/// What is the capital of France?
+/// #pdf.layer("Solutions", visible: false)[*Paris*]
+/// ```
+///
+/// # Names
+/// Layers are identified by their name. All layers with the same name are
+/// shown or hidden together. In PDF export, the first layer with a name
+/// determines whether they are visible and printed by default.
+///
+/// # Notes
+/// - In PDF export, each layer name becomes an optional content group that
+///   readers list in their layer panel.
+/// - PDF 1.4 does not support optional content, and PDF/A and PDF/X restrict
+///   it. When exporting to one of these standards, a layer is simply shown or
+///   hidden according to its [`visible`]($pdf.layer.visible) field.
+/// - The same holds for PNG, SVG, and HTML export.
+#[elem(Locatable)]
+pub struct LayerElem {
+    /// The name of the layer, shown in the layer panel of PDF readers.
+    #[required]
+    pub name: EcoString,
+
+    /// Whether the layer is visible by default.
+    #[default(true)]
+    pub visible: bool,
+
+    /// Whether the layer is printed.
+    ///
+    /// When set to `{auto}`, the layer is printed if it is visible. Only has an
+    /// effect in PDF export.
+    pub print: Smart<bool>,
+
+    /// The content of the layer.
+    #[required]
+    pub body: Content,
+
+    /// The layer that the content is currently in.
+    #[internal]
+    #[ghost]
+    pub current: Option<Packed<LayerElem>>,
+}
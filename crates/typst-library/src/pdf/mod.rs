@@ -1,10 +1,16 @@
 //! PDF-specific functionality.
 
 mod accessibility;
+mod annotation;
 mod attach;
+mod form;
+mod layer;
 
 pub use self::accessibility::*;
+pub use self::annotation::*;
 pub use self::attach::*;
+pub use self::form::*;
+pub use self::layer::*;
 
 use crate::foundations::{Deprecation, Element, Module, Scope};
 use crate::{Feature, Features};
@@ -21,6 +27,9 @@ pub fn module(features: &Features) -> Module {
             .with_until("0.15.0"),
     );
     pdf.define_elem::<ArtifactElem>();
+    pdf.define_elem::<FormFieldElem>();
+    pdf.define_elem::<LayerElem>();
+    pdf.define_elem::<AnnotationElem>();
     if features.is_enabled(Feature::A11yExtras) {
         pdf.define_func::<table_summary>();
         pdf.define_func::<header_cell>();
@@ -14,7 +14,7 @@ use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
 use std::sync::{Arc, OnceLock};
 
-use ttf_parser::{GlyphId, name_id};
+use ttf_parser::{GlyphId, Tag, name_id};
 
 use self::book::find_name;
 use crate::foundations::{Bytes, Cast};
@@ -134,6 +134,33 @@ impl Font {
         find_name(&self.0.ttf, id)
     }
 
+    /// What the font's license permits regarding embedding the font into a
+    /// document, as declared in the `fsType` field of its `OS/2` table.
+    pub fn embedding(&self) -> FontEmbedding {
+        let Some(fs_type) = self
+            .0
+            .ttf
+            .raw_face()
+            .table(Tag::from_bytes(b"OS/2"))
+            .and_then(|os2| os2.get(8..10))
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+        else {
+            return FontEmbedding::Installable;
+        };
+
+        // If multiple of the usage bits are set, the least restrictive one
+        // applies.
+        if fs_type & 0x000E == 0x0002 {
+            FontEmbedding::Restricted
+        } else if fs_type & 0x0200 != 0 {
+            FontEmbedding::BitmapOnly
+        } else if fs_type & 0x0100 != 0 {
+            FontEmbedding::NoSubsetting
+        } else {
+            FontEmbedding::Installable
+        }
+    }
+
     /// A reference to the underlying `ttf-parser` face.
     pub fn ttf(&self) -> &ttf_parser::Face<'_> {
         // We can't implement Deref because that would leak the
@@ -212,6 +239,20 @@ impl PartialEq for Font {
     }
 }
 
+/// What a font's license permits regarding embedding the font into a
+/// document.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FontEmbedding {
+    /// The font may be embedded.
+    Installable,
+    /// The font may only be embedded in full, not as a subset.
+    NoSubsetting,
+    /// Only the bitmaps of the font may be embedded, not its outlines.
+    BitmapOnly,
+    /// The font must not be embedded at all.
+    Restricted,
+}
+
 /// Metrics of a font.
 #[derive(Debug, Clone)]
 pub struct FontMetrics {
@@ -555,7 +555,9 @@ pub struct TextElem {
     /// `widow` and `orphan` costs allow disabling these modifications.
     /// (Currently, `{0%}` allows widows/orphans; anything else, including the
     /// default of `{100%}`, prevents them. More nuanced cost specification for
-    /// these modifications is planned for the future.)
+    /// these modifications is planned for the future.) The number of lines
+    /// that must be kept together is configured through the paragraph's
+    /// [`orphans`]($par.orphans) and [`widows`]($par.widows) properties.
     ///
     /// ```example
     /// #set text(hyphenate: true, size: 11.4pt)
@@ -10,6 +10,7 @@ use palette::{
 };
 use qcms::Profile;
 use typst_syntax::{Span, Spanned};
+use typst_utils::PicoStr;
 
 use crate::diag::{At, SourceResult, StrResult, bail};
 use crate::foundations::{
@@ -207,6 +208,8 @@ pub enum Color {
     Hsl(Hsl),
     /// A 32-bit HSV color.
     Hsv(Hsv),
+    /// A spot color with a tint.
+    Spot(Spot),
 }
 
 #[scope]
@@ -580,6 +583,57 @@ impl Color {
         })
     }
 
+    /// Create a spot color.
+    ///
+    /// A spot color is printed with a dedicated ink instead of being mixed
+    /// from the process colors. This is how brand colors from color systems
+    /// like Pantone, metallic inks, or varnishes are reproduced. The ink is
+    /// identified by its name, and a CMYK fallback describes what it looks
+    /// like.
+    ///
+    /// A spot color behaves like a CMYK color: Its [space]($color.space) is
+    /// [`cmyk`]($color.cmyk), and its [components]($color.components) are
+    /// those of the fallback at the color's tint. Lightening and darkening a
+    /// spot color changes its tint, and mixing spot colors of the same ink
+    /// mixes their tints, so that gradients between them keep the ink. Other
+    /// operations, such as saturating or converting to another color space,
+    /// produce a regular color.
+    ///
+    /// In PDF export, spot colors are written as separation color spaces,
+    /// which print shops can output to a dedicated printing plate. This also
+    /// holds for gradients whose stops all use the same ink. Gradients that
+    /// mix inks or combine spot colors with other colors use the fallback. In
+    /// PNG, SVG, and HTML export, spot colors are displayed with their
+    /// fallback.
+    ///
+    /// ```example
+    /// #let blue = color.spot("PANTONE 286 C", cmyk(100%, 66%, 0%, 2%))
+    /// #square(fill: blue)
+    /// #square(fill: blue.lighten(50%))
+    /// ```
+    #[func]
+    pub fn spot(
+        /// The name of the ink.
+        name: Str,
+        /// The color of the ink at full tint. It is converted to CMYK.
+        fallback: Color,
+        /// How much of the ink to apply.
+        #[named]
+        #[default(RatioComponent(Ratio::one()))]
+        tint: RatioComponent,
+    ) -> StrResult<Color> {
+        if name.is_empty() {
+            bail!("spot color name must not be empty");
+        }
+
+        let RatioComponent(tint) = tint;
+        Ok(Self::Spot(Spot {
+            name: PicoStr::intern(&name),
+            tint: tint.get() as f32,
+            fallback: fallback.to_cmyk(),
+        }))
+    }
+
     /// Create an HSL color.
     ///
     /// This color space is useful for specifying colors by hue, saturation and
@@ -712,10 +766,11 @@ impl Color {
     /// | [`hsv`]($color.hsv)     |     Hue   | Saturation |   Value   |  Alpha |
     ///
     /// For the meaning and type of each individual value, see the documentation
-    /// of the corresponding color space. The alpha component is optional and
-    /// only included if the `alpha` argument is `true`. The length of the
-    /// returned array depends on the number of components and whether the alpha
-    /// component is included.
+    /// of the corresponding color space. [Spot colors]($color.spot) return the
+    /// CMYK components of their fallback at their tint. The alpha component is
+    /// optional and only included if the `alpha` argument is `true`. The length
+    /// of the returned array depends on the number of components and whether
+    /// the alpha component is included.
     ///
     /// ```example
     /// // note that the alpha component is included by default
@@ -789,9 +844,18 @@ impl Color {
                     Ratio::new(c.alpha.into()),
                 ]
             }
+            Self::Spot(c) => {
+                let c = c.to_cmyk();
+                array![
+                    Ratio::new(c.c.into()),
+                    Ratio::new(c.m.into()),
+                    Ratio::new(c.y.into()),
+                    Ratio::new(c.k.into())
+                ]
+            }
         };
         // Remove the alpha component if the corresponding argument was set.
-        if !alpha && !matches!(self, Self::Cmyk(_)) {
+        if !alpha && !matches!(self, Self::Cmyk(_) | Self::Spot(_)) {
             let _ = components.pop();
         }
         components
@@ -809,6 +873,8 @@ impl Color {
     /// - [`hsl`]($color.hsl)
     /// - [`hsv`]($color.hsv)
     ///
+    /// Spot colors return [`cmyk`]($color.cmyk), the space of their fallback.
+    ///
     /// ```example
     /// #let color = cmyk(1%, 2%, 3%, 4%)
     /// #(color.space() == cmyk)
@@ -824,6 +890,7 @@ impl Color {
             Self::Cmyk(_) => ColorSpace::Cmyk,
             Self::Hsl(_) => ColorSpace::Hsl,
             Self::Hsv(_) => ColorSpace::Hsv,
+            Self::Spot(_) => ColorSpace::Cmyk,
         }
     }
 
@@ -857,6 +924,7 @@ impl Color {
             Self::Cmyk(c) => Self::Cmyk(c.lighten(factor)),
             Self::Hsl(c) => Self::Hsl(c.lighten(factor)),
             Self::Hsv(c) => Self::Hsv(c.lighten(factor)),
+            Self::Spot(c) => Self::Spot(c.lighten(factor)),
         }
     }
 
@@ -877,6 +945,7 @@ impl Color {
             Self::Cmyk(c) => Self::Cmyk(c.darken(factor)),
             Self::Hsl(c) => Self::Hsl(c.darken(factor)),
             Self::Hsv(c) => Self::Hsv(c.darken(factor)),
+            Self::Spot(c) => Self::Spot(c.darken(factor)),
         }
     }
 
@@ -900,7 +969,8 @@ impl Color {
             | Self::Oklch(_)
             | Self::LinearRgb(_)
             | Self::Rgb(_)
-            | Self::Cmyk(_) => {
+            | Self::Cmyk(_)
+            | Self::Spot(_) => {
                 Color::Hsv(self.to_hsv().saturate(f)).to_space(self.space())
             }
         })
@@ -926,7 +996,8 @@ impl Color {
             | Self::Oklch(_)
             | Self::LinearRgb(_)
             | Self::Rgb(_)
-            | Self::Cmyk(_) => {
+            | Self::Cmyk(_)
+            | Self::Spot(_) => {
                 Color::Hsv(self.to_hsv().desaturate(f)).to_space(self.space())
             }
         })
@@ -979,6 +1050,7 @@ impl Color {
                 c.value,
                 c.alpha,
             )),
+            Self::Spot(_) => unreachable!("spot colors are not a color space"),
         };
         result.to_space(self.space())
     }
@@ -1022,6 +1094,9 @@ impl Color {
     /// can be mixed at once. Mixing more than two colors in such a space will
     /// result in an error!
     ///
+    /// Mixing [spot colors]($color.spot) of the same ink mixes their tints
+    /// regardless of the space and produces a spot color of that ink.
+    ///
     /// ```example
     /// #set block(height: 20pt, width: 100%)
     /// #block(fill: red.mix(blue))
@@ -1098,6 +1173,11 @@ impl Color {
         >,
         space: ColorSpace,
     ) -> StrResult<Color> {
+        let colors: Vec<WeightedColor> = colors.into_iter().collect();
+        if let Some(spot) = Spot::mix(&colors)? {
+            return Ok(Color::Spot(spot));
+        }
+
         let mut colors = colors.into_iter();
         if space.hue_index().is_some() && colors.len() > 2 {
             bail!("cannot mix more than two colors in a hue-based space");
@@ -1197,7 +1277,7 @@ impl Color {
     /// Returns the alpha channel of the color, if it has one.
     pub fn alpha(&self) -> Option<f32> {
         match self {
-            Color::Cmyk(_) => None,
+            Color::Cmyk(_) | Color::Spot(_) => None,
             Color::Luma(c) => Some(c.alpha),
             Color::Oklab(c) => Some(c.alpha),
             Color::Oklch(c) => Some(c.alpha),
@@ -1211,7 +1291,7 @@ impl Color {
     /// Sets the alpha channel of the color, if it has one.
     pub fn with_alpha(mut self, alpha: f32) -> Self {
         match &mut self {
-            Color::Cmyk(_) => {}
+            Color::Cmyk(_) | Color::Spot(_) => {}
             Color::Luma(c) => c.alpha = alpha,
             Color::Oklab(c) => c.alpha = alpha,
             Color::Oklch(c) => c.alpha = alpha,
@@ -1244,6 +1324,7 @@ impl Color {
             Color::Rgb(c) => Color::Rgb(transform(c, scale)),
             Color::LinearRgb(c) => Color::LinearRgb(transform(c, scale)),
             Color::Cmyk(_) => bail!("CMYK does not have an alpha component"),
+            Color::Spot(_) => bail!("spot colors do not have an alpha component"),
             Color::Hsl(c) => Color::Hsl(transform(c, scale)),
             Color::Hsv(c) => Color::Hsv(transform(c, scale)),
         })
@@ -1269,6 +1350,10 @@ impl Color {
             Color::Hsv(c) => {
                 [c.hue.into_degrees().rem_euclid(360.0), c.saturation, c.value, c.alpha]
             }
+            Color::Spot(c) => {
+                let c = c.to_cmyk();
+                [c.c, c.m, c.y, c.k]
+            }
         }
     }
 
@@ -1298,6 +1383,7 @@ impl Color {
             Self::Rgb(c) => Luma::from_color(c),
             Self::LinearRgb(c) => Luma::from_color(c),
             Self::Cmyk(c) => Luma::from_color(c.to_rgba()),
+            Self::Spot(c) => Luma::from_color(c.to_cmyk().to_rgba()),
             Self::Hsl(c) => Luma::from_color(c),
             Self::Hsv(c) => Luma::from_color(c),
         }
@@ -1311,6 +1397,7 @@ impl Color {
             Self::Rgb(c) => Oklab::from_color(c),
             Self::LinearRgb(c) => Oklab::from_color(c),
             Self::Cmyk(c) => Oklab::from_color(c.to_rgba()),
+            Self::Spot(c) => Oklab::from_color(c.to_cmyk().to_rgba()),
             Self::Hsl(c) => Oklab::from_color(c),
             Self::Hsv(c) => Oklab::from_color(c),
         }
@@ -1324,6 +1411,7 @@ impl Color {
             Self::Rgb(c) => Oklch::from_color(c),
             Self::LinearRgb(c) => Oklch::from_color(c),
             Self::Cmyk(c) => Oklch::from_color(c.to_rgba()),
+            Self::Spot(c) => Oklch::from_color(c.to_cmyk().to_rgba()),
             Self::Hsl(c) => Oklch::from_color(c),
             Self::Hsv(c) => Oklch::from_color(c),
         }
@@ -1337,6 +1425,7 @@ impl Color {
             Self::Rgb(c) => c,
             Self::LinearRgb(c) => Rgb::from_linear(c),
             Self::Cmyk(c) => Rgb::from_color(c.to_rgba()),
+            Self::Spot(c) => Rgb::from_color(c.to_cmyk().to_rgba()),
             Self::Hsl(c) => Rgb::from_color(c),
             Self::Hsv(c) => Rgb::from_color(c),
         }
@@ -1350,6 +1439,7 @@ impl Color {
             Self::Rgb(c) => LinearRgb::from_color(c),
             Self::LinearRgb(c) => c,
             Self::Cmyk(c) => LinearRgb::from_color(c.to_rgba()),
+            Self::Spot(c) => LinearRgb::from_color(c.to_cmyk().to_rgba()),
             Self::Hsl(c) => Rgb::from_color(c).into_linear(),
             Self::Hsv(c) => Rgb::from_color(c).into_linear(),
         }
@@ -1363,6 +1453,7 @@ impl Color {
             Self::Rgb(c) => Cmyk::from_rgba(c),
             Self::LinearRgb(c) => Cmyk::from_rgba(Rgb::from_linear(c)),
             Self::Cmyk(c) => c,
+            Self::Spot(c) => c.to_cmyk(),
             Self::Hsl(c) => Cmyk::from_rgba(Rgb::from_color(c)),
             Self::Hsv(c) => Cmyk::from_rgba(Rgb::from_color(c)),
         }
@@ -1376,6 +1467,7 @@ impl Color {
             Self::Rgb(c) => Hsl::from_color(c),
             Self::LinearRgb(c) => Hsl::from_color(Rgb::from_linear(c)),
             Self::Cmyk(c) => Hsl::from_color(c.to_rgba()),
+            Self::Spot(c) => Hsl::from_color(c.to_cmyk().to_rgba()),
             Self::Hsl(c) => c,
             Self::Hsv(c) => Hsl::from_color(c),
         }
@@ -1389,6 +1481,7 @@ impl Color {
             Self::Rgb(c) => Hsv::from_color(c),
             Self::LinearRgb(c) => Hsv::from_color(Rgb::from_linear(c)),
             Self::Cmyk(c) => Hsv::from_color(c.to_rgba()),
+            Self::Spot(c) => Hsv::from_color(c.to_cmyk().to_rgba()),
             Self::Hsl(c) => Hsv::from_color(c),
             Self::Hsv(c) => c,
         }
@@ -1433,6 +1526,13 @@ impl Debug for Color {
                 v.value,
                 v.alpha
             ),
+            Self::Spot(v) => write!(
+                f,
+                "Spot({:?}, {}, {:?})",
+                v.name.resolve().as_str(),
+                v.tint,
+                Self::Cmyk(v.fallback)
+            ),
         }
     }
 }
@@ -1551,6 +1651,18 @@ impl Repr for Color {
                     )
                 }
             }
+            Self::Spot(c) => {
+                let name = c.name.resolve().as_str().repr();
+                let fallback = Self::Cmyk(c.fallback).repr();
+                if c.tint == 1.0 {
+                    eco_format!("color.spot({name}, {fallback})")
+                } else {
+                    eco_format!(
+                        "color.spot({name}, {fallback}, tint: {})",
+                        Ratio::new(c.tint.into()).repr(),
+                    )
+                }
+            }
         }
     }
 }
@@ -1574,6 +1686,7 @@ impl PartialEq for Color {
             (Self::Cmyk(a), Self::Cmyk(b)) => a == b,
             (Self::Hsl(a), Self::Hsl(b)) => a == b,
             (Self::Hsv(a), Self::Hsv(b)) => a == b,
+            (Self::Spot(a), Self::Spot(b)) => a == b,
             _ => false,
         }
     }
@@ -1584,6 +1697,10 @@ impl Eq for Color {}
 impl Hash for Color {
     fn hash<H: Hasher>(&self, state: &mut H) {
         core::mem::discriminant(self).hash(state);
+        if let Self::Spot(c) = self {
+            c.name.hash(state);
+            c.tint.to_bits().hash(state);
+        }
         let [x, y, z, w] = self.to_vec4();
         x.to_bits().hash(state);
         y.to_bits().hash(state);
@@ -1754,6 +1871,66 @@ impl Cmyk {
     }
 }
 
+/// A spot color: A named ink, applied with a tint.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Spot {
+    /// The name of the ink.
+    pub name: PicoStr,
+    /// How much of the ink is applied.
+    pub tint: f32,
+    /// The color of the ink at full tint.
+    pub fallback: Cmyk,
+}
+
+impl Spot {
+    /// The color of the ink at its tint.
+    pub fn to_cmyk(self) -> Cmyk {
+        let tint = |u: f32| u * self.tint;
+        let Cmyk { c, m, y, k } = self.fallback;
+        Cmyk::new(tint(c), tint(m), tint(y), tint(k))
+    }
+
+    /// Mixes the tints of spot colors with the same ink. Returns `None` if
+    /// the colors are not all spot colors of the same ink.
+    fn mix(colors: &[WeightedColor]) -> StrResult<Option<Self>> {
+        let Some(&WeightedColor { color: Color::Spot(first), .. }) = colors.first()
+        else {
+            return Ok(None);
+        };
+
+        let mut total = 0.0;
+        let mut tint = 0.0;
+        for &WeightedColor { color, weight } in colors {
+            let Color::Spot(spot) = color else { return Ok(None) };
+            if spot.name != first.name {
+                return Ok(None);
+            }
+            tint += weight as f32 * spot.tint;
+            total += weight as f32;
+        }
+
+        if total <= 0.0 {
+            bail!("sum of weights must be positive");
+        }
+
+        Ok(Some(Self { tint: (tint / total).clamp(0.0, 1.0), ..first }))
+    }
+
+    fn lighten(self, factor: f32) -> Self {
+        Self {
+            tint: (self.tint - self.tint * factor).clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    fn darken(self, factor: f32) -> Self {
+        Self {
+            tint: (self.tint + (1.0 - self.tint) * factor).clamp(0.0, 1.0),
+            ..self
+        }
+    }
+}
+
 /// A color with a weight.
 pub struct WeightedColor {
     color: Color,
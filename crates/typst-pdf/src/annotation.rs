@@ -0,0 +1,318 @@
+use std::fmt::Write;
+
+use ecow::eco_format;
+use typst_library::diag::{At, SourceResult, StrResult, bail};
+use typst_library::foundations::{Datetime, Packed, StyleChain};
+use typst_library::introspection::Location;
+use typst_library::layout::{Abs, Size, Transform};
+use typst_library::pdf::{AnnotationElem, AnnotationKind};
+use typst_library::text::TextItem;
+use typst_library::visualize::ColorSpace;
+use typst_syntax::Span;
+
+use crate::convert::{FrameContext, GlobalContext};
+use crate::link::bounding_box;
+use crate::print;
+use crate::update::{Update, dict_set, write_ref, write_stream, write_text_str};
+
+/// The size of the icon of a note.
+const NOTE_SIZE: f32 = 16.0;
+
+/// The default size of a free text annotation without a body.
+const FREE_TEXT_SIZE: (f32, f32) = (144.0, 36.0);
+
+/// The annotations of a document, collected during conversion.
+#[derive(Default)]
+pub(crate) struct Annotations {
+    /// All annotations in document order.
+    items: Vec<PdfAnnotation>,
+    /// Indices of the annotations whose body is currently being converted.
+    open: Vec<usize>,
+}
+
+/// An annotation and where it is placed.
+struct PdfAnnotation {
+    /// The element the annotation is created from.
+    elem: Packed<AnnotationElem>,
+    /// The index of the page and the point in PDF coordinates where the
+    /// element starts.
+    start: Option<(usize, [f32; 2])>,
+    /// The rectangles covered by the element's text, with the index of their
+    /// page, in PDF coordinates (left, bottom, right, top).
+    rects: Vec<(usize, [f32; 4])>,
+}
+
+impl PdfAnnotation {
+    /// Adds a rectangle, merging it with the previous one if it continues the
+    /// same line.
+    fn push(&mut self, page: usize, rect: [f32; 4]) {
+        if let Some((prev_page, prev)) = self.rects.last_mut()
+            && *prev_page == page
+            && (prev[1] - rect[1]).abs() < 0.5
+            && (prev[3] - rect[3]).abs() < 0.5
+            && rect[0] <= prev[2] + 1.0
+        {
+            prev[0] = prev[0].min(rect[0]);
+            prev[2] = prev[2].max(rect[2]);
+            return;
+        }
+
+        self.rects.push((page, rect));
+    }
+}
+
+/// Starts collecting an annotation at the start tag of its element.
+pub(crate) fn handle_start(
+    fc: &mut FrameContext,
+    gc: &mut GlobalContext,
+    elem: Packed<AnnotationElem>,
+) -> SourceResult<()> {
+    if let Some(standard) = print::forbidding_standard(gc.options) {
+        bail!(
+            elem.span(),
+            "{standard} error: annotations are not supported";
+            hint: "export without a PDF standard to use annotations"
+        );
+    }
+
+    let start = fc.page_idx.map(|page| {
+        let rect = to_pdf_rect(fc, bounding_box(fc, Size::zero()));
+        (page, [rect[0], rect[3]])
+    });
+
+    let annotations = &mut gc.annotations;
+    annotations.open.push(annotations.items.len());
+    annotations.items.push(PdfAnnotation { elem, start, rects: vec![] });
+
+    Ok(())
+}
+
+/// Stops collecting an annotation at the end tag of its element.
+pub(crate) fn handle_end(gc: &mut GlobalContext, loc: Location) {
+    let annotations = &mut gc.annotations;
+    if let Some(pos) = annotations
+        .open
+        .iter()
+        .rposition(|&i| annotations.items[i].elem.location() == Some(loc))
+    {
+        annotations.open.remove(pos);
+    }
+}
+
+/// Records the area of a text item for all annotations it is part of.
+pub(crate) fn handle_text(fc: &mut FrameContext, gc: &mut GlobalContext, t: &TextItem) {
+    let annotations = &mut gc.annotations;
+    if annotations.open.is_empty() {
+        return;
+    }
+
+    let Some(page) = fc.page_idx else { return };
+
+    let metrics = t.font.metrics();
+    let ascent = metrics.ascender.at(t.size);
+    let descent = -metrics.descender.at(t.size);
+
+    fc.push();
+    fc.state_mut().pre_concat(Transform::translate(Abs::zero(), -ascent));
+    let bbox = bounding_box(fc, Size::new(t.width(), ascent + descent));
+    let rect = to_pdf_rect(fc, bbox);
+    fc.pop();
+
+    for &i in &annotations.open {
+        annotations.items[i].push(page, rect);
+    }
+}
+
+/// Flips a rectangle into PDF's coordinate system, whose y-axis points
+/// upwards.
+pub(crate) fn to_pdf_rect(fc: &FrameContext, rect: krilla::geom::Rect) -> [f32; 4] {
+    let height = fc.page_size().y.to_pt() as f32;
+    [rect.left(), height - rect.bottom(), rect.right(), height - rect.top()]
+}
+
+/// Adds the collected annotations to a finished PDF file.
+pub(crate) fn write_annotations(
+    update: &mut Update,
+    annotations: Annotations,
+) -> SourceResult<()> {
+    if annotations.items.is_empty() {
+        return Ok(());
+    }
+
+    write(update, &annotations.items)
+        .map_err(|err| eco_format!("failed to write annotations: {err}"))
+        .at(Span::detached())
+}
+
+/// Writes the annotations into the update.
+fn write(update: &mut Update, items: &[PdfAnnotation]) -> StrResult<()> {
+    let pages = update.pages()?;
+    let mut annots = vec![vec![]; pages.len()];
+
+    for annotation in items {
+        let kind = annotation.elem.kind.get(StyleChain::default());
+        let placements = match kind {
+            // A highlight covers the body on every page it appears on.
+            AnnotationKind::Highlight if !annotation.rects.is_empty() => {
+                let mut placements: Vec<(usize, Vec<[f32; 4]>)> = vec![];
+                for &(page, rect) in &annotation.rects {
+                    match placements.last_mut() {
+                        Some((last, rects)) if *last == page => rects.push(rect),
+                        _ => placements.push((page, vec![rect])),
+                    }
+                }
+                placements
+            }
+            // Everything else is placed once, where the body starts.
+            _ => match (annotation.rects.first(), annotation.start) {
+                (Some(&(page, _)), _) => {
+                    let rects = annotation
+                        .rects
+                        .iter()
+                        .filter(|&&(p, _)| p == page)
+                        .map(|&(_, rect)| rect)
+                        .collect();
+                    vec![(page, rects)]
+                }
+                (None, Some((page, _))) => vec![(page, vec![])],
+                (None, None) => vec![],
+            },
+        };
+
+        for (page, rects) in placements {
+            let Some(&page_id) = pages.get(page) else { continue };
+            let id = update.alloc();
+            let dict = annotation_dict(update, annotation, kind, page_id, &rects);
+            update.write(id, dict);
+            annots[page].push(id);
+        }
+    }
+
+    for (&page, ids) in pages.iter().zip(annots) {
+        if !ids.is_empty() {
+            update.add_annotations(page, ids)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the dictionary of an annotation on one page.
+fn annotation_dict(
+    update: &mut Update,
+    annotation: &PdfAnnotation,
+    kind: AnnotationKind,
+    page: i32,
+    rects: &[[f32; 4]],
+) -> Vec<u8> {
+    let styles = StyleChain::default();
+    let elem = &annotation.elem;
+
+    // The point where the annotation is anchored.
+    let [x, y] = match rects.first() {
+        Some(rect) => [rect[0], rect[3]],
+        None => annotation.start.map_or([0.0, 0.0], |(_, point)| point),
+    };
+
+    let mut dict = b"<<>>".to_vec();
+    dict = dict_set(&dict, b"Type", b"/Annot");
+    dict = dict_set(&dict, b"P", &write_ref(page));
+    dict = dict_set(&dict, b"Contents", &write_text_str(&elem.contents));
+    if let Some(author) = elem.author.get_ref(styles) {
+        dict = dict_set(&dict, b"T", &write_text_str(author));
+    }
+    if let Some(date) = elem.date.get(styles).and_then(write_date) {
+        dict = dict_set(&dict, b"M", &write_text_str(&date));
+    }
+
+    let [red, green, blue, _] =
+        elem.color.get(styles).to_space(ColorSpace::Srgb).to_vec4();
+    let color = format!("{red} {green} {blue}");
+    dict = dict_set(&dict, b"C", format!("[{color}]").as_bytes());
+
+    match kind {
+        AnnotationKind::Highlight if !rects.is_empty() => {
+            let mut bbox = rects[0];
+            let mut quads = String::new();
+            let mut fill = format!("/GS0 gs {color} rg\n");
+            for &[l, b, r, t] in rects {
+                bbox = [bbox[0].min(l), bbox[1].min(b), bbox[2].max(r), bbox[3].max(t)];
+                if !quads.is_empty() {
+                    quads.push(' ');
+                }
+                write!(quads, "{l} {t} {r} {t} {l} {b} {r} {b}").unwrap();
+                writeln!(fill, "{l} {b} {} {} re", r - l, t - b).unwrap();
+            }
+            fill.push('f');
+
+            // Readers may not generate appearances for highlights, so we
+            // provide one that multiplies the color with the page.
+            let [l, b, r, t] = bbox;
+            let appearance = update.alloc();
+            let stream = format!(
+                "<</Type /XObject /Subtype /Form /BBox [{l} {b} {r} {t}] \
+                 /Resources <</ExtGState <</GS0 <</BM /Multiply>>>>>>>>"
+            );
+            update.write(appearance, write_stream(stream.as_bytes(), fill.as_bytes()));
+
+            dict = dict_set(&dict, b"Subtype", b"/Highlight");
+            dict = dict_set(&dict, b"Rect", format!("[{l} {b} {r} {t}]").as_bytes());
+            dict = dict_set(&dict, b"QuadPoints", format!("[{quads}]").as_bytes());
+            dict = dict_set(&dict, b"AP", format!("<</N {appearance} 0 R>>").as_bytes());
+            // Print the annotation.
+            dict = dict_set(&dict, b"F", b"4");
+        }
+        AnnotationKind::FreeText => {
+            let [l, b, r, t] = match rects.first() {
+                Some(&first) => rects.iter().fold(first, |acc, rect| {
+                    [
+                        acc[0].min(rect[0]),
+                        acc[1].min(rect[1]),
+                        acc[2].max(rect[2]),
+                        acc[3].max(rect[3]),
+                    ]
+                }),
+                None => [x, y - FREE_TEXT_SIZE.1, x + FREE_TEXT_SIZE.0, y],
+            };
+            dict = dict_set(&dict, b"Subtype", b"/FreeText");
+            dict = dict_set(&dict, b"Rect", format!("[{l} {b} {r} {t}]").as_bytes());
+            dict = dict_set(&dict, b"DA", b"(/Helv 10 Tf 0 g)");
+            dict = dict_set(&dict, b"F", b"4");
+        }
+        // Notes, as well as highlights without anything to highlight.
+        _ => {
+            let rect = [x, y - NOTE_SIZE, x + NOTE_SIZE, y];
+            let [l, b, r, t] = rect;
+            dict = dict_set(&dict, b"Subtype", b"/Text");
+            dict = dict_set(&dict, b"Rect", format!("[{l} {b} {r} {t}]").as_bytes());
+            dict = dict_set(&dict, b"Name", b"/Comment");
+            // Print the icon, but don't scale or rotate it with the page.
+            dict = dict_set(&dict, b"F", b"28");
+        }
+    }
+
+    dict
+}
+
+/// Formats a date as a PDF date string.
+fn write_date(date: Datetime) -> Option<String> {
+    let mut out = format!("D:{:04}", date.year()?);
+    for part in [date.month(), date.day(), date.hour(), date.minute(), date.second()] {
+        let Some(part) = part else { break };
+        write!(out, "{part:02}").unwrap();
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_date() {
+        let date = Datetime::from_ymd(2024, 3, 9).unwrap();
+        assert_eq!(write_date(date).as_deref(), Some("D:20240309"));
+        let time = Datetime::from_hms(12, 0, 0).unwrap();
+        assert_eq!(write_date(time), None);
+    }
+}
@@ -13,28 +13,39 @@ use krilla::{Document, SerializeSettings};
 use krilla_svg::render_svg_glyph;
 use rustc_hash::{FxBuildHasher, FxHashMap, FxHashSet};
 use smallvec::SmallVec;
-use typst_library::diag::{SourceDiagnostic, SourceResult, bail, error};
-use typst_library::foundations::{NativeElement, Repr};
+use typst_library::diag::{At, SourceDiagnostic, SourceResult, bail, error};
+use typst_library::foundations::Repr;
 use typst_library::introspection::{Location, Tag};
 use typst_library::layout::{
     Frame, FrameItem, GroupItem, PagedDocument, Size, Transform,
 };
-use typst_library::model::HeadingElem;
+use typst_library::pdf::AnnotationElem;
 use typst_library::text::{Font, Locale};
 use typst_library::visualize::{Geometry, Paint};
 use typst_syntax::Span;
 
-use crate::PdfOptions;
+use crate::annotation::{self, Annotations, write_annotations};
 use crate::attach::attach_files;
+use crate::font::write_full_fonts;
+use crate::form::{FormWidget, handle_form_field, write_form_fields};
 use crate::image::handle_image;
-use crate::link::{LinkAnnotation, handle_link};
-use crate::metadata::build_metadata;
+use crate::linearize::linearize;
+use crate::link::{
+    FileLinkAnnotation, LinkAnnotation, collect_named_destinations, handle_link,
+    write_file_links,
+};
+use crate::metadata::{build_metadata, write_viewer_settings};
 use crate::outline::build_outline;
-use crate::page::PageLabelExt;
+use crate::page::{PageLabelExt, with_print_boxes};
+use crate::print::{check_document, write_print_info};
+use crate::rewrite::{self, Marks, write_marks};
 use crate::shape::handle_shape;
+use crate::sign::sign;
 use crate::tags::{self, GroupId, Tags};
 use crate::text::handle_text;
+use crate::update::Update;
 use crate::util::{AbsExt, TransformExt, convert_path, display_font};
+use crate::{PdfOptions, PdfSigning};
 
 #[typst_macros::time(name = "convert document")]
 pub fn convert(
@@ -43,6 +54,7 @@ pub fn convert(
 ) -> SourceResult<Vec<u8>> {
     let (mut document, mut gc) = setup(typst_document, options)?;
 
+    check_document(&gc)?;
     convert_pages(&mut gc, &mut document)?;
     attach_files(&gc, &mut document)?;
     let (doc_lang, tree) = tags::resolve(&mut gc)?;
@@ -51,7 +63,40 @@ pub fn convert(
     document.set_metadata(build_metadata(&gc, doc_lang));
     document.set_tag_tree(tree);
 
-    finish(document, gc, options.standards.config)
+    let marks = std::mem::take(&mut gc.marks);
+    let full_fonts = std::mem::take(&mut gc.full_fonts);
+    let form_widgets = std::mem::take(&mut gc.form_widgets);
+    let annotations = std::mem::take(&mut gc.annotations);
+    let file_links = std::mem::take(&mut gc.file_links);
+    let pdf = finish(document, gc, options.standards.config)?;
+
+    // Features that krilla does not support are added to the serialized file
+    // in a single incremental update.
+    let mut update = Update::new(pdf)
+        .map_err(|err| eco_format!("failed to read PDF: {err}"))
+        .at(Span::detached())?;
+    write_marks(&mut update, marks)?;
+    write_full_fonts(&mut update, full_fonts, options)?;
+    write_print_info(&mut update, options)?;
+    write_viewer_settings(&mut update, typst_document, options)?;
+    write_annotations(&mut update, annotations)?;
+    write_file_links(&mut update, typst_document, file_links)?;
+    write_form_fields(&mut update, form_widgets, options.signing.as_ref())?;
+    let mut pdf = update.finish();
+
+    if options.linearize {
+        pdf = linearize(pdf)?;
+    }
+
+    // The signature is filled into the placeholder that was reserved in the
+    // update. As this happens in place, it keeps a linearized file intact.
+    if let Some(PdfSigning::Sign(signer)) = options.signing {
+        sign(&mut pdf, signer)
+            .map_err(|err| eco_format!("failed to sign PDF: {err}"))
+            .at(Span::detached())?;
+    }
+
+    Ok(pdf)
 }
 
 pub fn tag_tree(
@@ -86,7 +131,8 @@ fn setup<'a>(
 ) -> SourceResult<(Document, GlobalContext<'a>)> {
     let settings = SerializeSettings {
         compress_content_streams: true,
-        no_device_cs: true,
+        // PDF/X-1a requires device color spaces.
+        no_device_cs: options.standards.pdf_x.is_none_or(|x| x.allows_rgb()),
         ascii_compatible: false,
         xmp_metadata: true,
         cmyk_profile: None,
@@ -143,6 +189,9 @@ fn convert_pages(gc: &mut GlobalContext, document: &mut Document) -> SourceResul
                 settings = settings.with_page_label(label);
             }
 
+            let force = gc.options.standards.pdf_x.is_some();
+            settings = with_print_boxes(settings, typst_page, force);
+
             let mut page = document.start_page_with(settings);
             let mut surface = page.surface();
             let page_idx = gc.page_index_converter.pdf_page_index(i);
@@ -247,6 +296,11 @@ impl FrameContext {
         self.states.last_mut().unwrap()
     }
 
+    /// The size of the page or tiling the frame belongs to.
+    pub(crate) fn page_size(&self) -> Size {
+        self.states[0].container_size
+    }
+
     pub(crate) fn get_link_annotation(
         &mut self,
         id: GroupId,
@@ -286,6 +340,16 @@ pub(crate) struct GlobalContext<'a> {
     pub(crate) page_index_converter: PageIndexConverter,
     /// Tagged PDF context.
     pub(crate) tags: Tags,
+    /// The widgets of the document's form fields.
+    pub(crate) form_widgets: Vec<FormWidget>,
+    /// The document's review annotations.
+    pub(crate) annotations: Annotations,
+    /// The document's links into other files.
+    pub(crate) file_links: Vec<FileLinkAnnotation>,
+    /// The drawing operations whose content is rewritten after export.
+    pub(crate) marks: Marks,
+    /// The fonts that are embedded in full.
+    pub(crate) full_fonts: FxHashSet<Font>,
 }
 
 impl<'a> GlobalContext<'a> {
@@ -306,6 +370,11 @@ impl<'a> GlobalContext<'a> {
             image_spans: FxHashSet::default(),
             page_index_converter,
             tags,
+            form_widgets: vec![],
+            annotations: Annotations::default(),
+            file_links: vec![],
+            marks: Marks::default(),
+            full_fonts: FxHashSet::default(),
         }
     }
 }
@@ -334,19 +403,31 @@ pub(crate) fn handle_frame(
         fc.state_mut().pre_concat(Transform::translate(point.x, point.y));
 
         match item {
-            FrameItem::Group(g) => handle_group(fc, g, surface, gc)?,
-            FrameItem::Text(t) => handle_text(fc, t, surface, gc)?,
+            FrameItem::Group(g) => {
+                if let Some(location) = g.form_field {
+                    handle_form_field(fc, gc, location, g)?;
+                }
+                handle_group(fc, g, surface, gc)?
+            }
+            FrameItem::Text(t) => {
+                annotation::handle_text(fc, gc, t);
+                handle_text(fc, t, surface, gc)?
+            }
             FrameItem::Shape(s, span) => handle_shape(fc, s, surface, gc, *span)?,
             FrameItem::Image(image, size, span) => {
                 handle_image(gc, fc, image, *size, surface, *span)?
             }
             FrameItem::Link(dest, size) => handle_link(fc, gc, dest, *size)?,
-            FrameItem::Tag(Tag::Start(_, flags)) => {
+            FrameItem::Tag(Tag::Start(elem, flags)) => {
+                if let Some(annot) = elem.to_packed::<AnnotationElem>() {
+                    annotation::handle_start(fc, gc, annot.clone())?;
+                }
                 if flags.tagged {
                     tags::handle_start(gc, surface);
                 }
             }
-            FrameItem::Tag(Tag::End(_, _, flags)) => {
+            FrameItem::Tag(Tag::End(loc, _, flags)) => {
+                annotation::handle_end(gc, *loc);
                 if flags.tagged {
                     tags::handle_end(gc, surface);
                 }
@@ -385,7 +466,28 @@ pub(crate) fn handle_group(
             surface.push_clip_path(clip_path, &krilla::paint::FillRule::NonZero);
         }
 
-        let res = handle_frame(fc, &group.frame, None, surface, gc);
+        // Layers are written as optional content where possible. Otherwise,
+        // they are exported with their default visibility and hidden layers
+        // keep their tags for the tag tree.
+        let marker = match &group.layer {
+            Some(layer) if rewrite::supports_layers(gc.options) => {
+                Some(gc.marks.layer(layer))
+            }
+            _ => None,
+        };
+        let hidden;
+        let frame = if marker.is_none() && group.in_hidden_layer() {
+            let mut frame = group.frame.clone();
+            frame.hide();
+            hidden = frame;
+            &hidden
+        } else {
+            &group.frame
+        };
+
+        let res = rewrite::marked(surface, marker, |surface| {
+            handle_frame(fc, frame, None, surface, gc)
+        });
 
         if clip_path.is_some() {
             surface.pop();
@@ -711,37 +813,6 @@ pub(crate) fn to_span(loc: Option<krilla::surface::Location>) -> Span {
     loc.map(Span::from_raw).unwrap_or(Span::detached())
 }
 
-fn collect_named_destinations(
-    document: &PagedDocument,
-    pic: &PageIndexConverter,
-) -> FxHashMap<Location, NamedDestination> {
-    let mut locs_to_names = FxHashMap::default();
-
-    // Find all headings that have a label and are the first among other
-    // headings with the same label.
-    let matches: Vec<_> = {
-        let mut seen = FxHashSet::default();
-        document
-            .introspector
-            .query(&HeadingElem::ELEM.select())
-            .iter()
-            .filter_map(|elem| elem.location().zip(elem.label()))
-            .filter(|&(_, label)| seen.insert(label))
-            .collect()
-    };
-
-    for (loc, label) in matches {
-        // Only add named destination if page belonging to the position is exported.
-        let pos = document.introspector.position(loc);
-        if let Some(dest) = crate::link::pos_to_xyz(pic, pos) {
-            let named = NamedDestination::new(label.resolve().to_string(), dest);
-            locs_to_names.insert(loc, named);
-        }
-    }
-
-    locs_to_names
-}
-
 pub(crate) struct PageIndexConverter {
     page_indices: FxHashMap<usize, usize>,
     skipped_pages: usize,
@@ -0,0 +1,373 @@
+//! Embedding of fonts in full.
+//!
+//! krilla always embeds fonts as subsets. To embed a font in full, all of its
+//! glyphs are registered with krilla in the order of their IDs before the font
+//! is first used. The subset then keeps every glyph under its original ID, so
+//! that the font program in the finished file can be replaced with the
+//! original font.
+
+use std::io::Write;
+use std::ops::Range;
+
+use ecow::eco_format;
+use flate2::Compression;
+use flate2::write::ZlibEncoder;
+use krilla::configure::{PdfVersion, Validator};
+use krilla::surface::{Location, Surface};
+use krilla::text::GlyphId;
+use rustc_hash::{FxHashMap, FxHashSet};
+use typst_library::diag::{At, SourceResult, StrResult, bail};
+use typst_library::text::Font;
+use typst_syntax::Span;
+
+use crate::PdfOptions;
+use crate::convert::GlobalContext;
+use crate::update::{Update, dict_get, dict_remove, dict_set, parse_ref, write_stream};
+
+/// The ID of the PostScript name in a font's `name` table.
+const POSTSCRIPT_NAME: u16 = 6;
+
+/// Registers all glyphs of a font with krilla if fonts are embedded in full.
+/// Must be called before the font is first drawn.
+pub(crate) fn register_glyphs(
+    gc: &mut GlobalContext,
+    font: &Font,
+    krilla_font: &krilla::text::Font,
+    surface: &mut Surface,
+    span: Span,
+) -> SourceResult<()> {
+    if !gc.options.full_fonts || gc.full_fonts.contains(font) {
+        return Ok(());
+    }
+
+    // The glyphs are registered without their text where the font does not
+    // map them to Unicode, which the PDF/A and PDF/UA standards may forbid.
+    let validator = gc.options.standards.config.validator();
+    if validator != Validator::None {
+        bail!(
+            span,
+            "{} error: fonts cannot be embedded in full", validator.as_str();
+            hint: "export without `--pdf-full-fonts` to embed fonts as subsets"
+        );
+    }
+
+    let mut chars = FxHashMap::default();
+    if let Some(cmap) = font.ttf().tables().cmap {
+        for subtable in cmap.subtables.into_iter().filter(|s| s.is_unicode()) {
+            subtable.codepoints(|cp| {
+                let Some(c) = char::from_u32(cp) else { return };
+                if let Some(glyph) = subtable.glyph_index(cp) {
+                    chars.entry(glyph.0).or_insert(c);
+                }
+            });
+        }
+    }
+
+    let mut text = String::new();
+    let glyphs: Vec<FullGlyph> = (0..font.ttf().number_of_glyphs())
+        .map(|id| {
+            let start = text.len();
+            text.extend(chars.get(&id));
+            FullGlyph { id, range: start..text.len() }
+        })
+        .collect();
+
+    // The glyphs are drawn into a stream that is never used.
+    let mut stream_builder = surface.stream_builder();
+    let mut surface = stream_builder.surface();
+    surface.draw_glyphs(
+        krilla::geom::Point::from_xy(0.0, 0.0),
+        &glyphs,
+        krilla_font.clone(),
+        &text,
+        1.0,
+        false,
+    );
+    surface.finish();
+    stream_builder.finish();
+
+    gc.full_fonts.insert(font.clone());
+    Ok(())
+}
+
+/// A glyph that is registered with krilla, but not displayed.
+struct FullGlyph {
+    id: u16,
+    range: Range<usize>,
+}
+
+impl krilla::text::Glyph for FullGlyph {
+    fn glyph_id(&self) -> GlyphId {
+        GlyphId::new(self.id as u32)
+    }
+
+    fn text_range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    fn x_advance(&self, _: f32) -> f32 {
+        0.0
+    }
+
+    fn x_offset(&self, _: f32) -> f32 {
+        0.0
+    }
+
+    fn y_offset(&self, _: f32) -> f32 {
+        0.0
+    }
+
+    fn y_advance(&self, _: f32) -> f32 {
+        0.0
+    }
+
+    fn location(&self) -> Option<Location> {
+        None
+    }
+}
+
+/// Replaces the subsets of the fonts that are embedded in full with the
+/// original fonts.
+pub(crate) fn write_full_fonts(
+    update: &mut Update,
+    fonts: FxHashSet<Font>,
+    options: &PdfOptions,
+) -> SourceResult<()> {
+    if fonts.is_empty() {
+        return Ok(());
+    }
+
+    write(update, &fonts, options)
+        .map_err(|err| eco_format!("failed to embed fonts in full: {err}"))
+        .at(Span::detached())
+}
+
+/// Writes the original fonts into the update.
+fn write(
+    update: &mut Update,
+    fonts: &FxHashSet<Font>,
+    options: &PdfOptions,
+) -> StrResult<()> {
+    // Font descriptors only name their font, so fonts whose PostScript names
+    // clash cannot be told apart and keep their subsets.
+    let mut by_name: FxHashMap<Vec<u8>, Option<&Font>> = FxHashMap::default();
+    for font in fonts {
+        let Some(name) = font.find_name(POSTSCRIPT_NAME) else { continue };
+        by_name
+            .entry(name.into_bytes())
+            .and_modify(|entry| *entry = None)
+            .or_insert(Some(font));
+    }
+
+    let ids = update.ids();
+
+    // The subset names of the replaced fonts.
+    let mut replaced = vec![];
+    for &id in &ids {
+        let Some(dict) = update.get(id) else { continue };
+        if dict_get(dict, b"Type") != Some(b"/FontDescriptor") {
+            continue;
+        }
+
+        let Some(tagged) = dict_get(dict, b"FontName") else { continue };
+        let decoded = decode_name(tagged);
+        let Some(plus) = decoded.iter().position(|&b| b == b'+') else { continue };
+        let Some(&Some(font)) = by_name.get(&decoded[plus + 1..]) else { continue };
+
+        let (file, cff) =
+            match (dict_get(dict, b"FontFile2"), dict_get(dict, b"FontFile3")) {
+                (Some(file), _) => (file, false),
+                (None, Some(file)) => (file, true),
+                (None, None) => continue,
+            };
+        let Some(file) = parse_ref(file) else { continue };
+        let Some(program) = font_program(font, cff, options) else { continue };
+
+        // The full font has no subset tag and no set of subset glyphs.
+        let tagged = tagged.to_vec();
+        let untagged = untag(&tagged);
+        let descriptor = dict_remove(dict, b"CIDSet");
+        let descriptor = dict_set(&descriptor, b"FontName", &untagged);
+
+        update.write(id, descriptor);
+        update.write(file, program);
+        replaced.push((tagged, untagged));
+    }
+
+    // The fonts that use the descriptors name the font with the same tag.
+    for &id in &ids {
+        let Some(dict) = update.get(id) else { continue };
+        if dict_get(dict, b"Type") != Some(b"/Font") {
+            continue;
+        }
+        let Some(base) = dict_get(dict, b"BaseFont") else { continue };
+        let Some((tagged, untagged)) = replaced.iter().find(|(tagged, _)| {
+            base.strip_prefix(tagged.as_slice())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(b"-"))
+        }) else {
+            continue;
+        };
+
+        let mut name = untagged.clone();
+        name.extend(&base[tagged.len()..]);
+        let dict = dict_set(dict, b"BaseFont", &name);
+        update.write(id, dict);
+    }
+
+    Ok(())
+}
+
+/// Writes the stream with the original program of a font. Returns `None` if
+/// the font cannot replace its subset.
+fn font_program(font: &Font, cff: bool, options: &PdfOptions) -> Option<Vec<u8>> {
+    let data = font.data().as_slice();
+
+    // Embedded programs must hold a single font.
+    if font.index() != 0 || data.starts_with(b"ttcf") {
+        return None;
+    }
+
+    let dict = if cff {
+        // Embedding OpenType fonts requires PDF 1.6. For CID-keyed fonts, the
+        // glyphs are selected through the font's own CIDs, which differ from
+        // the glyph IDs that the content streams use.
+        let version = options.standards.config.version();
+        if matches!(version, PdfVersion::Pdf14 | PdfVersion::Pdf15) || is_cid_keyed(data)?
+        {
+            return None;
+        }
+        b"<</Subtype /OpenType /Filter /FlateDecode>>".to_vec()
+    } else {
+        let length = data.len().to_string();
+        dict_set(b"<</Filter /FlateDecode>>", b"Length1", length.as_bytes())
+    };
+
+    let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+    encoder.write_all(data).ok()?;
+    let compressed = encoder.finish().ok()?;
+    Some(write_stream(&dict, &compressed))
+}
+
+/// Whether the `CFF` table of an OpenType font is CID-keyed, that is whether
+/// its top dictionary starts with the `ROS` operator.
+fn is_cid_keyed(data: &[u8]) -> Option<bool> {
+    let u16_at =
+        |pos: usize| Some(u16::from_be_bytes(data.get(pos..pos + 2)?.try_into().ok()?));
+    let u32_at =
+        |pos: usize| Some(u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?));
+
+    let tables = usize::from(u16_at(4)?);
+    let cff = (0..tables)
+        .map(|i| 12 + 16 * i)
+        .find(|&record| data.get(record..record + 4) == Some(b"CFF "))?;
+    let start = usize::try_from(u32_at(cff + 8)?).ok()?;
+    let cff = data.get(start..)?;
+
+    // Skips an INDEX structure and returns the range of its first item.
+    let index = |pos: usize| -> Option<(Range<usize>, usize)> {
+        let count =
+            usize::from(u16::from_be_bytes(cff.get(pos..pos + 2)?.try_into().ok()?));
+        if count == 0 {
+            return Some((0..0, pos + 2));
+        }
+        let size = usize::from(*cff.get(pos + 2)?);
+        let offset = |i: usize| {
+            let bytes = cff.get(pos + 3 + i * size..pos + 3 + (i + 1) * size)?;
+            Some(bytes.iter().fold(0, |acc, &b| acc << 8 | usize::from(b)))
+        };
+        let data = pos + 3 + (count + 1) * size - 1;
+        Some((data + offset(0)?..data + offset(1)?, data + offset(count)?))
+    };
+
+    let header = usize::from(*cff.get(2)?);
+    let (_, names_end) = index(header)?;
+    let (top, _) = index(names_end)?;
+    let top = cff.get(top)?;
+
+    // The ROS operator must be the first in a CIDFont's top dictionary.
+    let mut pos = 0;
+    while let Some(&b) = top.get(pos) {
+        pos += match b {
+            12 => return Some(top.get(pos + 1) == Some(&30)),
+            0..=21 => return Some(false),
+            28 => 3,
+            29 => 5,
+            30 => {
+                // A real number ends with a nibble of 0xf.
+                let len = top[pos + 1..]
+                    .iter()
+                    .position(|&b| b & 0xf == 0xf || b >> 4 == 0xf)?;
+                len + 2
+            }
+            32..=246 => 1,
+            247..=254 => 2,
+            _ => return None,
+        };
+    }
+
+    Some(false)
+}
+
+/// Decodes the escape sequences in a PDF name and strips its slash.
+fn decode_name(name: &[u8]) -> Vec<u8> {
+    let mut out = vec![];
+    let mut bytes = name.strip_prefix(b"/").unwrap_or(name).iter();
+    while let Some(&b) = bytes.next() {
+        if b == b'#' {
+            let hex: Vec<u8> = bytes.by_ref().take(2).copied().collect();
+            let decoded = std::str::from_utf8(&hex)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            out.push(decoded.unwrap_or(b'#'));
+        } else {
+            out.push(b);
+        }
+    }
+    out
+}
+
+/// Removes the subset tag from a font name.
+fn untag(name: &[u8]) -> Vec<u8> {
+    match name.iter().position(|&b| b == b'+') {
+        Some(plus) => [b"/", &name[plus + 1..]].concat(),
+        None => name.to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_name() {
+        assert_eq!(decode_name(b"/ABCDEF+Noto#20Sans"), b"ABCDEF+Noto Sans");
+        assert_eq!(untag(b"/ABCDEF+Noto#20Sans"), b"/Noto#20Sans");
+        assert_eq!(untag(b"/Helvetica"), b"/Helvetica");
+    }
+
+    #[test]
+    fn test_is_cid_keyed() {
+        /// An OpenType file whose `CFF ` table has the given top dictionary.
+        fn otf(top: &[u8]) -> Vec<u8> {
+            let mut cff = vec![1, 0, 4, 1];
+            // A name INDEX with one name.
+            cff.extend([0, 1, 1, 1, 2, b'A']);
+            // A top DICT INDEX with one dictionary.
+            cff.extend([0, 1, 1, 1, 1 + top.len() as u8]);
+            cff.extend(top);
+
+            let mut data = b"OTTO\0\x01\0\0\0\0\0\0".to_vec();
+            data.extend(b"CFF \0\0\0\0");
+            data.extend(28u32.to_be_bytes());
+            data.extend((cff.len() as u32).to_be_bytes());
+            data.extend(cff);
+            data
+        }
+
+        // ROS with two strings and a supplement.
+        assert_eq!(is_cid_keyed(&otf(&[248, 0, 248, 1, 139, 12, 30])), Some(true));
+        // A real number and an integer followed by the FontBBox operator.
+        assert_eq!(is_cid_keyed(&otf(&[30, 0x1a, 0x5f, 139, 5])), Some(false));
+        assert_eq!(is_cid_keyed(b"OTTO"), None);
+    }
+}
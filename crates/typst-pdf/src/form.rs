@@ -0,0 +1,502 @@
+use std::io::Write;
+
+use ecow::{EcoString, eco_format};
+use indexmap::IndexMap;
+use rustc_hash::FxBuildHasher;
+use typst_library::diag::{At, SourceResult, StrResult, bail};
+use typst_library::foundations::{Packed, Selector, StyleChain};
+use typst_library::introspection::Location;
+use typst_library::layout::GroupItem;
+use typst_library::pdf::{FormFieldElem, FormFieldKind};
+use typst_syntax::Span;
+
+use crate::PdfSigning;
+use crate::convert::{FrameContext, GlobalContext};
+use crate::link::bounding_box;
+use crate::print;
+use crate::sign::write_placeholder;
+use crate::update::{
+    Update, dict_entries, dict_set, write_array, write_name, write_ref, write_stream,
+    write_text_str,
+};
+
+/// The default appearance of text in form fields: Helvetica in automatic size.
+const DEFAULT_APPEARANCE: &[u8] = b"(/Helv 0 Tf 0 g)";
+
+/// Field flags.
+const READ_ONLY: u32 = 1 << 0;
+const REQUIRED: u32 = 1 << 1;
+const MULTILINE: u32 = 1 << 12;
+const NO_TOGGLE_TO_OFF: u32 = 1 << 14;
+const RADIO: u32 = 1 << 15;
+const COMBO: u32 = 1 << 17;
+
+/// A form field widget placed on a page.
+pub(crate) struct FormWidget {
+    /// The field the widget belongs to.
+    field: Packed<FormFieldElem>,
+    /// The index of the page in the PDF.
+    page: usize,
+    /// The widget's rectangle in PDF coordinates (left, bottom, right, top).
+    rect: [f32; 4],
+}
+
+/// Records the widget for the form field at the given location, whose box is
+/// the given group.
+pub(crate) fn handle_form_field(
+    fc: &mut FrameContext,
+    gc: &mut GlobalContext,
+    location: Location,
+    group: &GroupItem,
+) -> SourceResult<()> {
+    let Some(page) = fc.page_idx else { return Ok(()) };
+    let Some(field) = gc
+        .document
+        .introspector
+        .query_first(&Selector::Location(location))
+        .and_then(|elem| elem.into_packed::<FormFieldElem>().ok())
+    else {
+        return Ok(());
+    };
+
+    if let Some(standard) = print::forbidding_standard(gc.options) {
+        bail!(
+            field.span(),
+            "{standard} error: form fields are not supported";
+            hint: "export without a PDF standard to use form fields"
+        );
+    }
+
+    fc.push();
+    fc.state_mut().pre_concat(group.transform);
+    let bbox = bounding_box(fc, group.frame.size());
+    fc.pop();
+
+    // PDF's y-axis points upwards.
+    let height = fc.page_size().y.to_pt() as f32;
+    let rect = [bbox.left(), height - bbox.bottom(), bbox.right(), height - bbox.top()];
+    gc.form_widgets.push(FormWidget { field, page, rect });
+
+    Ok(())
+}
+
+/// Adds the collected form fields to a finished PDF file and, if requested,
+/// prepares it for a signature.
+pub(crate) fn write_form_fields(
+    update: &mut Update,
+    widgets: Vec<FormWidget>,
+    signing: Option<&PdfSigning>,
+) -> SourceResult<()> {
+    if widgets.is_empty() && signing.is_none() {
+        return Ok(());
+    }
+
+    let styles = StyleChain::default();
+    let mut fields: IndexMap<EcoString, Vec<FormWidget>, FxBuildHasher> =
+        IndexMap::default();
+    for widget in widgets {
+        fields.entry(widget.field.name.clone()).or_default().push(widget);
+    }
+
+    for (name, widgets) in &fields {
+        let kind = widgets[0].field.kind.get(styles);
+        if let Some(other) = widgets.iter().find(|w| w.field.kind.get(styles) != kind) {
+            bail!(
+                other.field.span(),
+                "form field `{name}` is used with different kinds";
+                hint: "all form fields with the same name must be of the same kind"
+            );
+        }
+    }
+
+    write(update, &fields, signing)
+        .map_err(|err| eco_format!("failed to write form fields: {err}"))
+        .at(Span::detached())
+}
+
+/// Writes the fields into the update.
+fn write(
+    update: &mut Update,
+    fields: &IndexMap<EcoString, Vec<FormWidget>, FxBuildHasher>,
+    signing: Option<&PdfSigning>,
+) -> StrResult<()> {
+    let pages = update.pages()?;
+    let mut signature =
+        signing.map(|signing| write_placeholder(update, signing.reserved()));
+
+    let mut roots = vec![];
+    let mut annots = vec![vec![]; pages.len()];
+    let mut order = 0;
+
+    for (name, widgets) in fields {
+        let kind = widgets[0].field.kind.get(StyleChain::default());
+        let mut field = field_dict(name, widgets, kind);
+        if kind == FormFieldKind::Signature
+            && let Some(sig) = signature.take()
+        {
+            field = dict_set(&field, b"V", &write_ref(sig));
+        }
+
+        // A field with a single widget can be merged with it. Radio buttons
+        // always need a parent to group them.
+        let merged = widgets.len() == 1 && kind != FormFieldKind::Radio;
+        let ids: Vec<_> = widgets.iter().map(|_| update.alloc()).collect();
+        let parent = if merged { ids[0] } else { update.alloc() };
+
+        let mut first = TabKey::MAX;
+        for (i, (widget, &id)) in widgets.iter().zip(&ids).enumerate() {
+            let Some(&page) = pages.get(widget.page) else { continue };
+            let mut dict = widget_dict(update, widget, i, kind, page);
+            if merged {
+                for (key, value) in dict_entries(&field).unwrap_or_default() {
+                    dict = dict_set(&dict, key, value);
+                }
+            } else {
+                dict = dict_set(&dict, b"Parent", &write_ref(parent));
+            }
+            update.write(id, dict);
+
+            let index = widget.field.tab_index.get(StyleChain::default());
+            let key = TabKey { index: index.unwrap_or(usize::MAX), order };
+            annots[widget.page].push((key, id));
+            first = first.min(key);
+            order += 1;
+        }
+
+        if !merged {
+            let kids: Vec<_> = ids.iter().map(|&id| write_ref(id)).collect();
+            update.write(parent, dict_set(&field, b"Kids", &write_array(&kids)));
+        }
+
+        roots.push((first, parent));
+    }
+
+    // Without a signature field in the document, the signature goes into an
+    // invisible one on the first page.
+    if let Some(sig) = signature {
+        let Some(&page) = pages.first() else { bail!("document has no pages") };
+        let id = update.alloc();
+        let mut dict = b"<<>>".to_vec();
+        dict = dict_set(&dict, b"Type", b"/Annot");
+        dict = dict_set(&dict, b"Subtype", b"/Widget");
+        dict = dict_set(&dict, b"Rect", b"[0 0 0 0]");
+        dict = dict_set(&dict, b"P", &write_ref(page));
+        // Print and lock the widget.
+        dict = dict_set(&dict, b"F", b"132");
+        dict = dict_set(&dict, b"T", &write_text_str("Signature"));
+        dict = dict_set(&dict, b"FT", b"/Sig");
+        dict = dict_set(&dict, b"V", &write_ref(sig));
+        update.write(id, dict);
+        annots[0].push((TabKey::MAX, id));
+        roots.push((TabKey::MAX, id));
+    }
+
+    // Add the widgets to their pages in tab order.
+    for (&page, mut widgets) in pages.iter().zip(annots) {
+        if widgets.is_empty() {
+            continue;
+        }
+
+        widgets.sort_by_key(|&(key, _)| key);
+        update.add_annotations(page, widgets.into_iter().map(|(_, id)| id))?;
+    }
+
+    // Add the interactive form dictionary to the catalog.
+    let font = update.alloc();
+    update.write(
+        font,
+        b"<</Type /Font /Subtype /Type1 /BaseFont /Helvetica \
+          /Encoding /WinAnsiEncoding>>"
+            .to_vec(),
+    );
+
+    roots.sort_by_key(|&(key, _)| key);
+    let refs: Vec<_> = roots.iter().map(|&(_, id)| write_ref(id)).collect();
+    let mut form = b"<<>>".to_vec();
+    form = dict_set(&form, b"Fields", &write_array(&refs));
+    form = dict_set(&form, b"NeedAppearances", b"true");
+    form = dict_set(&form, b"DA", DEFAULT_APPEARANCE);
+    form = dict_set(&form, b"DR", format!("<</Font <</Helv {font} 0 R>>>>").as_bytes());
+    if signing.is_some() {
+        // The document contains signatures and may only be changed through
+        // incremental updates.
+        form = dict_set(&form, b"SigFlags", b"3");
+    }
+
+    let root = update.root()?;
+    let catalog = update.get(root).ok_or("failed to read document catalog")?;
+    let catalog = dict_set(catalog, b"AcroForm", &form);
+    update.write(root, catalog);
+
+    Ok(())
+}
+
+/// Determines the position of a widget in the tab order.
+///
+/// Widgets with an explicit tab index come first, all others follow in
+/// document order.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+struct TabKey {
+    index: usize,
+    order: usize,
+}
+
+impl TabKey {
+    const MAX: Self = Self { index: usize::MAX, order: usize::MAX };
+}
+
+/// Writes the dictionary entries shared by all widgets of a field.
+fn field_dict(name: &str, widgets: &[FormWidget], kind: FormFieldKind) -> Vec<u8> {
+    let styles = StyleChain::default();
+    let first = &widgets[0].field;
+
+    let mut flags = 0;
+    if first.read_only.get(styles) {
+        flags |= READ_ONLY;
+    }
+    if first.required.get(styles) {
+        flags |= REQUIRED;
+    }
+
+    let mut dict = b"<<>>".to_vec();
+    dict = dict_set(&dict, b"T", &write_text_str(name));
+    if let Some(tooltip) =
+        widgets.iter().find_map(|w| w.field.tooltip.get_ref(styles).as_ref())
+    {
+        dict = dict_set(&dict, b"TU", &write_text_str(tooltip));
+    }
+
+    match kind {
+        FormFieldKind::Text => {
+            dict = dict_set(&dict, b"FT", b"/Tx");
+            dict = dict_set(&dict, b"DA", DEFAULT_APPEARANCE);
+            if first.multiline.get(styles) {
+                flags |= MULTILINE;
+            }
+            if let Some(value) = first.value.get_ref(styles) {
+                dict = dict_set(&dict, b"V", &write_text_str(value));
+                dict = dict_set(&dict, b"DV", &write_text_str(value));
+            }
+        }
+        FormFieldKind::Checkbox | FormFieldKind::Radio => {
+            dict = dict_set(&dict, b"FT", b"/Btn");
+            if kind == FormFieldKind::Radio {
+                flags |= RADIO | NO_TOGGLE_TO_OFF;
+            }
+            let state = widgets
+                .iter()
+                .enumerate()
+                .find(|(_, w)| w.field.checked.get(styles))
+                .map_or_else(|| write_name("Off"), |(i, w)| write_name(&on_state(w, i)));
+            dict = dict_set(&dict, b"V", &state);
+            dict = dict_set(&dict, b"DV", &state);
+        }
+        FormFieldKind::Choice => {
+            dict = dict_set(&dict, b"FT", b"/Ch");
+            dict = dict_set(&dict, b"DA", DEFAULT_APPEARANCE);
+            if !first.multiline.get(styles) {
+                flags |= COMBO;
+            }
+            let options: Vec<_> = first
+                .options
+                .get_ref(styles)
+                .iter()
+                .map(|o| write_text_str(o))
+                .collect();
+            dict = dict_set(&dict, b"Opt", &write_array(&options));
+            if let Some(value) = first.value.get_ref(styles) {
+                dict = dict_set(&dict, b"V", &write_text_str(value));
+                dict = dict_set(&dict, b"DV", &write_text_str(value));
+            }
+        }
+        FormFieldKind::Signature => {
+            dict = dict_set(&dict, b"FT", b"/Sig");
+        }
+    }
+
+    if flags != 0 {
+        dict = dict_set(&dict, b"Ff", flags.to_string().as_bytes());
+    }
+
+    dict
+}
+
+/// Writes the dictionary of a single widget annotation. The `index` is the
+/// position of the widget among those of its field.
+fn widget_dict(
+    update: &mut Update,
+    widget: &FormWidget,
+    index: usize,
+    kind: FormFieldKind,
+    page: i32,
+) -> Vec<u8> {
+    let [l, b, r, t] = widget.rect;
+    let mut dict = b"<<>>".to_vec();
+    dict = dict_set(&dict, b"Type", b"/Annot");
+    dict = dict_set(&dict, b"Subtype", b"/Widget");
+    dict = dict_set(&dict, b"Rect", format!("[{l} {b} {r} {t}]").as_bytes());
+    dict = dict_set(&dict, b"P", &write_ref(page));
+    // Print the widget along with the page.
+    dict = dict_set(&dict, b"F", b"4");
+
+    // Buttons need explicit appearances for their on and off states.
+    if matches!(kind, FormFieldKind::Checkbox | FormFieldKind::Radio) {
+        let on = write_name(&on_state(widget, index));
+        let (w, h) = (r - l, t - b);
+        let marker = button_marker(w, h, kind == FormFieldKind::Radio);
+
+        let on_id = update.alloc();
+        update.write(on_id, appearance(w, h, &marker));
+        let off_id = update.alloc();
+        update.write(off_id, appearance(w, h, b""));
+
+        let mut normal = b"<<".to_vec();
+        normal.extend(&on);
+        write!(normal, " {on_id} 0 R /Off {off_id} 0 R>>").unwrap();
+        let ap = dict_set(b"<<>>", b"N", &normal);
+        dict = dict_set(&dict, b"AP", &ap);
+
+        let checked = widget.field.checked.get(StyleChain::default());
+        let state = if checked { on } else { write_name("Off") };
+        dict = dict_set(&dict, b"AS", &state);
+    }
+
+    dict
+}
+
+/// The name of the state a button takes when it is checked. The `index` is
+/// the position of the button among those of its field.
+fn on_state(widget: &FormWidget, index: usize) -> EcoString {
+    let styles = StyleChain::default();
+    match widget.field.value.get_ref(styles) {
+        Some(value) => value.clone(),
+        None if widget.field.kind.get(styles) == FormFieldKind::Radio => {
+            eco_format!("Choice{}", index + 1)
+        }
+        None => "Yes".into(),
+    }
+}
+
+/// Writes a form XObject with the given content.
+fn appearance(w: f32, h: f32, content: &[u8]) -> Vec<u8> {
+    let dict = format!("<</Type /XObject /Subtype /Form /BBox [0 0 {w} {h}]>>");
+    write_stream(dict.as_bytes(), content)
+}
+
+/// Draws the marker of a checked button: a square for check boxes and a
+/// circle for radio buttons.
+fn button_marker(w: f32, h: f32, radio: bool) -> Vec<u8> {
+    let inset = 0.2 * w.min(h);
+    let (x0, y0, x1, y1) = (inset, inset, w - inset, h - inset);
+    if !radio {
+        return format!("0 g {x0} {y0} {} {} re f", x1 - x0, y1 - y0).into_bytes();
+    }
+
+    // Approximate the circle with four Bézier curves.
+    const K: f32 = 0.552_284_8;
+    let (cx, cy) = ((x0 + x1) / 2.0, (y0 + y1) / 2.0);
+    let (rx, ry) = ((x1 - x0) / 2.0, (y1 - y0) / 2.0);
+    let (kx, ky) = (K * rx, K * ry);
+
+    let mut out = format!("0 g {} {cy} m\n", cx + rx);
+    for (sx, sy) in [(1.0, 1.0), (-1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)] {
+        // Each quadrant goes counterclockwise from one axis to the next.
+        let (from, to) = if sx * sy > 0.0 {
+            ((cx + sx * rx, cy), (cx, cy + sy * ry))
+        } else {
+            ((cx, cy + sy * ry), (cx + sx * rx, cy))
+        };
+        let c1 =
+            if from.1 == cy { (from.0, cy + sy * ky) } else { (cx + sx * kx, from.1) };
+        let c2 = if to.1 == cy { (to.0, cy + sy * ky) } else { (cx + sx * kx, to.1) };
+        out.push_str(&format!(
+            "{} {} {} {} {} {} c\n",
+            c1.0, c1.1, c2.0, c2.1, to.0, to.1
+        ));
+    }
+    out.push('f');
+    out.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use typst_library::foundations::{Content, Datetime, NativeElement, Smart};
+    use typst_library::introspection::{IntrospectorBuilder, Tag, TagFlags};
+    use typst_library::layout::{
+        Abs, Frame, FrameItem, PagedDocument, Point, Size, Transform,
+    };
+    use typst_library::model::DocumentInfo;
+    use typst_library::visualize::{Color, Geometry};
+    use typst_utils::NonZeroExt;
+
+    use super::*;
+    use crate::page::test_page;
+    use crate::{PdfOptions, PdfStandard, PdfStandards};
+
+    /// A document with a single form field, whose box is nested in a wrapper
+    /// that is encountered first.
+    fn document() -> PagedDocument {
+        let loc = Location::new(1);
+        let field = FormFieldElem::new("name".into()).pack().located(loc);
+        let flags = TagFlags { introspectable: true, tagged: false };
+
+        let size = Size::new(Abs::pt(50.0), Abs::pt(10.0));
+        let mut widget = Frame::soft(size);
+        let shape = Geometry::Rect(size).filled(Color::BLACK);
+        widget.push(Point::zero(), FrameItem::Shape(shape, Span::detached()));
+        widget.set_form_field(loc);
+        let mut wrapper = Frame::hard(Size::new(Abs::pt(70.0), Abs::pt(10.0)));
+        wrapper.push_frame(Point::with_x(Abs::pt(20.0)), widget);
+
+        let mut frame = Frame::hard(Size::splat(Abs::pt(100.0)));
+        frame.push(Point::zero(), FrameItem::Tag(Tag::Start(field, flags)));
+        frame.push_frame(Point::splat(Abs::pt(10.0)), wrapper);
+        frame.push(Point::zero(), FrameItem::Tag(Tag::End(loc, 0, flags)));
+
+        let mut builder = IntrospectorBuilder::new();
+        builder.pages = 1;
+        builder.page_numberings = vec![None];
+        builder.page_supplements = vec![Content::empty()];
+        let mut pairs = vec![];
+        builder.discover_in_frame(
+            &mut pairs,
+            &frame,
+            NonZeroUsize::ONE,
+            Transform::identity(),
+        );
+
+        let page = test_page(frame);
+        PagedDocument {
+            pages: vec![page],
+            info: Default::default(),
+            introspector: builder.finalize(pairs),
+        }
+    }
+
+    #[test]
+    fn test_widget_behind_wrapper() {
+        let options = PdfOptions { tagged: false, ..Default::default() };
+        let pdf = crate::pdf(&document(), &options).unwrap();
+        let rect = b"/Rect [30 80 80 90]";
+        assert!(pdf.windows(rect.len()).any(|w| w == rect));
+    }
+
+    #[test]
+    fn test_form_field_pdf_x() {
+        let mut document = document();
+        document.info = DocumentInfo {
+            title: Some("Print".into()),
+            date: Smart::Custom(Datetime::from_ymd(2025, 1, 1)),
+            ..Default::default()
+        };
+        let options = PdfOptions {
+            standards: PdfStandards::new(&[PdfStandard::X_4]).unwrap(),
+            tagged: false,
+            ..Default::default()
+        };
+        let errors = crate::pdf(&document, &options).unwrap_err();
+        assert_eq!(errors[0].message, "PDF/X-4 error: form fields are not supported");
+    }
+}
@@ -16,8 +16,8 @@ use typst_syntax::Span;
 use typst_utils::defer;
 
 use crate::convert::{FrameContext, GlobalContext};
-use crate::tags;
 use crate::util::{SizeExt, TransformExt};
+use crate::{print, tags};
 
 #[typst_macros::time(name = "handle image")]
 pub(crate) fn handle_image(
@@ -38,6 +38,7 @@ pub(crate) fn handle_image(
     let interpolate = image.scaling() == Smart::Custom(ImageScaling::Smooth);
 
     gc.image_spans.insert(span);
+    print::check_image(gc, image, span)?;
 
     let mut handle = tags::image(gc, fc, &mut surface, image, size);
     let surface = handle.surface();
@@ -1,29 +1,39 @@
 //! Exporting Typst documents to PDF.
 
+mod annotation;
 mod attach;
 mod convert;
+mod font;
+mod form;
 mod image;
+mod linearize;
 mod link;
 mod metadata;
 mod outline;
 mod page;
 mod paint;
+mod print;
+mod rewrite;
 mod shape;
+mod sign;
 mod tags;
 mod text;
+mod update;
 mod util;
 
 pub use self::metadata::{Timestamp, Timezone};
 
 use std::fmt::{self, Debug, Formatter};
 
-use ecow::eco_format;
+use ecow::{EcoString, eco_format};
 use krilla::configure::Validator;
 use serde::{Deserialize, Serialize};
 use typst_library::diag::{SourceResult, StrResult, bail};
-use typst_library::foundations::Smart;
+use typst_library::foundations::{Bytes, Smart};
 use typst_library::layout::{PageRanges, PagedDocument};
 
+use crate::print::{OutputIntent, PdfX};
+
 /// Export a document into a PDF file.
 ///
 /// Returns the raw bytes making up the PDF file.
@@ -32,6 +42,15 @@ pub fn pdf(document: &PagedDocument, options: &PdfOptions) -> SourceResult<Vec<u
     convert::convert(document, options)
 }
 
+/// Fill in the signature placeholder of a PDF file that was exported with
+/// [`PdfSigning::Placeholder`].
+///
+/// This allows signing a document at a later point, e.g. after it was
+/// reviewed, without re-exporting it.
+pub fn pdf_sign(pdf: &mut [u8], signer: &dyn PdfSigner) -> StrResult<()> {
+    sign::sign(pdf, signer)
+}
+
 /// Generate the document tag tree and display it in a human readable form.
 #[doc(hidden)]
 pub fn pdf_tags(document: &PagedDocument, options: &PdfOptions) -> SourceResult<String> {
@@ -66,6 +85,27 @@ pub struct PdfOptions<'a> {
     /// circumstances, for example when trying to reduce the size of a document,
     /// it can be desirable to disable tagged PDF.
     pub tagged: bool,
+    /// If not `None`, the PDF is prepared for a digital signature and, if a
+    /// signer is given, signed.
+    pub signing: Option<PdfSigning<'a>>,
+    /// How to handle fonts whose license does not permit embedding them.
+    ///
+    /// Unless fonts are embedded in full, fonts that may only be embedded in
+    /// full also count as restricted.
+    pub restricted_fonts: RestrictedFonts,
+    /// Whether to embed fonts in full instead of as subsets, so that the text
+    /// can be edited in other tools.
+    ///
+    /// Fonts in collections, CID-keyed CFF fonts, and CFF fonts below PDF 1.6
+    /// keep all their glyphs, but are still embedded as subsets. Not supported
+    /// with PDF/A and PDF/UA.
+    pub full_fonts: bool,
+    /// Whether to linearize the PDF for fast web view, so that PDF readers
+    /// can display the first page before the whole file has been loaded.
+    ///
+    /// Can be combined with signing: The signature placeholder is reserved
+    /// before linearizing and the signature is filled in place afterwards.
+    pub linearize: bool,
 }
 
 impl PdfOptions<'_> {
@@ -84,14 +124,103 @@ impl Default for PdfOptions<'_> {
             page_ranges: None,
             standards: PdfStandards::default(),
             tagged: true,
+            signing: None,
+            restricted_fonts: RestrictedFonts::default(),
+            full_fonts: false,
+            linearize: false,
+        }
+    }
+}
+
+/// How to handle fonts whose license does not permit embedding them, as
+/// declared in the `fsType` field of their `OS/2` table.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum RestrictedFonts {
+    /// Embed the fonts anyway.
+    ///
+    /// Many fonts declare stricter permissions than their license actually
+    /// imposes, so this is the default.
+    #[default]
+    Embed,
+    /// Fail with an error.
+    Error,
+    /// Draw the text set in these fonts as outlines, so that the fonts do not
+    /// need to be embedded. The text can then not be selected or searched.
+    Outline,
+}
+
+/// How to digitally sign a PDF.
+///
+/// The signature is stored in a signature field. If the document contains a
+/// `signature` form field, the first one is used. Otherwise, an invisible
+/// signature field is added to the first page.
+#[derive(Copy, Clone)]
+pub enum PdfSigning<'a> {
+    /// Reserves space for a signature of at most the given number of bytes,
+    /// which can be filled in later with [`pdf_sign`].
+    Placeholder(usize),
+    /// Signs the PDF during export.
+    Sign(&'a dyn PdfSigner),
+}
+
+impl PdfSigning<'_> {
+    /// The number of bytes reserved for the signature.
+    pub(crate) fn reserved(&self) -> usize {
+        match self {
+            Self::Placeholder(size) => *size,
+            Self::Sign(signer) => signer.max_size(),
+        }
+    }
+}
+
+impl Debug for PdfSigning<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Placeholder(size) => f.debug_tuple("Placeholder").field(size).finish(),
+            Self::Sign(_) => f.pad("Sign(..)"),
         }
     }
 }
 
+/// Produces digital signatures for PDF files.
+///
+/// This is implemented for all functions and closures with a matching
+/// signature.
+pub trait PdfSigner {
+    /// Signs the given data and returns a DER-encoded, detached PKCS#7 or CMS
+    /// signature.
+    ///
+    /// The data is the complete PDF file except for the placeholder the
+    /// signature is written into.
+    fn sign(&self, data: &[u8]) -> StrResult<Vec<u8>>;
+
+    /// The maximum size of the signatures this signer produces, in bytes.
+    ///
+    /// This much space is reserved in the file before signing. The default
+    /// suffices for a signature with a typical certificate chain.
+    fn max_size(&self) -> usize {
+        8192
+    }
+}
+
+impl<F> PdfSigner for F
+where
+    F: Fn(&[u8]) -> StrResult<Vec<u8>>,
+{
+    fn sign(&self, data: &[u8]) -> StrResult<Vec<u8>> {
+        self(data)
+    }
+}
+
 /// Encapsulates a list of compatible PDF standards.
 #[derive(Clone)]
 pub struct PdfStandards {
     pub(crate) config: krilla::configure::Configuration,
+    /// The PDF/X standard for print production, if any.
+    pub(crate) pdf_x: Option<PdfX>,
+    /// The output intent that describes the intended printing condition. Only
+    /// written for PDF/X.
+    pub(crate) output_intent: OutputIntent,
 }
 
 impl PdfStandards {
@@ -122,6 +251,15 @@ impl PdfStandards {
             Ok(())
         };
 
+        let mut pdf_x = None;
+        let mut set_pdf_x = |x: PdfX| -> StrResult<()> {
+            if pdf_x.is_some() {
+                bail!("Typst currently only supports one PDF substandard at a time");
+            }
+            pdf_x = Some(x);
+            Ok(())
+        };
+
         for standard in list {
             match standard {
                 PdfStandard::V_1_4 => set_version(PdfVersion::Pdf14)?,
@@ -141,7 +279,32 @@ impl PdfStandards {
                 PdfStandard::A_4f => set_validator(Validator::A4F)?,
                 PdfStandard::A_4e => set_validator(Validator::A4E)?,
                 PdfStandard::Ua_1 => set_validator(Validator::UA1)?,
+                PdfStandard::X_1a => set_pdf_x(PdfX::X1a)?,
+                PdfStandard::X_3 => set_pdf_x(PdfX::X3)?,
+                PdfStandard::X_4 => set_pdf_x(PdfX::X4)?,
+            }
+        }
+
+        // PDF/X is not validated by krilla, so we only need to check the
+        // version here.
+        if let Some(x) = pdf_x {
+            if validator.is_some() {
+                bail!("Typst currently only supports one PDF substandard at a time");
             }
+
+            let version = match version {
+                Some(version) if !x.versions().contains(&version) => {
+                    bail!("{} is not compatible with {}", version.as_str(), x.as_str())
+                }
+                Some(version) => version,
+                None => *x.versions().last().unwrap(),
+            };
+
+            return Ok(Self {
+                config: Configuration::new_with_version(version),
+                pdf_x,
+                output_intent: OutputIntent::default(),
+            });
         }
 
         let config = match (version, validator) {
@@ -159,7 +322,29 @@ impl PdfStandards {
             (None, None) => Configuration::new_with_version(PdfVersion::Pdf17),
         };
 
-        Ok(Self { config })
+        Ok(Self {
+            config,
+            pdf_x: None,
+            output_intent: OutputIntent::default(),
+        })
+    }
+
+    /// Sets the output intent written for PDF/X, consisting of an ICC profile
+    /// and an identifier for the printing condition it describes, e.g.
+    /// `FOGRA39`.
+    ///
+    /// By default, a profile for the CGATS TR 001 printing condition is used.
+    pub fn with_output_intent(
+        mut self,
+        profile: Bytes,
+        condition: impl Into<EcoString>,
+    ) -> StrResult<Self> {
+        let intent = OutputIntent::new(profile, condition.into())?;
+        if self.pdf_x == Some(PdfX::X1a) && intent.components != 4 {
+            bail!("PDF/X-1a requires a CMYK output intent");
+        }
+        self.output_intent = intent;
+        Ok(self)
     }
 }
 
@@ -174,6 +359,8 @@ impl Default for PdfStandards {
         use krilla::configure::{Configuration, PdfVersion};
         Self {
             config: Configuration::new_with_version(PdfVersion::Pdf17),
+            pdf_x: None,
+            output_intent: OutputIntent::default(),
         }
     }
 }
@@ -236,4 +423,13 @@ pub enum PdfStandard {
     /// PDF/UA-1.
     #[serde(rename = "ua-1")]
     Ua_1,
+    /// PDF/X-1a:2003.
+    #[serde(rename = "x-1a")]
+    X_1a,
+    /// PDF/X-3:2003.
+    #[serde(rename = "x-3")]
+    X_3,
+    /// PDF/X-4.
+    #[serde(rename = "x-4")]
+    X_4,
 }
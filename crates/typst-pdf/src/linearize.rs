@@ -0,0 +1,767 @@
+//! Linearization of PDF files for fast web view.
+//!
+//! A linearized file starts with everything needed to display the first page,
+//! followed by the remaining pages in order. A linearization dictionary at the
+//! very start of the file and hint tables tell the reader where each page
+//! lives, so that it can show the first page before the rest of the file has
+//! been downloaded.
+//!
+//! krilla does not linearize files, so we rewrite the finished file: All
+//! objects are renumbered and reordered as described in Annex F of the PDF
+//! specification. Objects that are no longer referenced, for example ones
+//! replaced by incremental updates, are dropped in the process.
+
+use std::collections::VecDeque;
+use std::io::Write;
+
+use ecow::eco_format;
+use rustc_hash::{FxHashMap, FxHashSet};
+use typst_library::diag::{At, SourceResult, StrResult, bail};
+use typst_syntax::Span;
+
+use crate::update::{
+    Update, dict_get, is_delimiter, is_whitespace, parse_int, parse_ref,
+};
+
+/// The number of digits reserved for offsets and lengths that are written
+/// before they are known.
+const DIGITS: usize = 10;
+
+/// Rewrites a finished PDF file into a linearized one.
+pub(crate) fn linearize(pdf: Vec<u8>) -> SourceResult<Vec<u8>> {
+    write(pdf)
+        .map_err(|err| eco_format!("failed to linearize PDF: {err}"))
+        .at(Span::detached())
+}
+
+/// Performs the linearization.
+fn write(pdf: Vec<u8>) -> StrResult<Vec<u8>> {
+    let header = pdf.split(|&b| b == b'\n').next().unwrap_or(b"%PDF-1.7").to_vec();
+    let reader = Update::new(pdf)?;
+    let layout = Layout::new(&reader)?;
+
+    let mut objects = FxHashMap::default();
+    for &id in layout.order() {
+        let body = reader.get_raw(id).ok_or("failed to read object")?;
+        objects.insert(id, renumber(body, &layout.numbers));
+    }
+
+    let trailer = reader.trailer();
+    let mut state = State::default();
+
+    // The positions of the objects depend on the size of the hint stream and
+    // the hint stream depends on the positions. We thus assemble the file
+    // until nothing changes anymore. The hint stream never shrinks, so this
+    // terminates quickly.
+    for _ in 0..16 {
+        let (out, next) = assemble(&header, trailer, &layout, &objects, &state)?;
+        if next == state {
+            return Ok(out);
+        }
+        state = next;
+    }
+
+    bail!("layout did not converge")
+}
+
+/// Where the objects of the original file go.
+struct Layout {
+    /// The objects needed for the first page, starting with the page object.
+    first_page: Vec<i32>,
+    /// The objects of the remaining pages, each starting with the page object.
+    pages: Vec<Vec<i32>>,
+    /// Objects used by multiple pages that are not needed for the first page.
+    shared: Vec<i32>,
+    /// All other objects.
+    rest: Vec<i32>,
+    /// The document catalog.
+    catalog: i32,
+    /// The objects each page refers to that are used by multiple pages.
+    shared_refs: Vec<Vec<i32>>,
+    /// The new object numbers, by old object number.
+    numbers: FxHashMap<i32, i32>,
+    /// The first new object number of the first-page section. The
+    /// linearization dictionary gets this number.
+    split: i32,
+    /// The number of objects in the new file, including object zero.
+    size: i32,
+}
+
+impl Layout {
+    /// Determines the order of the objects.
+    fn new(reader: &Update) -> StrResult<Self> {
+        let catalog = reader.root()?;
+        let page_ids = reader.pages()?;
+        if page_ids.is_empty() {
+            bail!("document has no pages");
+        }
+
+        let refs = |id: i32| -> Vec<i32> {
+            let mut refs = vec![];
+            if let Some(body) = reader.get_raw(id) {
+                map_refs(body, |id| {
+                    refs.push(id);
+                    id
+                });
+            }
+            refs
+        };
+
+        // Objects that pages must not pull in: other pages, the page tree and
+        // the catalog.
+        let mut barrier: FxHashSet<i32> = page_ids.iter().copied().collect();
+        barrier.insert(catalog);
+        let mut queue = VecDeque::from([catalog]);
+        let mut seen = FxHashSet::default();
+        while let Some(id) = queue.pop_front() {
+            if !seen.insert(id) {
+                continue;
+            }
+            let Some(dict) = reader.get(id) else { continue };
+            if id == catalog || dict_get(dict, b"Type") == Some(b"/Pages") {
+                barrier.insert(id);
+                let kids = dict_get(dict, b"Kids").or_else(|| dict_get(dict, b"Pages"));
+                queue.extend(kids.into_iter().flat_map(|kids| {
+                    let mut ids = vec![];
+                    map_refs(kids, |id| {
+                        ids.push(id);
+                        id
+                    });
+                    ids
+                }));
+            }
+        }
+
+        // Collect the objects reachable from each page.
+        let mut page_objects = vec![];
+        let mut users: FxHashMap<i32, usize> = FxHashMap::default();
+        for &page in &page_ids {
+            let objects = reachable(page, refs, |id| id != page && barrier.contains(&id));
+            for &id in &objects {
+                *users.entry(id).or_default() += 1;
+            }
+            page_objects.push(objects);
+        }
+
+        let is_shared = |id: &i32| users.get(id).is_some_and(|&n| n > 1);
+        let first_page = page_objects[0].clone();
+        let placed_first: FxHashSet<i32> = first_page.iter().copied().collect();
+
+        let pages: Vec<Vec<i32>> = page_objects[1..]
+            .iter()
+            .map(|objects| objects.iter().copied().filter(|id| !is_shared(id)).collect())
+            .collect();
+
+        let mut shared = vec![];
+        let mut placed: FxHashSet<i32> = placed_first.clone();
+        placed.extend(pages.iter().flatten().copied());
+        for objects in &page_objects[1..] {
+            for &id in objects {
+                if is_shared(&id) && placed.insert(id) {
+                    shared.push(id);
+                }
+            }
+        }
+
+        let shared_refs = page_objects
+            .iter()
+            .map(|objects| objects.iter().copied().filter(is_shared).collect())
+            .collect();
+
+        // Everything else that is still referenced from the trailer.
+        placed.insert(catalog);
+        let mut roots = vec![catalog];
+        roots.extend(reader.info());
+        let mut rest = vec![];
+        for root in roots {
+            for id in reachable(root, refs, |_| false) {
+                if placed.insert(id) {
+                    rest.push(id);
+                }
+            }
+        }
+
+        // The first-page section gets the highest numbers: the linearization
+        // dictionary, the catalog, the hint stream, and the first page.
+        let mut numbers = FxHashMap::default();
+        let mut next = 1;
+        for &id in pages.iter().flatten().chain(&shared).chain(&rest) {
+            numbers.insert(id, next);
+            next += 1;
+        }
+        let split = next;
+        numbers.insert(catalog, split + 1);
+        next = split + 3;
+        for &id in &first_page {
+            numbers.insert(id, next);
+            next += 1;
+        }
+
+        Ok(Self {
+            first_page,
+            pages,
+            shared,
+            rest,
+            catalog,
+            shared_refs,
+            numbers,
+            split,
+            size: next,
+        })
+    }
+
+    /// All objects of the original file that are kept.
+    fn order(&self) -> impl Iterator<Item = &i32> {
+        std::iter::once(&self.catalog)
+            .chain(&self.first_page)
+            .chain(self.pages.iter().flatten())
+            .chain(&self.shared)
+            .chain(&self.rest)
+    }
+}
+
+/// The values that are only known after the file has been assembled.
+#[derive(Default, PartialEq)]
+struct State {
+    /// The data of the hint stream.
+    hints: Vec<u8>,
+    /// The offset of the shared object hint table in the hint stream.
+    shared_hints: usize,
+    /// The offsets of the objects in the first-page section.
+    first_offsets: Vec<usize>,
+    /// The offset and length of the hint stream object.
+    hint_range: (usize, usize),
+    /// The offset of the end of the first page.
+    first_page_end: usize,
+    /// The offset of the white-space before the first entry of the main
+    /// cross-reference table.
+    main_xref_entry: usize,
+    /// The offset of the main cross-reference table.
+    main_xref: usize,
+    /// The length of the file.
+    len: usize,
+}
+
+/// Assembles the linearized file with the values of the previous pass and
+/// returns it along with the values for the next pass.
+fn assemble(
+    header: &[u8],
+    trailer: &[u8],
+    layout: &Layout,
+    objects: &FxHashMap<i32, Vec<u8>>,
+    state: &State,
+) -> StrResult<(Vec<u8>, State)> {
+    let num = |id: i32| layout.numbers[&id];
+    let mut out = header.to_vec();
+    out.extend(b"\n%\xe2\xe3\xcf\xd3\n");
+
+    let mut spans: FxHashMap<i32, (usize, usize)> = FxHashMap::default();
+    let mut first_offsets = vec![];
+
+    // Part 2: The linearization dictionary.
+    first_offsets.push(out.len());
+    writeln!(out, "{} 0 obj", layout.split).unwrap();
+    write!(
+        out,
+        "<</Linearized 1 /L {:0DIGITS$} /H [{:0DIGITS$} {:0DIGITS$}] /O {} \
+         /E {:0DIGITS$} /N {} /T {:0DIGITS$}>>",
+        state.len,
+        state.hint_range.0,
+        state.hint_range.1,
+        num(layout.first_page[0]),
+        state.first_page_end,
+        layout.pages.len() + 1,
+        state.main_xref_entry,
+    )
+    .unwrap();
+    out.extend(b"\nendobj\n");
+
+    // Part 3: The cross-reference table and trailer of the first-page
+    // section.
+    let first_xref = out.len();
+    let count = layout.size - layout.split;
+    writeln!(out, "xref\n{} {count}", layout.split).unwrap();
+    for i in 0..count as usize {
+        let offset = state.first_offsets.get(i).copied().unwrap_or_default();
+        write!(out, "{offset:010} 00000 n\r\n").unwrap();
+    }
+    write!(out, "trailer\n<</Size {} /Root {} 0 R", layout.size, num(layout.catalog))
+        .unwrap();
+    if let Some(info) = dict_get(trailer, b"Info").and_then(parse_ref) {
+        write!(out, " /Info {} 0 R", num(info)).unwrap();
+    }
+    if let Some(id) = dict_get(trailer, b"ID") {
+        out.extend(b" /ID ");
+        out.extend(id);
+    }
+    write!(out, " /Prev {:0DIGITS$}>>\nstartxref\n0\n%%EOF\n", state.main_xref).unwrap();
+
+    let mut write_object = |out: &mut Vec<u8>, id: i32| {
+        let start = out.len();
+        writeln!(out, "{} 0 obj", num(id)).unwrap();
+        out.extend(&objects[&id]);
+        out.extend(b"\nendobj\n");
+        spans.insert(id, (start, out.len()));
+        start
+    };
+
+    // Part 4: The catalog.
+    first_offsets.push(write_object(&mut out, layout.catalog));
+
+    // Part 5: The hint stream.
+    let hint_start = out.len();
+    first_offsets.push(hint_start);
+    writeln!(out, "{} 0 obj", layout.split + 2).unwrap();
+    write!(out, "<</S {} /Length {}>>\nstream\n", state.shared_hints, state.hints.len())
+        .unwrap();
+    out.extend(&state.hints);
+    out.extend(b"\nendstream\nendobj\n");
+    let hint_range = (hint_start, out.len() - hint_start);
+
+    // Part 6: The first page.
+    for &id in &layout.first_page {
+        first_offsets.push(write_object(&mut out, id));
+    }
+    let first_page_end = out.len();
+
+    // Parts 7 to 9: The remaining pages, shared objects, and everything else.
+    for &id in layout
+        .pages
+        .iter()
+        .flatten()
+        .chain(&layout.shared)
+        .chain(&layout.rest)
+    {
+        write_object(&mut out, id);
+    }
+
+    // Part 11: The main cross-reference table and trailer.
+    let main_xref = out.len();
+    write!(out, "xref\n0 {}", layout.split).unwrap();
+    let main_xref_entry = out.len();
+    out.extend(b"\n0000000000 65535 f\r\n");
+    let mut main: Vec<(i32, usize)> = layout
+        .pages
+        .iter()
+        .flatten()
+        .chain(&layout.shared)
+        .chain(&layout.rest)
+        .map(|&id| (num(id), spans[&id].0))
+        .collect();
+    main.sort();
+    for (_, offset) in main {
+        write!(out, "{offset:010} 00000 n\r\n").unwrap();
+    }
+    write!(out, "trailer\n<</Size {}>>\nstartxref\n{first_xref}\n%%EOF\n", layout.split)
+        .unwrap();
+
+    let (mut hints, shared_hints) = write_hints(layout, &spans, hint_range)?;
+    if hints.len() < state.hints.len() {
+        hints.resize(state.hints.len(), 0);
+    }
+
+    let next = State {
+        hints,
+        shared_hints,
+        first_offsets,
+        hint_range,
+        first_page_end,
+        main_xref_entry,
+        main_xref,
+        len: out.len(),
+    };
+
+    Ok((out, next))
+}
+
+/// Writes the page offset and shared object hint tables. Returns the data of
+/// the hint stream and the offset of the shared object hint table in it.
+fn write_hints(
+    layout: &Layout,
+    spans: &FxHashMap<i32, (usize, usize)>,
+    hint_range: (usize, usize),
+) -> StrResult<(Vec<u8>, usize)> {
+    // Offsets in the hint tables are given as if the hint stream was not
+    // there.
+    let offset = |id: i32| spans[&id].0 - hint_range.1;
+    let span_len = |ids: &[i32]| match (ids.first(), ids.last()) {
+        (Some(first), Some(last)) => spans[last].1 - spans[first].0,
+        _ => 0,
+    };
+
+    // The shared object groups: one per object of the first page, followed
+    // by one per shared object. Each group consists of a single object.
+    let groups: Vec<i32> =
+        layout.first_page.iter().chain(&layout.shared).copied().collect();
+    let group_index: FxHashMap<i32, usize> =
+        groups.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    let group_lengths: Vec<usize> = groups.iter().map(|&id| span_len(&[id])).collect();
+
+    // The number of objects and the lengths of the pages.
+    let pages = std::iter::once(&layout.first_page).chain(&layout.pages);
+    let object_counts: Vec<usize> = pages.clone().map(Vec::len).collect();
+    let lengths: Vec<usize> = pages.map(|page| span_len(page)).collect();
+    let shared_ids: Vec<Vec<usize>> = layout
+        .shared_refs
+        .iter()
+        .map(|refs| refs.iter().filter_map(|id| group_index.get(id).copied()).collect())
+        .collect();
+
+    let least_objects = object_counts.iter().copied().min().unwrap_or(0);
+    let most_objects = object_counts.iter().copied().max().unwrap_or(0);
+    let least_length = lengths.iter().copied().min().unwrap_or(0);
+    let most_length = lengths.iter().copied().max().unwrap_or(0);
+    let most_shared = shared_ids.iter().map(Vec::len).max().unwrap_or(0);
+    let greatest_id = shared_ids.iter().flatten().copied().max().unwrap_or(0);
+
+    let objects_bits = bits(most_objects - least_objects);
+    let length_bits = bits(most_length - least_length);
+    let shared_bits = bits(most_shared);
+    let id_bits = bits(greatest_id);
+
+    // The header of the page offset hint table. The content stream of each
+    // page is described as spanning the whole page, so that readers fetch
+    // all of it. Shared objects are not subdivided, so their position within
+    // a page needs no bits.
+    let mut w = BitWriter::default();
+    w.write(least_objects, 32)?;
+    w.write(offset(layout.first_page[0]), 32)?;
+    w.write(objects_bits, 16)?;
+    w.write(least_length, 32)?;
+    w.write(length_bits, 16)?;
+    w.write(0, 32)?;
+    w.write(0, 16)?;
+    w.write(least_length, 32)?;
+    w.write(length_bits, 16)?;
+    w.write(shared_bits, 16)?;
+    w.write(id_bits, 16)?;
+    w.write(0, 16)?;
+    w.write(0, 16)?;
+
+    // The entries of the page offset hint table, one item for all pages at
+    // a time.
+    for &count in &object_counts {
+        w.write(count - least_objects, objects_bits)?;
+    }
+    w.flush();
+    for &length in &lengths {
+        w.write(length - least_length, length_bits)?;
+    }
+    w.flush();
+    for ids in &shared_ids {
+        w.write(ids.len(), shared_bits)?;
+    }
+    w.flush();
+    for &id in shared_ids.iter().flatten() {
+        w.write(id, id_bits)?;
+    }
+    w.flush();
+    for &length in &lengths {
+        w.write(length - least_length, length_bits)?;
+    }
+    w.flush();
+
+    // The shared object hint table.
+    let shared_hints = w.out.len();
+    let least_group = group_lengths.iter().copied().min().unwrap_or(0);
+    let most_group = group_lengths.iter().copied().max().unwrap_or(0);
+    let group_bits = bits(most_group - least_group);
+    let first_shared = layout.shared.first().copied();
+    w.write(first_shared.map_or(0, |id| layout.numbers[&id] as usize), 32)?;
+    w.write(first_shared.map_or(0, offset), 32)?;
+    w.write(layout.first_page.len(), 32)?;
+    w.write(groups.len(), 32)?;
+    w.write(0, 16)?;
+    w.write(least_group, 32)?;
+    w.write(group_bits, 16)?;
+    for &length in &group_lengths {
+        w.write(length - least_group, group_bits)?;
+    }
+    w.flush();
+    // No group has an MD5 signature.
+    for _ in &groups {
+        w.write(0, 1)?;
+    }
+    w.flush();
+
+    Ok((w.out, shared_hints))
+}
+
+/// Collects the objects reachable from an object in breadth-first order,
+/// without following references to objects for which `stop` returns true.
+fn reachable(
+    start: i32,
+    refs: impl Fn(i32) -> Vec<i32>,
+    stop: impl Fn(i32) -> bool,
+) -> Vec<i32> {
+    let mut order = vec![];
+    let mut seen = FxHashSet::default();
+    let mut queue = VecDeque::from([start]);
+    while let Some(id) = queue.pop_front() {
+        if stop(id) || !seen.insert(id) {
+            continue;
+        }
+        order.push(id);
+        queue.extend(refs(id));
+    }
+    order
+}
+
+/// Rewrites the indirect references in an object to the new object numbers.
+fn renumber(body: &[u8], numbers: &FxHashMap<i32, i32>) -> Vec<u8> {
+    // References to objects that were dropped become references to object
+    // zero, which PDF readers treat as null.
+    map_refs(body, |id| numbers.get(&id).copied().unwrap_or(0))
+}
+
+/// Calls `f` for each indirect reference in an object and replaces the object
+/// number with the returned one. Stream data is copied as is.
+fn map_refs(body: &[u8], mut f: impl FnMut(i32) -> i32) -> Vec<u8> {
+    let is_regular = |b: u8| !is_whitespace(b) && !is_delimiter(b);
+    let mut out = Vec::with_capacity(body.len());
+    let mut i = 0;
+
+    while let Some(&b) = body.get(i) {
+        let start = i;
+        match b {
+            b'(' => {
+                let mut depth = 0;
+                while let Some(&c) = body.get(i) {
+                    i += 1;
+                    match c {
+                        b'\\' => i += 1,
+                        b'(' => depth += 1,
+                        b')' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            b'<' if body.get(i + 1) == Some(&b'<') => i += 2,
+            b'<' => {
+                i += body[i..]
+                    .iter()
+                    .position(|&c| c == b'>')
+                    .map_or(body.len(), |p| p + 1);
+            }
+            b'%' => {
+                i += body[i..]
+                    .iter()
+                    .position(|&c| c == b'\n' || c == b'\r')
+                    .unwrap_or(body.len() - i);
+            }
+            b'/' => {
+                i += 1;
+                while body.get(i).is_some_and(|&c| is_regular(c)) {
+                    i += 1;
+                }
+            }
+            _ if is_regular(b) => {
+                while body.get(i).is_some_and(|&c| is_regular(c)) {
+                    i += 1;
+                }
+                let token = &body[start..i];
+                if token == b"stream" {
+                    out.extend(&body[start..]);
+                    return out;
+                }
+                if let Some(id) = parse_int(token).filter(|&id| id > 0)
+                    && let Some(end) = match_ref(body, i)
+                {
+                    write!(out, "{} 0 R", f(id as i32)).unwrap();
+                    i = end;
+                    continue;
+                }
+            }
+            _ => i += 1,
+        }
+        out.extend(&body[start..i.min(body.len())]);
+    }
+
+    out
+}
+
+/// Checks whether a generation number and the `R` keyword follow at the
+/// given position. Returns the position after the keyword.
+fn match_ref(body: &[u8], mut i: usize) -> Option<usize> {
+    let is_regular = |b: u8| !is_whitespace(b) && !is_delimiter(b);
+    let token = |i: &mut usize| {
+        while body.get(*i).is_some_and(|&c| is_whitespace(c)) {
+            *i += 1;
+        }
+        let start = *i;
+        while body.get(*i).is_some_and(|&c| is_regular(c)) {
+            *i += 1;
+        }
+        &body[start..*i]
+    };
+
+    parse_int(token(&mut i)).filter(|&generation| generation >= 0)?;
+    (token(&mut i) == b"R").then_some(i)
+}
+
+/// The number of bits needed to represent a value.
+fn bits(value: usize) -> usize {
+    (usize::BITS - value.leading_zeros()) as usize
+}
+
+/// Writes values with arbitrary bit widths, most significant bit first.
+#[derive(Default)]
+struct BitWriter {
+    out: Vec<u8>,
+    /// The bits written to the current byte so far.
+    current: u8,
+    /// The number of bits written to the current byte.
+    filled: u32,
+}
+
+impl BitWriter {
+    /// Writes the lowest `width` bits of a value.
+    fn write(&mut self, value: usize, width: usize) -> StrResult<()> {
+        if width < usize::BITS as usize && value >> width != 0 {
+            bail!("hint table value does not fit");
+        }
+
+        for i in (0..width).rev() {
+            let bit = ((value >> i) & 1) as u8;
+            self.current |= bit << (7 - self.filled);
+            self.filled += 1;
+            if self.filled == 8 {
+                self.flush();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pads the current byte with zeros.
+    fn flush(&mut self) {
+        if self.filled > 0 {
+            self.out.push(self.current);
+            self.current = 0;
+            self.filled = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A file with two pages that share a font and whose catalog was replaced
+    /// by an incremental update.
+    fn two_page_pdf() -> Vec<u8> {
+        let objects: [&[u8]; 7] = [
+            b"<</Type /Catalog /Pages 2 0 R>>",
+            b"<</Type /Pages /Kids [3 0 R 4 0 R] /Count 2>>",
+            b"<</Type /Page /Parent 2 0 R /Contents 5 0 R \
+              /Resources <</Font <</F1 7 0 R>>>>>>",
+            b"<</Type /Page /Parent 2 0 R /Contents 6 0 R \
+              /Resources <</Font <</F1 7 0 R>>>>>>",
+            b"<</Length 11>>\nstream\n(1 0 R) Tj\n\nendstream",
+            b"<</Length 11>>\nstream\n(2 0 R) Tj\n\nendstream",
+            b"<</Type /Font /Subtype /Type1 /BaseFont /Helvetica>>",
+        ];
+
+        let mut pdf = b"%PDF-1.7\n".to_vec();
+        let mut offsets = vec![];
+        for (i, body) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            writeln!(pdf, "{} 0 obj", i + 1).unwrap();
+            pdf.extend(*body);
+            pdf.extend(b"\nendobj\n");
+        }
+
+        let xref = pdf.len();
+        writeln!(pdf, "xref\n0 {}\n0000000000 65535 f\r", objects.len() + 1).unwrap();
+        for offset in offsets {
+            write!(pdf, "{offset:010} 00000 n\r\n").unwrap();
+        }
+        write!(pdf, "trailer\n<</Size 8 /Root 1 0 R>>\nstartxref\n{xref}\n%%EOF\n")
+            .unwrap();
+
+        let mut update = Update::new(pdf).unwrap();
+        update.write(1, b"<</Type /Catalog /Pages 2 0 R /PageMode /UseNone>>".to_vec());
+        update.finish()
+    }
+
+    /// Reads an integer entry of the linearization dictionary.
+    fn entry(dict: &[u8], key: &[u8]) -> usize {
+        dict_get(dict, key).and_then(parse_int).unwrap() as usize
+    }
+
+    #[test]
+    fn test_linearize() {
+        let pdf = linearize(two_page_pdf()).unwrap();
+
+        // The linearization dictionary is the first object.
+        let start = pdf.windows(3).position(|w| w == b"obj").unwrap() + 3;
+        let end = pdf.windows(6).position(|w| w == b"endobj").unwrap();
+        let dict = &pdf[start..end];
+        assert!(start < 1024);
+        assert_eq!(entry(dict, b"Linearized"), 1);
+        assert_eq!(entry(dict, b"L"), pdf.len());
+        assert_eq!(entry(dict, b"N"), 2);
+
+        // The first page object lies within the first-page section.
+        let first_page = entry(dict, b"O");
+        let marker = format!("\n{first_page} 0 obj\n<</Type /Page ");
+        let pos = pdf.windows(marker.len()).position(|w| w == marker.as_bytes());
+        assert!(pos.unwrap() < entry(dict, b"E"));
+
+        // The hint stream is where the dictionary says.
+        let hints = dict_get(dict, b"H").and_then(crate::update::parse_array).unwrap();
+        let hint_offset = parse_int(hints[0]).unwrap() as usize;
+        assert!(pdf[hint_offset..].starts_with(b"6 0 obj\n<</S "));
+
+        // The main cross-reference table follows `/T`.
+        let t = entry(dict, b"T");
+        assert!(pdf[..t].ends_with(b"xref\n0 4"));
+        assert!(pdf[t..].starts_with(b"\n0000000000 65535 f"));
+
+        // The file can still be read like any other.
+        let reader = Update::new(pdf.clone()).unwrap();
+        assert_eq!(reader.pages().unwrap().len(), 2);
+        let catalog = reader.get(reader.root().unwrap()).unwrap();
+        assert_eq!(dict_get(catalog, b"PageMode"), Some(b"/UseNone".as_slice()));
+
+        // Stream data is copied without changes.
+        assert_eq!(pdf.windows(11).filter(|w| w == b"(1 0 R) Tj\n").count(), 1);
+    }
+
+    #[test]
+    fn test_linearize_and_sign() {
+        // The placeholder is reserved before linearizing and the signature is
+        // filled in place afterwards, so the layout stays valid.
+        let mut update = Update::new(two_page_pdf()).unwrap();
+        let sig = crate::sign::write_placeholder(&mut update, 4);
+        let catalog =
+            format!("<</Type /Catalog /Pages 2 0 R /Perms <</DocMDP {sig} 0 R>>>>");
+        update.write(1, catalog.into_bytes());
+        let mut pdf = linearize(update.finish()).unwrap();
+        let unsigned = pdf.clone();
+
+        let signer = |_: &[u8]| -> StrResult<Vec<u8>> { Ok(vec![0xCA, 0xFE]) };
+        crate::sign::sign(&mut pdf, &signer).unwrap();
+        assert_eq!(pdf.len(), unsigned.len());
+        assert!(pdf.windows(10).any(|w| w == b"<CAFE0000>"));
+
+        // Only the byte range and the signature changed.
+        let changed: Vec<usize> =
+            (0..pdf.len()).filter(|&i| pdf[i] != unsigned[i]).collect();
+        let range = pdf.windows(12).position(|w| w == b"/ByteRange [").unwrap();
+        let contents = pdf.windows(11).position(|w| w == b"/Contents <").unwrap();
+        assert!(changed.iter().all(|&i| i > range && i < contents + 20));
+    }
+
+    #[test]
+    fn test_map_refs() {
+        let body = b"<</A 1 0 R /B [2 0 R 3] /C (4 0 R) /D5 6 0 R>>";
+        let out = map_refs(body, |id| id * 10);
+        assert_eq!(out, b"<</A 10 0 R /B [20 0 R 3] /C (4 0 R) /D5 60 0 R>>");
+    }
+}
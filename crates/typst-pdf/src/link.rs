@@ -1,14 +1,22 @@
+use ecow::eco_format;
 use krilla::action::{Action, LinkAction};
 use krilla::annotation::Target;
-use krilla::destination::XyzDestination;
+use krilla::configure::Validator;
+use krilla::destination::{NamedDestination, XyzDestination};
 use krilla::geom as kg;
-use typst_library::diag::{SourceResult, bail};
-use typst_library::layout::{Abs, Point, Position, Size};
-use typst_library::model::Destination;
+use rustc_hash::{FxHashMap, FxHashSet};
+use typst_library::diag::{At, SourceResult, StrResult, bail};
+use typst_library::foundations::NativeElement;
+use typst_library::introspection::Location;
+use typst_library::layout::{Abs, PagedDocument, Point, Position, Size};
+use typst_library::model::{Destination, FileLink, FileTarget};
+use typst_library::pdf::AttachElem;
 use typst_syntax::Span;
 
+use crate::annotation::to_pdf_rect;
 use crate::convert::{FrameContext, GlobalContext, PageIndexConverter};
 use crate::tags::{self, AnnotationId, GroupId};
+use crate::update::{Update, dict_set, write_ref, write_text_str};
 use crate::util::PointExt;
 
 pub(crate) struct LinkAnnotation {
@@ -26,12 +34,31 @@ pub(crate) enum LinkAnnotationKind {
     Artifact,
 }
 
+/// A link into another file.
+///
+/// krilla can only write links within the document and to URLs, so these are
+/// added to the finished file.
+pub(crate) struct FileLinkAnnotation {
+    /// The index of the page the link is on.
+    page: usize,
+    /// The area of the link in PDF coordinates (left, bottom, right, top).
+    rect: [f32; 4],
+    /// Where the link goes.
+    link: FileLink,
+}
+
 pub(crate) fn handle_link(
     fc: &mut FrameContext,
     gc: &mut GlobalContext,
     dest: &Destination,
     size: Size,
 ) -> SourceResult<()> {
+    // Links have no printed appearance, but PDF/X restricts annotations on
+    // the page, so they are left out.
+    if gc.options.standards.pdf_x.is_some() {
+        return Ok(());
+    }
+
     let target = match dest {
         Destination::Url(u) => {
             Target::Action(Action::Link(LinkAction::new(u.to_string())))
@@ -42,6 +69,7 @@ pub(crate) fn handle_link(
             };
             Target::Destination(krilla::destination::Destination::Xyz(dest))
         }
+        Destination::File(link) => return handle_file_link(fc, gc, link, size),
         Destination::Location(loc) => {
             if let Some(nd) = gc.loc_to_names.get(loc) {
                 // If a named destination has been registered, it's already guaranteed to
@@ -144,8 +172,36 @@ pub(crate) fn handle_link(
     Ok(())
 }
 
+/// Records a link into another file.
+fn handle_file_link(
+    fc: &mut FrameContext,
+    gc: &mut GlobalContext,
+    link: &FileLink,
+    size: Size,
+) -> SourceResult<()> {
+    let validator = gc.options.standards.config.validator();
+    if validator != Validator::None {
+        let span = match gc.tags.tree.parent_link() {
+            Some((_, link)) if !tags::disabled(gc) => link.span(),
+            _ => Span::detached(),
+        };
+        bail!(
+            span,
+            "{} error: links to other files are not supported", validator.as_str();
+            hint: "export without a PDF standard to link to other files"
+        );
+    }
+
+    let Some(page) = fc.page_idx else { return Ok(()) };
+    let rect = to_pdf_rect(fc, bounding_box(fc, size));
+    gc.file_links
+        .push(FileLinkAnnotation { page, rect, link: link.clone() });
+
+    Ok(())
+}
+
 /// Compute the bouding box of the transformed rectangle for this frame.
-fn bounding_box(fc: &FrameContext, size: Size) -> kg::Rect {
+pub(crate) fn bounding_box(fc: &FrameContext, size: Size) -> kg::Rect {
     let pos = Point::zero();
     let points = [
         pos + Point::with_y(size.y),
@@ -186,3 +242,120 @@ pub(crate) fn pos_to_xyz(
         Point::new(pos.point.x, (pos.point.y - Abs::pt(10.0)).max(Abs::zero()));
     Some(XyzDestination::new(page_index, adjusted.to_krilla()))
 }
+
+/// Registers a named destination for every label in the document.
+///
+/// The name of a destination is the label itself, so that other files can
+/// link to it. If a label is attached to multiple elements, the destination
+/// points to the first one.
+pub(crate) fn collect_named_destinations(
+    document: &PagedDocument,
+    pic: &PageIndexConverter,
+) -> FxHashMap<Location, NamedDestination> {
+    let mut locs_to_names = FxHashMap::default();
+    let mut seen = FxHashSet::default();
+
+    let matches = document
+        .introspector
+        .all()
+        .filter_map(|elem| elem.location().zip(elem.label()))
+        .filter(|&(_, label)| seen.insert(label));
+
+    for (loc, label) in matches {
+        // Only add named destination if page belonging to the position is exported.
+        let pos = document.introspector.position(loc);
+        if let Some(dest) = pos_to_xyz(pic, pos) {
+            let named = NamedDestination::new(label.resolve().to_string(), dest);
+            locs_to_names.insert(loc, named);
+        }
+    }
+
+    locs_to_names
+}
+
+/// Adds the links into other files to a finished PDF file.
+pub(crate) fn write_file_links(
+    update: &mut Update,
+    document: &PagedDocument,
+    links: Vec<FileLinkAnnotation>,
+) -> SourceResult<()> {
+    if links.is_empty() {
+        return Ok(());
+    }
+
+    write(update, document, &links)
+        .map_err(|err| eco_format!("failed to write links to other files: {err}"))
+        .at(Span::detached())
+}
+
+/// Writes the links into the update.
+fn write(
+    update: &mut Update,
+    document: &PagedDocument,
+    links: &[FileLinkAnnotation],
+) -> StrResult<()> {
+    // Links to attached files open the attachment instead of a file on disk.
+    // The attachment is identified by the path it was embedded with.
+    let mut attached = FxHashMap::default();
+    for elem in document.introspector.query(&AttachElem::ELEM.select()) {
+        let elem = elem.to_packed::<AttachElem>().unwrap();
+        let name = elem.path.derived.clone();
+        attached.insert(elem.path.source.clone(), name.clone());
+        attached.insert(name.clone(), name);
+    }
+
+    let pages = update.pages()?;
+    let mut annots = vec![vec![]; pages.len()];
+
+    for FileLinkAnnotation { page, rect, link } in links {
+        let Some(&page_id) = pages.get(*page) else { continue };
+
+        let mut action = b"<<>>".to_vec();
+        match attached.get(&link.path) {
+            Some(name) => {
+                let target = format!(
+                    "<</R /C /N {}>>",
+                    String::from_utf8_lossy(&write_text_str(name))
+                );
+                action = dict_set(&action, b"S", b"/GoToE");
+                action = dict_set(&action, b"T", target.as_bytes());
+            }
+            None => {
+                action = dict_set(&action, b"S", b"/GoToR");
+                action = dict_set(&action, b"F", &write_text_str(&link.path));
+            }
+        }
+        let dest = match &link.target {
+            Some(FileTarget::Name(name)) => write_text_str(name),
+            Some(FileTarget::Page(number)) => {
+                format!("[{} /Fit]", number.get() - 1).into_bytes()
+            }
+            None => b"[0 /Fit]".to_vec(),
+        };
+        action = dict_set(&action, b"D", &dest);
+        action = dict_set(&action, b"NewWindow", b"true");
+
+        let [l, b, r, t] = *rect;
+        let mut dict = b"<<>>".to_vec();
+        dict = dict_set(&dict, b"Type", b"/Annot");
+        dict = dict_set(&dict, b"Subtype", b"/Link");
+        dict = dict_set(&dict, b"Rect", format!("[{l} {b} {r} {t}]").as_bytes());
+        dict = dict_set(&dict, b"P", &write_ref(page_id));
+        dict = dict_set(&dict, b"Border", b"[0 0 0]");
+        dict = dict_set(&dict, b"Contents", &write_text_str(&link.href()));
+        dict = dict_set(&dict, b"A", &action);
+        dict = dict_set(&dict, b"F", b"4");
+
+        let id = update.alloc();
+        update.write(id, dict);
+        annots[*page].push(id);
+    }
+
+    for (&page, ids) in pages.iter().zip(annots) {
+        if !ids.is_empty() {
+            update.add_annotations(page, ids)?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,9 +1,16 @@
+use ecow::eco_format;
 use krilla::metadata::{Metadata, TextDirection};
+use typst_library::diag::{At, SourceResult, StrResult, bail};
 use typst_library::foundations::{Datetime, Smart};
-use typst_library::layout::Dir;
+use typst_library::layout::{Dir, PagedDocument};
+use typst_library::model::{PageLayout, PageMode, ViewerPreferences, Zoom};
 use typst_library::text::Locale;
+use typst_syntax::Span;
 
-use crate::convert::GlobalContext;
+use crate::PdfOptions;
+use crate::convert::{GlobalContext, PageIndexConverter};
+use crate::page::with_transition;
+use crate::update::{Update, dict_get, dict_set, parse_ref, write_ref};
 
 pub(crate) fn build_metadata(gc: &GlobalContext, doc_lang: Option<Locale>) -> Metadata {
     let creator = format!("Typst {}", env!("CARGO_PKG_VERSION"));
@@ -91,6 +98,129 @@ pub fn creation_date(gc: &GlobalContext) -> Option<krilla::metadata::DateTime> {
     Some(kd)
 }
 
+/// Adds the viewer preferences and page transitions to a finished PDF file.
+pub(crate) fn write_viewer_settings(
+    update: &mut Update,
+    document: &PagedDocument,
+    options: &PdfOptions,
+) -> SourceResult<()> {
+    let viewer = &document.info.viewer;
+    if options.is_pdf_ua() && viewer.display_title == Smart::Custom(false) {
+        let validator = options.standards.config.validator().as_str();
+        bail!(
+            Span::detached(),
+            "{validator} error: the document title must be displayed";
+            hint: "remove `display-title: false` from the document set rule"
+        );
+    }
+
+    let has_transitions = document
+        .pages
+        .iter()
+        .any(|page| page.transition.is_some() || page.duration.is_some());
+    if *viewer == ViewerPreferences::default() && !has_transitions {
+        return Ok(());
+    }
+
+    write(update, document, options)
+        .map_err(|err| eco_format!("failed to write viewer preferences: {err}"))
+        .at(Span::detached())
+}
+
+/// Writes the viewer preferences and page transitions into the update.
+fn write(
+    update: &mut Update,
+    document: &PagedDocument,
+    options: &PdfOptions,
+) -> StrResult<()> {
+    let viewer = &document.info.viewer;
+    let pic = PageIndexConverter::new(document, options);
+    let pages = update.pages()?;
+
+    let root = update.root()?;
+    let mut catalog = update.get(root).ok_or("failed to read document catalog")?.to_vec();
+
+    if let Smart::Custom(layout) = viewer.page_layout {
+        let name: &[u8] = match layout {
+            PageLayout::SinglePage => b"/SinglePage",
+            PageLayout::OneColumn => b"/OneColumn",
+            PageLayout::TwoColumnLeft => b"/TwoColumnLeft",
+            PageLayout::TwoColumnRight => b"/TwoColumnRight",
+            PageLayout::TwoPageLeft => b"/TwoPageLeft",
+            PageLayout::TwoPageRight => b"/TwoPageRight",
+        };
+        catalog = dict_set(&catalog, b"PageLayout", name);
+    }
+
+    if let Smart::Custom(mode) = viewer.page_mode {
+        let name: &[u8] = match mode {
+            PageMode::Plain => b"/UseNone",
+            PageMode::Outline => b"/UseOutlines",
+            PageMode::Thumbnails => b"/UseThumbs",
+            PageMode::Attachments => b"/UseAttachments",
+            PageMode::Layers if dict_get(&catalog, b"OCProperties").is_some() => {
+                b"/UseOC"
+            }
+            // Without optional content, there is no layer panel to show.
+            PageMode::Layers => b"/UseNone",
+            PageMode::FullScreen => b"/FullScreen",
+        };
+        catalog = dict_set(&catalog, b"PageMode", name);
+    }
+
+    if let Smart::Custom(display) = viewer.display_title {
+        // Keep the preferences krilla has already written.
+        let prefs = match dict_get(&catalog, b"ViewerPreferences") {
+            Some(value) => match parse_ref(value) {
+                Some(id) => update.get(id).ok_or("failed to read viewer preferences")?,
+                None => value,
+            },
+            None => b"<<>>",
+        };
+        let value: &[u8] = if display { b"true" } else { b"false" };
+        let prefs = dict_set(prefs, b"DisplayDocTitle", value);
+        catalog = dict_set(&catalog, b"ViewerPreferences", &prefs);
+    }
+
+    if viewer.initial_page.is_custom() || viewer.initial_zoom.is_custom() {
+        let index = match viewer.initial_page {
+            Smart::Custom(page) => pic.pdf_page_index(page.get() - 1),
+            Smart::Auto => Some(0),
+        };
+        if let Some(&page) = index.and_then(|i| pages.get(i)) {
+            let page = String::from_utf8_lossy(&write_ref(page)).into_owned();
+            let dest = match viewer.initial_zoom {
+                Smart::Auto => format!("[{page} /XYZ null null null]"),
+                Smart::Custom(Zoom::Ratio(ratio)) => {
+                    format!("[{page} /XYZ null null {}]", ratio.get() as f32)
+                }
+                Smart::Custom(Zoom::Fit) => format!("[{page} /Fit]"),
+                Smart::Custom(Zoom::FitWidth) => format!("[{page} /FitH null]"),
+            };
+            catalog = dict_set(&catalog, b"OpenAction", dest.as_bytes());
+        }
+    }
+
+    update.write(root, catalog);
+
+    let exported = document
+        .pages
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| pic.pdf_page_index(i).is_some())
+        .map(|(_, page)| page);
+    for (page, &id) in exported.zip(&pages) {
+        if page.transition.is_none() && page.duration.is_none() {
+            continue;
+        }
+        let dict = update.get(id).ok_or("failed to read page")?;
+        let dict = with_transition(dict, page);
+        update.write(id, dict);
+    }
+
+    Ok(())
+}
+
 /// A timestamp with timezone information.
 #[derive(Debug, Copy, Clone)]
 pub struct Timestamp {
@@ -138,7 +268,97 @@ pub enum Timezone {
 
 #[cfg(test)]
 mod tests {
+    use std::num::NonZeroUsize;
+
+    use typst_library::foundations::{Content, Duration, Packed};
+    use typst_library::layout::{
+        Abs, Frame, FrameItem, GroupItem, Page, PageTransition, Point, Size,
+    };
+    use typst_library::model::DocumentInfo;
+    use typst_library::pdf::LayerElem;
+    use typst_library::visualize::{Color, Geometry};
+
     use super::*;
+    use crate::page::test_page;
+
+    /// Exports a document with the given viewer preferences and pages and
+    /// returns its catalog and the dictionaries of its pages.
+    fn export(viewer: ViewerPreferences, pages: Vec<Page>) -> (Vec<u8>, Vec<Vec<u8>>) {
+        let document = PagedDocument {
+            pages,
+            info: DocumentInfo { viewer, ..Default::default() },
+            introspector: Default::default(),
+        };
+        let options = PdfOptions { tagged: false, ..Default::default() };
+        let pdf = crate::pdf(&document, &options).unwrap();
+        let update = Update::new(pdf).unwrap();
+        let catalog = update.get(update.root().unwrap()).unwrap().to_vec();
+        let pages = update
+            .pages()
+            .unwrap()
+            .into_iter()
+            .map(|id| update.get(id).unwrap().to_vec())
+            .collect();
+        (catalog, pages)
+    }
+
+    fn page(transition: Option<PageTransition>, duration: Option<Duration>) -> Page {
+        Page {
+            transition,
+            duration,
+            ..test_page(Frame::hard(Size::splat(Abs::pt(10.0))))
+        }
+    }
+
+    #[test]
+    fn test_write_viewer_settings() {
+        let viewer = ViewerPreferences {
+            page_layout: Smart::Custom(PageLayout::TwoPageRight),
+            page_mode: Smart::Custom(PageMode::Outline),
+            initial_page: Smart::Custom(NonZeroUsize::new(2).unwrap()),
+            initial_zoom: Smart::Custom(Zoom::Fit),
+            ..Default::default()
+        };
+        let pages = vec![
+            page(None, None),
+            page(Some(PageTransition::Wipe), Some(Duration::construct(3, 0, 0, 0, 0))),
+        ];
+        let (catalog, pages) = export(viewer, pages);
+        assert_eq!(dict_get(&catalog, b"PageLayout"), Some(&b"/TwoPageRight"[..]));
+        assert_eq!(dict_get(&catalog, b"PageMode"), Some(&b"/UseOutlines"[..]));
+
+        let open_action = dict_get(&catalog, b"OpenAction").unwrap();
+        assert!(open_action.ends_with(b" 0 R /Fit]"));
+
+        assert_eq!(dict_get(&pages[0], b"Trans"), None);
+        assert_eq!(
+            dict_get(&pages[1], b"Trans"),
+            Some(&b"<</Type /Trans /S /Wipe>>"[..])
+        );
+        assert_eq!(dict_get(&pages[1], b"Dur"), Some(&b"3"[..]));
+    }
+
+    #[test]
+    fn test_write_layer_page_mode() {
+        // Without layers, there is no layer panel to show.
+        let viewer = ViewerPreferences {
+            page_mode: Smart::Custom(PageMode::Layers),
+            ..Default::default()
+        };
+        let (catalog, _) = export(viewer.clone(), vec![page(None, None)]);
+        assert_eq!(dict_get(&catalog, b"PageMode"), Some(&b"/UseNone"[..]));
+
+        let mut layered = page(None, None);
+        let mut frame = Frame::soft(Size::splat(Abs::pt(5.0)));
+        let shape = Geometry::Rect(frame.size()).filled(Color::BLACK);
+        frame.push(Point::zero(), FrameItem::Shape(shape, Span::detached()));
+        let mut group = GroupItem::new(frame);
+        let layer = LayerElem::new("Notes".into(), Content::empty());
+        group.layer = Some(Packed::new(layer));
+        layered.frame.push(Point::zero(), FrameItem::Group(group));
+        let (catalog, _) = export(viewer, vec![layered]);
+        assert_eq!(dict_get(&catalog, b"PageMode"), Some(&b"/UseOC"[..]));
+    }
 
     #[test]
     fn test_timestamp_new_local() {
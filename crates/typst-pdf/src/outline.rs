@@ -54,7 +54,7 @@ fn convert_node(
     let pos = gc.document.introspector.position(loc);
 
     // Prepend the numbers to the title if they exist.
-    let text = node.entry.body.plain_text();
+    let text = node.entry.short_or_body().plain_text();
     let title = match &node.entry.numbers {
         Some(num) => format!("{num} {text}"),
         None => text.to_string(),
@@ -1,7 +1,13 @@
 use std::num::NonZeroUsize;
 
-use krilla::page::{NumberingStyle, PageLabel};
+use krilla::geom as kg;
+use krilla::page::{NumberingStyle, PageLabel, PageSettings};
+use typst_library::layout::{Abs, Page, PageTransition};
 use typst_library::model::Numbering;
+use typst_utils::Numeric;
+
+use crate::update::dict_set;
+use crate::util::AbsExt;
 
 pub(crate) trait PageLabelExt {
     /// Create a new `PageLabel` from a `Numbering` applied to a page
@@ -62,3 +68,144 @@ impl PageLabelExt for PageLabel {
         )
     }
 }
+
+/// Adds the bleed and trim boxes to the settings of a page with bleed or
+/// printer marks.
+///
+/// The media box spans the whole frame of the page, which includes the bleed
+/// and the marks. If `force` is true, the boxes are also added to pages
+/// without bleed, as required by PDF/X.
+pub(crate) fn with_print_boxes(
+    settings: PageSettings,
+    page: &Page,
+    force: bool,
+) -> PageSettings {
+    match print_boxes(page, force) {
+        Some((bleed_box, trim_box)) => {
+            settings.with_bleed_box(Some(bleed_box)).with_trim_box(Some(trim_box))
+        }
+        None => settings,
+    }
+}
+
+/// Computes the bleed and trim boxes of a page, in this order.
+fn print_boxes(page: &Page, force: bool) -> Option<(kg::Rect, kg::Rect)> {
+    if page.trim_inset().is_zero() && !force {
+        return None;
+    }
+
+    let size = page.frame.size();
+    let inset = |amount: Abs| {
+        kg::Rect::from_xywh(
+            amount.to_f32(),
+            amount.to_f32(),
+            (size.x - 2.0 * amount).to_f32(),
+            (size.y - 2.0 * amount).to_f32(),
+        )
+    };
+
+    Some((inset(page.bleed_inset())?, inset(page.trim_inset())?))
+}
+
+/// Adds the transition and display duration of a page to its dictionary.
+pub(crate) fn with_transition(dict: &[u8], page: &Page) -> Vec<u8> {
+    let mut dict = dict.to_vec();
+
+    if let Some(transition) = page.transition {
+        let style = match transition {
+            PageTransition::Split => "Split",
+            PageTransition::Blinds => "Blinds",
+            PageTransition::Box => "Box",
+            PageTransition::Wipe => "Wipe",
+            PageTransition::Dissolve => "Dissolve",
+            PageTransition::Glitter => "Glitter",
+            PageTransition::Fly => "Fly",
+            PageTransition::Push => "Push",
+            PageTransition::Cover => "Cover",
+            PageTransition::Uncover => "Uncover",
+            PageTransition::Fade => "Fade",
+        };
+        let value = format!("<</Type /Trans /S /{style}>>");
+        dict = dict_set(&dict, b"Trans", value.as_bytes());
+    }
+
+    if let Some(duration) = page.duration {
+        let seconds = duration.seconds().max(0.0) as f32;
+        dict = dict_set(&dict, b"Dur", seconds.to_string().as_bytes());
+    }
+
+    dict
+}
+
+/// A page with the given frame and default settings, for tests.
+#[cfg(test)]
+pub(crate) fn test_page(frame: typst_library::layout::Frame) -> Page {
+    use typst_library::foundations::{Content, Smart};
+
+    Page {
+        frame,
+        fill: Smart::Auto,
+        numbering: None,
+        supplement: Content::empty(),
+        number: 1,
+        bleed: Abs::zero(),
+        slug: Abs::zero(),
+        transition: None,
+        duration: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use typst_library::foundations::Duration;
+    use typst_library::layout::{Frame, Size};
+
+    use super::*;
+
+    fn page(bleed: f64, slug: f64) -> Page {
+        let trim = Size::new(Abs::pt(100.0), Abs::pt(50.0));
+        Page {
+            bleed: Abs::pt(bleed),
+            slug: Abs::pt(slug),
+            ..test_page(Frame::hard(trim + Size::splat(Abs::pt(2.0 * (bleed + slug)))))
+        }
+    }
+
+    fn ltrb(rect: kg::Rect) -> [f32; 4] {
+        [rect.left(), rect.top(), rect.right(), rect.bottom()]
+    }
+
+    #[test]
+    fn test_print_boxes() {
+        assert!(print_boxes(&page(0.0, 0.0), false).is_none());
+
+        let (bleed, trim) = print_boxes(&page(0.0, 0.0), true).unwrap();
+        assert_eq!(ltrb(bleed), [0.0, 0.0, 100.0, 50.0]);
+        assert_eq!(ltrb(trim), [0.0, 0.0, 100.0, 50.0]);
+
+        let (bleed, trim) = print_boxes(&page(5.0, 0.0), false).unwrap();
+        assert_eq!(ltrb(bleed), [0.0, 0.0, 110.0, 60.0]);
+        assert_eq!(ltrb(trim), [5.0, 5.0, 105.0, 55.0]);
+
+        // With printer marks, the frame is shifted by both bleed and slug.
+        let (bleed, trim) = print_boxes(&page(5.0, 18.0), false).unwrap();
+        assert_eq!(ltrb(bleed), [18.0, 18.0, 128.0, 78.0]);
+        assert_eq!(ltrb(trim), [23.0, 23.0, 123.0, 73.0]);
+    }
+
+    #[test]
+    fn test_with_transition() {
+        let dict = b"<</Type /Page>>";
+        assert_eq!(with_transition(dict, &page(0.0, 0.0)), dict);
+
+        let page = Page {
+            transition: Some(PageTransition::Fade),
+            duration: Some(Duration::construct(1, 1, 0, 0, 0)),
+            ..page(0.0, 0.0)
+        };
+        assert_eq!(
+            String::from_utf8(with_transition(dict, &page)).unwrap(),
+            "<</Type /Page\n/Trans <</Type /Trans /S /Fade>>\n/Dur 61>>"
+        );
+    }
+}
@@ -11,13 +11,13 @@ use typst_library::diag::SourceResult;
 use typst_library::layout::{Abs, Angle, Quadrant, Ratio, Size, Transform};
 use typst_library::visualize::{
     Color, ColorSpace, DashPattern, FillRule, FixedStroke, Gradient, Paint, RatioOrAngle,
-    RelativeTo, Tiling, WeightedColor,
+    RelativeTo, Spot, Tiling, WeightedColor,
 };
 use typst_utils::Numeric;
 
 use crate::convert::{FrameContext, GlobalContext, State, handle_frame};
-use crate::tags;
 use crate::util::{AbsExt, FillRuleExt, LineCapExt, LineJoinExt, TransformExt};
+use crate::{rewrite, tags};
 
 pub(crate) fn convert_fill(
     gc: &mut GlobalContext,
@@ -87,6 +87,10 @@ fn convert_paint(
 }
 
 fn convert_solid(color: &Color) -> (color::Color, u8) {
+    if let Color::Spot(spot) = color {
+        return (convert_spot(spot).into(), 255);
+    }
+
     match color.space() {
         ColorSpace::D65Gray => {
             let (c, a) = convert_luma(color);
@@ -107,6 +111,13 @@ fn convert_cmyk(color: &Color) -> cmyk::Color {
     cmyk::Color::new(components[0], components[1], components[2], components[3])
 }
 
+/// Writes a spot color as a gray whose value is the tint. Its color space is
+/// replaced with the ink's separation in the finished file (see
+/// [`rewrite`](crate::rewrite)).
+fn convert_spot(spot: &Spot) -> luma::Color {
+    luma::Color::new((spot.tint * 255.0).round() as u8)
+}
+
 fn convert_rgb(color: &Color) -> (rgb::Color, u8) {
     let components = color.to_space(ColorSpace::Srgb).to_vec4_u8();
     (rgb::Color::new(components[0], components[1], components[2]), components[3])
@@ -256,14 +267,17 @@ fn convert_gradient(
 fn convert_gradient_stops(gradient: &Gradient) -> Vec<Stop> {
     let mut stops = vec![];
 
+    let use_spot = rewrite::gradient_spot(gradient).is_some();
     let use_cmyk = gradient.stops().iter().all(|s| s.color.space() == ColorSpace::Cmyk);
 
     let mut add_single = |color: &Color, offset: Ratio| {
-        let (color, opacity) = if use_cmyk {
-            (convert_cmyk(color).into(), 255)
-        } else {
-            let (c, a) = convert_rgb(color);
-            (c.into(), a)
+        let (color, opacity) = match color {
+            Color::Spot(spot) if use_spot => (convert_spot(spot).into(), 255),
+            _ if use_cmyk => (convert_cmyk(color).into(), 255),
+            _ => {
+                let (c, a) = convert_rgb(color);
+                (c.into(), a)
+            }
         };
 
         let opacity = NormalizedF32::new((opacity as f32) / 255.0).unwrap();
@@ -0,0 +1,502 @@
+//! Support for the PDF/X standards for print production.
+//!
+//! krilla does not know about PDF/X, so we check the restrictions of the
+//! standards ourselves while converting and add the required output intent and
+//! metadata to the finished file.
+
+use ecow::{EcoString, eco_format};
+use krilla::configure::{PdfVersion, Validator};
+use typst_library::diag::{At, SourceResult, StrResult, bail};
+use typst_library::foundations::Bytes;
+use typst_library::visualize::{Color, ColorSpace, Image, ImageKind, Paint};
+use typst_syntax::Span;
+
+use crate::PdfOptions;
+use crate::convert::GlobalContext;
+use crate::metadata::creation_date;
+use crate::update::{
+    Update, dict_get, dict_set, parse_ref, write_ref, write_stream, write_text_str,
+};
+
+/// A PDF/X standard.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum PdfX {
+    /// PDF/X-1a:2003, which only allows CMYK and grayscale colors.
+    X1a,
+    /// PDF/X-3:2003, which additionally allows color-managed RGB colors.
+    X3,
+    /// PDF/X-4, which additionally allows transparency.
+    X4,
+}
+
+impl PdfX {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::X1a => "PDF/X-1a:2003",
+            Self::X3 => "PDF/X-3:2003",
+            Self::X4 => "PDF/X-4",
+        }
+    }
+
+    /// The PDF versions the standard can be combined with, from lowest to
+    /// highest.
+    pub(crate) fn versions(self) -> &'static [PdfVersion] {
+        match self {
+            Self::X1a | Self::X3 => &[PdfVersion::Pdf14],
+            Self::X4 => &[PdfVersion::Pdf14, PdfVersion::Pdf15, PdfVersion::Pdf16],
+        }
+    }
+
+    /// Whether the standard allows RGB colors.
+    pub(crate) fn allows_rgb(self) -> bool {
+        self != Self::X1a
+    }
+
+    /// Whether the standard allows transparency.
+    fn allows_transparency(self) -> bool {
+        self == Self::X4
+    }
+}
+
+/// The printing condition a document is prepared for.
+#[derive(Clone)]
+pub(crate) struct OutputIntent {
+    /// The ICC profile of the printing condition.
+    profile: Bytes,
+    /// An identifier for the printing condition.
+    condition: EcoString,
+    /// The number of color components of the profile.
+    pub(crate) components: u8,
+}
+
+impl OutputIntent {
+    /// Creates an output intent from an ICC profile.
+    pub(crate) fn new(profile: Bytes, condition: EcoString) -> StrResult<Self> {
+        let data = profile.as_slice();
+        if data.len() < 128 || &data[36..40] != b"acsp" {
+            bail!("output intent profile is not a valid ICC profile");
+        }
+
+        let components = match &data[16..20] {
+            b"CMYK" => 4,
+            b"RGB " => 3,
+            b"GRAY" => 1,
+            _ => bail!("output intent profile must be for CMYK, RGB, or grayscale"),
+        };
+
+        Ok(Self { profile, condition, components })
+    }
+}
+
+impl Default for OutputIntent {
+    fn default() -> Self {
+        Self::new(Bytes::new(typst_assets::icc::CMYK_TO_XYZ), "CGATS TR 001".into())
+            .unwrap()
+    }
+}
+
+/// Checks that the document metadata is complete.
+pub(crate) fn check_document(gc: &GlobalContext) -> SourceResult<()> {
+    let Some(x) = gc.options.standards.pdf_x else { return Ok(()) };
+
+    if gc.document.info.title.is_none() {
+        bail!(
+            Span::detached(),
+            "{} error: missing document title", x.as_str();
+            hint: "set the title with `set document(title: [...])`"
+        );
+    }
+
+    if creation_date(gc).is_none() {
+        bail!(
+            Span::detached(),
+            "{} error: missing document date", x.as_str();
+            hint: "set the date of the document"
+        );
+    }
+
+    Ok(())
+}
+
+/// The standard that forbids form fields and annotations, if any.
+///
+/// krilla rejects them for PDF/A and PDF/UA, and PDF/X documents are meant for
+/// print production, where interactive elements on the page are not allowed.
+pub(crate) fn forbidding_standard(options: &PdfOptions) -> Option<&'static str> {
+    let validator = options.standards.config.validator();
+    if validator != Validator::None {
+        return Some(validator.as_str());
+    }
+    options.standards.pdf_x.map(PdfX::as_str)
+}
+
+/// Checks that a fill or stroke is allowed.
+pub(crate) fn check_paint(
+    gc: &GlobalContext,
+    paint: &Paint,
+    span: Span,
+) -> SourceResult<()> {
+    let Some(x) = gc.options.standards.pdf_x else { return Ok(()) };
+
+    let colors: Vec<Color> = match paint {
+        Paint::Solid(color) => vec![*color],
+        Paint::Gradient(gradient) => {
+            gradient.stops_ref().iter().map(|&(color, _)| color).collect()
+        }
+        // The contents of tilings are checked individually.
+        Paint::Tiling(_) => return Ok(()),
+    };
+
+    if !x.allows_transparency()
+        && colors.iter().any(|color| color.alpha().is_some_and(|a| a < 1.0))
+    {
+        bail!(
+            span, "{} error: the used fill or stroke has transparency", x.as_str();
+            hint: "don't use colors with transparency in this export mode";
+            hint: "or export with PDF/X-4, which supports transparency"
+        );
+    }
+
+    if !x.allows_rgb()
+        && colors
+            .iter()
+            .any(|color| !matches!(color.space(), ColorSpace::Cmyk | ColorSpace::D65Gray))
+    {
+        bail!(
+            span, "{} error: the used fill or stroke is not in CMYK or grayscale", x.as_str();
+            hint: "use colors created with `cmyk` or `luma`";
+            hint: "or export with PDF/X-3 or PDF/X-4, which support RGB colors"
+        );
+    }
+
+    // Gradients with a mix of CMYK and other stops are converted to RGB.
+    if !x.allows_rgb()
+        && let Paint::Gradient(_) = paint
+        && !colors.iter().all(|color| color.space() == ColorSpace::Cmyk)
+    {
+        bail!(
+            span, "{} error: the used gradient is not in CMYK", x.as_str();
+            hint: "use only colors created with `cmyk` in gradients"
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks that an image is allowed.
+pub(crate) fn check_image(
+    gc: &GlobalContext,
+    image: &Image,
+    span: Span,
+) -> SourceResult<()> {
+    let Some(x) = gc.options.standards.pdf_x else { return Ok(()) };
+
+    match image.kind() {
+        ImageKind::Raster(raster) => {
+            let color = raster.dynamic().color();
+            if !x.allows_transparency() && color.has_alpha() {
+                bail!(
+                    span, "{} error: the image contains transparency", x.as_str();
+                    hint: "convert the image to a non-transparent one";
+                    hint: "or export with PDF/X-4, which supports transparency"
+                );
+            }
+
+            if !x.allows_rgb() && color.has_color() {
+                bail!(
+                    span, "{} error: the image is not in grayscale", x.as_str();
+                    hint: "color images are embedded as RGB, which is forbidden in this export mode";
+                    hint: "or export with PDF/X-3 or PDF/X-4, which support RGB images"
+                );
+            }
+        }
+        ImageKind::Svg(_) if !x.allows_rgb() => bail!(
+            span, "{} error: SVG images are not supported in this export mode", x.as_str();
+            hint: "SVG images are embedded with RGB colors";
+            hint: "or export with PDF/X-3 or PDF/X-4, which support RGB images"
+        ),
+        // The content of vector images is not inspected, so it may contain
+        // transparency.
+        ImageKind::Svg(_) | ImageKind::Pdf(_) if !x.allows_transparency() => bail!(
+            span, "{} error: vector images are not supported in this export mode", x.as_str();
+            hint: "SVG and PDF images may contain transparency";
+            hint: "convert the image to a raster image or export with PDF/X-4, which supports transparency"
+        ),
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Adds the output intent and PDF/X metadata to a finished PDF file.
+pub(crate) fn write_print_info(
+    update: &mut Update,
+    options: &PdfOptions,
+) -> SourceResult<()> {
+    let Some(x) = options.standards.pdf_x else { return Ok(()) };
+    write(update, x, &options.standards.output_intent)
+        .map_err(|err| eco_format!("failed to write {} metadata: {err}", x.as_str()))
+        .at(Span::detached())
+}
+
+/// Writes the output intent and metadata into the update.
+fn write(update: &mut Update, x: PdfX, intent: &OutputIntent) -> StrResult<()> {
+    let profile = update.alloc();
+    let dict = format!("<</N {}>>", intent.components);
+    update.write(profile, write_stream(dict.as_bytes(), intent.profile.as_slice()));
+
+    let mut output = b"<<>>".to_vec();
+    output = dict_set(&output, b"Type", b"/OutputIntent");
+    output = dict_set(&output, b"S", b"/GTS_PDFX");
+    output = dict_set(
+        &output,
+        b"OutputConditionIdentifier",
+        &write_text_str(&intent.condition),
+    );
+    output = dict_set(&output, b"Info", &write_text_str(&intent.condition));
+    output = dict_set(&output, b"RegistryName", &write_text_str("http://www.color.org"));
+    output = dict_set(&output, b"DestOutputProfile", &write_ref(profile));
+
+    let root = update.root()?;
+    let catalog = update.get(root).ok_or("failed to read document catalog")?;
+    let mut intents = b"[".to_vec();
+    intents.extend(output);
+    intents.push(b']');
+    let catalog = dict_set(catalog, b"OutputIntents", &intents);
+
+    // PDF/X-4 identifies itself through the XMP metadata.
+    if x == PdfX::X4 {
+        let Some(id) = dict_get(&catalog, b"Metadata").and_then(parse_ref) else {
+            bail!("failed to find XMP metadata");
+        };
+        let (dict, xmp) = update.get_stream(id).ok_or("failed to read XMP metadata")?;
+        if dict_get(dict, b"Filter").is_some() {
+            bail!("XMP metadata is compressed");
+        }
+
+        const END: &[u8] = b"</rdf:RDF>";
+        let pos = xmp
+            .windows(END.len())
+            .rposition(|w| w == END)
+            .ok_or("failed to parse XMP metadata")?;
+        let mut data = xmp[..pos].to_vec();
+        data.extend(
+            b"<rdf:Description rdf:about=\"\" \
+              xmlns:pdf=\"http://ns.adobe.com/pdf/1.3/\" \
+              xmlns:pdfxid=\"http://www.npes.org/pdfx/ns/id/\">\
+              <pdf:Trapped>False</pdf:Trapped>\
+              <pdfxid:GTS_PDFXVersion>PDF/X-4</pdfxid:GTS_PDFXVersion>\
+              </rdf:Description>",
+        );
+        data.extend(&xmp[pos..]);
+
+        let body = write_stream(dict, &data);
+        update.write(id, body);
+    }
+
+    update.write(root, catalog);
+
+    // Identify the standard in the document information dictionary.
+    let (id, mut info) = match update.info() {
+        Some(id) => (id, update.get(id).ok_or("failed to read document info")?.to_vec()),
+        None => (update.alloc(), b"<<>>".to_vec()),
+    };
+    let version = match x {
+        PdfX::X1a => "PDF/X-1:2003",
+        PdfX::X3 => "PDF/X-3:2003",
+        PdfX::X4 => "PDF/X-4",
+    };
+    info = dict_set(&info, b"GTS_PDFXVersion", &write_text_str(version));
+    if x == PdfX::X1a {
+        info = dict_set(&info, b"GTS_PDFXConformance", &write_text_str(x.as_str()));
+    }
+    info = dict_set(&info, b"Trapped", b"/False");
+    update.write(id, info);
+    update.set_info(id);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use image::{DynamicImage, GrayAlphaImage, ImageFormat, LumaA, Rgb, RgbImage};
+    use typst_library::foundations::{Content, Datetime, NativeElement, Packed, Smart};
+    use typst_library::introspection::{Location, Tag, TagFlags};
+    use typst_library::layout::{
+        Abs, Frame, FrameItem, GroupItem, PagedDocument, Point, Size,
+    };
+    use typst_library::model::{Destination, DocumentInfo, Url};
+    use typst_library::pdf::{AnnotationElem, LayerElem};
+    use typst_library::visualize::{ExchangeFormat, Geometry, RasterImage, SvgImage};
+
+    use super::*;
+    use crate::page::test_page;
+    use crate::{PdfStandard, PdfStandards};
+
+    /// Exports a page with a single item while enforcing a PDF/X standard.
+    fn pdf(x: PdfStandard, info: DocumentInfo, item: FrameItem) -> SourceResult<Vec<u8>> {
+        let mut frame = Frame::hard(Size::splat(Abs::pt(10.0)));
+        frame.push(Point::zero(), item);
+        let page = test_page(frame);
+        let document = PagedDocument {
+            pages: vec![page],
+            info,
+            introspector: Default::default(),
+        };
+        let options = PdfOptions {
+            standards: PdfStandards::new(&[x]).unwrap(),
+            tagged: false,
+            ..Default::default()
+        };
+        crate::pdf(&document, &options)
+    }
+
+    /// Exports a page with a single item while enforcing a PDF/X standard and
+    /// returns the first error message, if any.
+    fn export(x: PdfStandard, info: DocumentInfo, item: FrameItem) -> Option<EcoString> {
+        pdf(x, info, item).err().map(|errors| errors[0].message.clone())
+    }
+
+    /// Whether a file contains the given bytes.
+    fn contains(pdf: &[u8], bytes: &[u8]) -> bool {
+        pdf.windows(bytes.len()).any(|window| window == bytes)
+    }
+
+    /// Metadata that satisfies all PDF/X standards.
+    fn info() -> DocumentInfo {
+        DocumentInfo {
+            title: Some("Print".into()),
+            date: Smart::Custom(Datetime::from_ymd(2025, 1, 1)),
+            ..Default::default()
+        }
+    }
+
+    /// A rectangle filled with the given color.
+    fn rect(color: Color) -> FrameItem {
+        let shape = Geometry::Rect(Size::splat(Abs::pt(5.0))).filled(color);
+        FrameItem::Shape(shape, Span::detached())
+    }
+
+    /// A one-pixel PNG image.
+    fn png(image: DynamicImage) -> FrameItem {
+        let mut buf = Cursor::new(vec![]);
+        image.write_to(&mut buf, ImageFormat::Png).unwrap();
+        let raster =
+            RasterImage::plain(Bytes::new(buf.into_inner()), ExchangeFormat::Png)
+                .unwrap();
+        let size = Size::splat(Abs::pt(5.0));
+        FrameItem::Image(Image::plain(raster), size, Span::detached())
+    }
+
+    #[test]
+    fn test_pdf_x_standards() {
+        let x4 = PdfStandards::new(&[PdfStandard::X_4]).unwrap();
+        assert_eq!(x4.pdf_x, Some(PdfX::X4));
+        assert_eq!(x4.config.version(), PdfVersion::Pdf16);
+
+        assert!(PdfStandards::new(&[PdfStandard::X_4, PdfStandard::V_1_5]).is_ok());
+        assert!(PdfStandards::new(&[PdfStandard::X_1a, PdfStandard::V_1_7]).is_err());
+        assert!(PdfStandards::new(&[PdfStandard::X_3, PdfStandard::A_2b]).is_err());
+        assert!(PdfStandards::new(&[PdfStandard::X_3, PdfStandard::X_4]).is_err());
+    }
+
+    #[test]
+    fn test_output_intent() {
+        assert_eq!(OutputIntent::default().components, 4);
+        assert!(OutputIntent::new(Bytes::new(b"not a profile"), "Test".into()).is_err());
+    }
+
+    #[test]
+    fn test_check_document() {
+        let item = rect(Color::BLACK);
+        assert_eq!(export(PdfStandard::X_4, info(), item.clone()), None);
+
+        let untitled = DocumentInfo { title: None, ..info() };
+        let message = export(PdfStandard::X_4, untitled, item.clone()).unwrap();
+        assert_eq!(message, "PDF/X-4 error: missing document title");
+
+        let undated = DocumentInfo { date: Smart::Auto, ..info() };
+        let message = export(PdfStandard::X_3, undated, item).unwrap();
+        assert_eq!(message, "PDF/X-3:2003 error: missing document date");
+    }
+
+    #[test]
+    fn test_check_paint() {
+        let cmyk = rect(Color::Cmyk(Color::RED.to_cmyk()));
+        assert_eq!(export(PdfStandard::X_1a, info(), cmyk), None);
+
+        let rgb = rect(Color::RED);
+        assert_eq!(export(PdfStandard::X_3, info(), rgb.clone()), None);
+        let message = export(PdfStandard::X_1a, info(), rgb).unwrap();
+        assert_eq!(
+            message,
+            "PDF/X-1a:2003 error: the used fill or stroke is not in CMYK or grayscale"
+        );
+
+        let transparent = rect(Color::BLACK.with_alpha(0.5));
+        assert_eq!(export(PdfStandard::X_4, info(), transparent.clone()), None);
+        let message = export(PdfStandard::X_3, info(), transparent).unwrap();
+        assert_eq!(
+            message,
+            "PDF/X-3:2003 error: the used fill or stroke has transparency"
+        );
+    }
+
+    #[test]
+    fn test_check_image() {
+        let rgb = png(RgbImage::from_pixel(1, 1, Rgb([255, 0, 0])).into());
+        assert_eq!(export(PdfStandard::X_3, info(), rgb.clone()), None);
+        let message = export(PdfStandard::X_1a, info(), rgb).unwrap();
+        assert_eq!(message, "PDF/X-1a:2003 error: the image is not in grayscale");
+
+        let transparent = png(GrayAlphaImage::from_pixel(1, 1, LumaA([0, 128])).into());
+        assert_eq!(export(PdfStandard::X_4, info(), transparent.clone()), None);
+        let message = export(PdfStandard::X_3, info(), transparent).unwrap();
+        assert_eq!(message, "PDF/X-3:2003 error: the image contains transparency");
+
+        let svg = SvgImage::new(Bytes::new(
+            b"<svg xmlns='http://www.w3.org/2000/svg' width='1' height='1'>\
+              <rect width='1' height='1' fill-opacity='0.5'/></svg>",
+        ))
+        .unwrap();
+        let size = Size::splat(Abs::pt(5.0));
+        let svg = FrameItem::Image(Image::plain(svg), size, Span::detached());
+        assert_eq!(export(PdfStandard::X_4, info(), svg.clone()), None);
+        let message = export(PdfStandard::X_3, info(), svg).unwrap();
+        assert_eq!(
+            message,
+            "PDF/X-3:2003 error: vector images are not supported in this export mode"
+        );
+    }
+
+    #[test]
+    fn test_annotation() {
+        let annot =
+            AnnotationElem::new("Comment".into()).pack().located(Location::new(1));
+        let flags = TagFlags { introspectable: true, tagged: false };
+        let item = FrameItem::Tag(Tag::Start(annot, flags));
+        let message = export(PdfStandard::X_4, info(), item).unwrap();
+        assert_eq!(message, "PDF/X-4 error: annotations are not supported");
+    }
+
+    #[test]
+    fn test_link() {
+        let url = Url::new("https://typst.app").unwrap();
+        let item = FrameItem::Link(Destination::Url(url), Size::splat(Abs::pt(5.0)));
+        let pdf = pdf(PdfStandard::X_4, info(), item).unwrap();
+        assert!(!contains(&pdf, b"/Subtype /Link"));
+    }
+
+    #[test]
+    fn test_layer() {
+        let mut frame = Frame::soft(Size::splat(Abs::pt(10.0)));
+        frame.push(Point::zero(), rect(Color::BLACK));
+        let mut group = GroupItem::new(frame);
+        let layer = LayerElem::new("Solutions".into(), Content::empty());
+        group.layer = Some(Packed::new(layer));
+        let pdf = pdf(PdfStandard::X_4, info(), FrameItem::Group(group)).unwrap();
+        assert!(!contains(&pdf, b"/OCProperties"));
+    }
+}
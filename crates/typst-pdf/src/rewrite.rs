@@ -0,0 +1,806 @@
+//! Rewriting of the content streams written by krilla.
+//!
+//! krilla does not support separation color spaces and optional content. To
+//! still provide them, drawing operations with spot colors and the content of
+//! layers are wrapped in a marked region while converting. Once the file is
+//! finished, the color operators in the marked regions of all content streams
+//! are replaced with ones that use the inks' separation color spaces, and the
+//! content of layers is enclosed in optional content.
+//!
+//! Spot colors are passed to krilla as grayscale colors whose value is the
+//! tint. Like a separation, grayscale has a single component, so the values
+//! in the content streams and in the shadings of gradients can be kept as-is
+//! and only the color space needs to be replaced.
+//!
+//! # Marker format
+//! [`marked`] and [`rewrite`] rely on the following invariant: A marked
+//! region starts with two consecutive `q -1 0 0 1 N 0 cm` sequences, where
+//! `N` is the region's index in [`Marks::regions`], and ends at the `Q` that
+//! restores the graphics state saved by the second one. The two horizontal
+//! flips cancel each other out and Typst practically never produces such a
+//! pair itself. This relies on krilla writing each pushed transform verbatim
+//! with its own saved graphics state. If that ever changes, the marker of a
+//! region is not found and writing fails instead of exporting the grayscale
+//! stand-ins.
+
+use std::collections::BTreeSet;
+use std::io::{Read, Write};
+
+use ecow::{EcoString, eco_format};
+use flate2::Compression;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use indexmap::IndexMap;
+use krilla::configure::{PdfVersion, Validator};
+use krilla::geom::Transform;
+use krilla::surface::Surface;
+use rustc_hash::FxHashMap;
+use typst_library::diag::{At, SourceResult, StrResult, bail};
+use typst_library::foundations::{Packed, StyleChain};
+use typst_library::pdf::LayerElem;
+use typst_library::visualize::{Cmyk, Color, Gradient, Paint, Spot};
+use typst_syntax::Span;
+use typst_utils::PicoStr;
+
+use crate::PdfOptions;
+use crate::update::{
+    Lexer, Update, dict_entries, dict_get, dict_set, parse_array, parse_ref, write_array,
+    write_name, write_ref, write_stream, write_text_str,
+};
+
+/// Drawing operations whose content is rewritten in the finished file.
+#[derive(Default)]
+pub(crate) struct Marks {
+    /// The fallbacks of the document's inks, by name.
+    inks: IndexMap<PicoStr, Cmyk>,
+    /// The document's layers, by name.
+    layers: IndexMap<EcoString, Layer>,
+    /// The marked regions, indexed by their marker.
+    regions: Vec<Region>,
+}
+
+/// A marked region of a content stream.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Region {
+    /// A drawing operation with spot colors. Holds the indices of the inks
+    /// of its fill and stroke.
+    Paint { fill: Option<usize>, stroke: Option<usize> },
+    /// The content of a layer. Holds the index of the layer.
+    Layer(usize),
+}
+
+/// The default visibility of a layer.
+#[derive(Debug, Copy, Clone)]
+struct Layer {
+    /// Whether the layer is shown in viewers.
+    visible: bool,
+    /// Whether the layer is printed.
+    print: bool,
+}
+
+impl Marks {
+    /// Registers a drawing operation with the given fill and stroke. Returns
+    /// its marker if either of them is painted with an ink.
+    pub(crate) fn paint(
+        &mut self,
+        fill: Option<&Paint>,
+        stroke: Option<&Paint>,
+    ) -> Option<usize> {
+        let fill = fill.and_then(spot).map(|spot| self.ink(spot));
+        let stroke = stroke.and_then(spot).map(|spot| self.ink(spot));
+        if fill.is_none() && stroke.is_none() {
+            return None;
+        }
+
+        self.regions.push(Region::Paint { fill, stroke });
+        Some(self.regions.len() - 1)
+    }
+
+    /// Registers the content of a layer and returns its marker. Layers with
+    /// the same name share the visibility of the first one.
+    pub(crate) fn layer(&mut self, layer: &Packed<LayerElem>) -> usize {
+        let visible = layer.visible.get(StyleChain::default());
+        let print = layer.print.get(StyleChain::default()).unwrap_or(visible);
+        let entry = self.layers.entry(layer.name.clone());
+        let index = entry.index();
+        entry.or_insert(Layer { visible, print });
+
+        self.regions.push(Region::Layer(index));
+        self.regions.len() - 1
+    }
+
+    /// Returns the index of a spot color's ink.
+    fn ink(&mut self, spot: Spot) -> usize {
+        let entry = self.inks.entry(spot.name);
+        let index = entry.index();
+        entry.or_insert(spot.fallback);
+        index
+    }
+}
+
+/// Whether layers can be written as optional content. PDF 1.4 predates
+/// optional content and PDF standards restrict how it may be configured, so
+/// layers are exported with their default visibility there.
+pub(crate) fn supports_layers(options: &PdfOptions) -> bool {
+    let standards = &options.standards;
+    standards.config.validator() == Validator::None
+        && standards.pdf_x.is_none()
+        && standards.config.version() != PdfVersion::Pdf14
+}
+
+/// The spot color that a paint is written with, if any.
+pub(crate) fn spot(paint: &Paint) -> Option<Spot> {
+    match paint {
+        Paint::Solid(Color::Spot(spot)) => Some(*spot),
+        Paint::Gradient(gradient) => gradient_spot(gradient),
+        _ => None,
+    }
+}
+
+/// The spot color of a gradient whose stops all use the same ink.
+pub(crate) fn gradient_spot(gradient: &Gradient) -> Option<Spot> {
+    let mut colors = gradient.stops_ref().iter().map(|&(color, _)| color);
+    let Some(Color::Spot(first)) = colors.next() else { return None };
+    colors
+        .all(|color| matches!(color, Color::Spot(spot) if spot.name == first.name))
+        .then_some(first)
+}
+
+/// Draws with `f` in the region with the given marker, if any.
+pub(crate) fn marked<T>(
+    surface: &mut Surface,
+    marker: Option<usize>,
+    f: impl FnOnce(&mut Surface) -> T,
+) -> T {
+    let Some(marker) = marker else { return f(surface) };
+
+    // See the module documentation for the format of the marker.
+    let flip = Transform::from_row(-1.0, 0.0, 0.0, 1.0, marker as f32, 0.0);
+    surface.push_transform(&flip);
+    surface.push_transform(&flip);
+    let output = f(surface);
+    surface.pop();
+    surface.pop();
+    output
+}
+
+/// Rewrites the marked regions of a finished PDF file.
+pub(crate) fn write_marks(update: &mut Update, marks: Marks) -> SourceResult<()> {
+    if marks.regions.is_empty() {
+        return Ok(());
+    }
+
+    write(update, &marks)
+        .map_err(|err| eco_format!("failed to write spot colors and layers: {err}"))
+        .at(Span::detached())
+}
+
+/// Writes the separation color spaces and optional content groups and rewrites
+/// the content streams into the update.
+fn write(update: &mut Update, marks: &Marks) -> StrResult<()> {
+    let spaces: Vec<i32> = marks
+        .inks
+        .iter()
+        .map(|(name, fallback)| {
+            let id = update.alloc();
+            update.write(id, separation(name.resolve().as_str(), *fallback));
+            id
+        })
+        .collect();
+
+    let groups: Vec<i32> = marks
+        .layers
+        .iter()
+        .map(|(name, layer)| {
+            let id = update.alloc();
+            update.write(id, optional_content_group(name, *layer));
+            id
+        })
+        .collect();
+
+    if !groups.is_empty() {
+        let root = update.root()?;
+        let catalog = update.get(root).ok_or("failed to read document catalog")?;
+        let properties = optional_content_properties(&groups, &marks.layers);
+        let catalog = dict_set(catalog, b"OCProperties", &properties);
+        update.write(root, catalog);
+    }
+
+    // The content streams of pages use the resources of their page.
+    let mut pages = FxHashMap::default();
+    for page in update.pages()? {
+        let dict = update.get(page).ok_or("failed to read page")?;
+        let contents = dict_get(dict, b"Contents").unwrap_or(b"[]");
+        let contents = parse_array(contents).unwrap_or_else(|| vec![contents]);
+        for content in contents.into_iter().filter_map(parse_ref) {
+            pages.insert(content, page);
+        }
+    }
+
+    let mut found = BTreeSet::new();
+    for id in update.ids() {
+        let Some((dict, data)) = update.get_stream(id) else { continue };
+        if !is_content(dict) {
+            continue;
+        }
+        let Some(content) = decode(dict, data) else { continue };
+        let Some((content, used)) = rewrite(&content, &marks.regions) else {
+            continue;
+        };
+
+        found.extend(&used.regions);
+        let mut dict = dict.to_vec();
+        if dict_get(&dict, b"Resources").is_some() {
+            dict = add_resources(update, &dict, &used, &spaces, &groups)?;
+        } else if let Some(&page) = pages.get(&id) {
+            let page_dict = update.get(page).ok_or("failed to read page")?.to_vec();
+            let page_dict = add_resources(update, &page_dict, &used, &spaces, &groups)?;
+            update.write(page, page_dict);
+        } else {
+            bail!("failed to find resources of content stream");
+        }
+
+        let data = encode(&dict, &content);
+        update.write(id, write_stream(&dict, &data));
+    }
+
+    // Without its marker, a region would silently keep the grayscale
+    // stand-in of its spot colors or be visible outside of its layer.
+    if let Some(marker) = (0..marks.regions.len()).find(|m| !found.contains(m)) {
+        bail!("failed to find marked region {marker} in content streams");
+    }
+
+    Ok(())
+}
+
+/// Formats a separation color space for an ink.
+fn separation(name: &str, fallback: Cmyk) -> Vec<u8> {
+    let Cmyk { c, m, y, k } = fallback;
+    let mut out = b"[/Separation ".to_vec();
+    out.extend(write_name(name));
+    write!(
+        out,
+        " /DeviceCMYK <</FunctionType 2 /Domain [0 1] /C0 [0 0 0 0] \
+         /C1 [{c} {m} {y} {k}] /N 1>>]"
+    )
+    .unwrap();
+    out
+}
+
+/// Formats an optional content group for a layer.
+fn optional_content_group(name: &str, layer: Layer) -> Vec<u8> {
+    let state = |on: bool| if on { "/ON" } else { "/OFF" };
+    let mut out = b"<</Type /OCG /Name ".to_vec();
+    out.extend(write_text_str(name));
+    write!(
+        out,
+        " /Usage <</View <</ViewState {}>> /Print <</PrintState {}>>>>>>",
+        state(layer.visible),
+        state(layer.print),
+    )
+    .unwrap();
+    out
+}
+
+/// Formats the optional content properties of the document catalog. Hidden
+/// layers are turned off by default, and viewers apply the layers' usage when
+/// viewing and printing.
+fn optional_content_properties(
+    groups: &[i32],
+    layers: &IndexMap<EcoString, Layer>,
+) -> Vec<u8> {
+    let all = write_array(&groups.iter().map(|&id| write_ref(id)).collect::<Vec<_>>());
+    let off: Vec<Vec<u8>> = groups
+        .iter()
+        .zip(layers.values())
+        .filter(|(_, layer)| !layer.visible)
+        .map(|(&id, _)| write_ref(id))
+        .collect();
+
+    let mut out = b"<</OCGs ".to_vec();
+    out.extend(&all);
+    out.extend(b" /D <</Order ");
+    out.extend(&all);
+    out.extend(b" /OFF ");
+    out.extend(write_array(&off));
+    out.extend(b" /AS [");
+    for event in ["View", "Print"] {
+        write!(out, "<</Event /{event} /OCGs ").unwrap();
+        out.extend(&all);
+        write!(out, " /Category [/{event}]>>").unwrap();
+    }
+    out.extend(b"]>>>>");
+    out
+}
+
+/// The name of an ink's color space in resource dictionaries.
+fn spot_name(ink: usize) -> Vec<u8> {
+    format!("TypstSpot{ink}").into_bytes()
+}
+
+/// The name of a layer's optional content group in resource dictionaries.
+fn layer_name(layer: usize) -> Vec<u8> {
+    format!("TypstLayer{layer}").into_bytes()
+}
+
+/// Whether a stream holds content: A page's content, a form, or a tiling.
+fn is_content(dict: &[u8]) -> bool {
+    match dict_get(dict, b"Subtype") {
+        Some(b"/Form") => return true,
+        Some(_) => return false,
+        None => {}
+    }
+
+    dict_get(dict, b"PatternType") == Some(b"1")
+        || dict_entries(dict).is_some_and(|entries| {
+            entries.iter().all(|(key, _)| matches!(*key, b"Length" | b"Filter"))
+        })
+}
+
+/// Decodes the data of a stream if it is uncompressed or compressed with
+/// Flate.
+fn decode(dict: &[u8], data: &[u8]) -> Option<Vec<u8>> {
+    if dict_get(dict, b"DecodeParms").is_some() {
+        return None;
+    }
+
+    match dict_get(dict, b"Filter") {
+        None => Some(data.to_vec()),
+        Some(b"/FlateDecode" | b"[/FlateDecode]") => {
+            let mut out = vec![];
+            ZlibDecoder::new(data).read_to_end(&mut out).ok()?;
+            Some(out)
+        }
+        Some(_) => None,
+    }
+}
+
+/// Encodes the data of a stream with the stream's filter.
+fn encode(dict: &[u8], data: &[u8]) -> Vec<u8> {
+    if dict_get(dict, b"Filter").is_none() {
+        return data.to_vec();
+    }
+
+    let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+/// What a rewritten content stream uses.
+#[derive(Debug, Default, PartialEq)]
+struct Used {
+    /// The markers of the rewritten regions.
+    regions: BTreeSet<usize>,
+    /// The inks whose color spaces are used.
+    inks: BTreeSet<usize>,
+    /// The layers whose optional content groups are used.
+    layers: BTreeSet<usize>,
+    /// The shading patterns painted with an ink, by resource name.
+    patterns: Vec<(Vec<u8>, usize)>,
+}
+
+/// Rewrites the marked regions of a content stream. Returns `None` if the
+/// stream has no marked regions or cannot be parsed.
+fn rewrite(content: &[u8], regions: &[Region]) -> Option<(Vec<u8>, Used)> {
+    let mut lexer = Lexer::new(content);
+    let mut out = Vec::with_capacity(content.len());
+    let mut used = Used::default();
+    let mut operands = vec![];
+
+    // The start of the operands of the next operator.
+    let mut start = 0;
+    // The nesting depth of saved graphics states.
+    let mut depth = 0;
+    // The regions we are in, with the depth at which they started.
+    let mut active: Vec<(Region, usize)> = vec![];
+    // A marker transform that starts a region if it is repeated.
+    let mut pending = None;
+
+    loop {
+        let pos = lexer.skip_ws();
+        let Some(b) = lexer.peek() else { break };
+        if matches!(b, b'0'..=b'9' | b'+' | b'-' | b'.' | b'/' | b'(' | b'<' | b'[') {
+            lexer.skip_value()?;
+            operands.push(&content[pos..lexer.pos]);
+            continue;
+        }
+
+        let op = lexer.token()?;
+        let segment = &content[start..lexer.pos];
+        start = lexer.pos;
+
+        let mut replacement = None;
+        let mut suffix = None;
+        match op {
+            // Inline images contain binary data that we cannot skip over.
+            b"BI" => return None,
+            b"q" => depth += 1,
+            b"Q" => {
+                if active.last().is_some_and(|&(_, d)| d == depth)
+                    && let Some((Region::Layer(_), _)) = active.pop()
+                {
+                    out.extend(b"\nEMC");
+                }
+                depth = depth.checked_sub(1)?;
+            }
+            b"cm" => {
+                let marker = parse_marker(&operands);
+                if let Some(marker) = marker
+                    && pending == Some(marker)
+                {
+                    let region = *regions.get(marker)?;
+                    if let Region::Layer(layer) = region {
+                        used.layers.insert(layer);
+                        let mut content = b"\n/OC /".to_vec();
+                        content.extend(layer_name(layer));
+                        content.extend(b" BDC");
+                        suffix = Some(content);
+                    }
+                    active.push((region, depth));
+                    used.regions.insert(marker);
+                    pending = None;
+                } else {
+                    pending = marker;
+                }
+            }
+            _ => {
+                if let Some(&(Region::Paint { fill, stroke }, _)) = active.last() {
+                    replacement = paint(op, &operands, fill, stroke, &mut used);
+                }
+            }
+        }
+
+        // The marker transforms are separated by a saved graphics state.
+        if !matches!(op, b"q" | b"cm") {
+            pending = None;
+        }
+
+        match replacement {
+            Some(replacement) => {
+                out.push(b'\n');
+                out.extend(replacement);
+            }
+            None => out.extend(segment),
+        }
+        out.extend(suffix.unwrap_or_default());
+        operands.clear();
+    }
+
+    out.extend(&content[start..]);
+    (!used.regions.is_empty()).then_some((out, used))
+}
+
+/// Parses the operands of a `cm` operator as a marker transform.
+fn parse_marker(operands: &[&[u8]]) -> Option<usize> {
+    let num = |value: &[u8]| std::str::from_utf8(value).ok()?.parse::<f32>().ok();
+    let [a, b, c, d, e, f] = operands else { return None };
+    let marker = num(e)?;
+    (num(a)? == -1.0
+        && num(b)? == 0.0
+        && num(c)? == 0.0
+        && num(d)? == 1.0
+        && num(f)? == 0.0
+        && marker >= 0.0
+        && marker.fract() == 0.0)
+        .then_some(marker as usize)
+}
+
+/// Rewrites a color operator in a region that is painted with spot colors.
+/// Returns `None` if the operator is kept.
+fn paint(
+    op: &[u8],
+    operands: &[&[u8]],
+    fill: Option<usize>,
+    stroke: Option<usize>,
+    used: &mut Used,
+) -> Option<Vec<u8>> {
+    let (ink, cs, scn) = match op {
+        b"g" | b"sc" | b"scn" | b"cs" => (fill?, "cs", "scn"),
+        b"G" | b"SC" | b"SCN" | b"CS" => (stroke?, "CS", "SCN"),
+        _ => return None,
+    };
+
+    // The color space is set together with the color below, except for
+    // gradients, which use a pattern.
+    if op == cs.as_bytes() {
+        return (!matches!(operands, [b"/Pattern"])).then(Vec::new);
+    }
+
+    let [value] = operands else { return None };
+    if let Some(name) = value.strip_prefix(b"/") {
+        used.patterns.push((name.to_vec(), ink));
+        return None;
+    }
+
+    used.inks.insert(ink);
+    let mut out = b"/".to_vec();
+    out.extend(spot_name(ink));
+    write!(out, " {cs} ").unwrap();
+    out.extend(*value);
+    write!(out, " {scn}").unwrap();
+    Some(out)
+}
+
+/// Adds what a rewritten content stream uses to the resources of `owner`,
+/// the dictionary of the stream or of its page. Returns the new dictionary.
+fn add_resources(
+    update: &mut Update,
+    owner: &[u8],
+    used: &Used,
+    spaces: &[i32],
+    groups: &[i32],
+) -> StrResult<Vec<u8>> {
+    let (id, mut resources) = resolve(update, owner, b"Resources")?;
+
+    if !used.inks.is_empty() {
+        let (spaces_id, mut dict) = resolve(update, &resources, b"ColorSpace")?;
+        for &ink in &used.inks {
+            dict = dict_set(&dict, &spot_name(ink), &write_ref(spaces[ink]));
+        }
+        resources = store(update, &resources, b"ColorSpace", spaces_id, dict);
+    }
+
+    if !used.layers.is_empty() {
+        let (properties_id, mut dict) = resolve(update, &resources, b"Properties")?;
+        for &layer in &used.layers {
+            dict = dict_set(&dict, &layer_name(layer), &write_ref(groups[layer]));
+        }
+        resources = store(update, &resources, b"Properties", properties_id, dict);
+    }
+
+    if !used.patterns.is_empty() {
+        let (_, patterns) = resolve(update, &resources, b"Pattern")?;
+        for (name, ink) in &used.patterns {
+            let Some(pattern) = dict_get(&patterns, name).and_then(parse_ref) else {
+                bail!("failed to find pattern");
+            };
+            set_shading_space(update, pattern, spaces[*ink])?;
+        }
+    }
+
+    Ok(store(update, owner, b"Resources", id, resources))
+}
+
+/// Makes the shading of a shading pattern use a separation color space.
+fn set_shading_space(update: &mut Update, pattern: i32, space: i32) -> StrResult<()> {
+    let dict = update.get(pattern).ok_or("failed to read pattern")?.to_vec();
+    if dict_get(&dict, b"PatternType") != Some(b"2") {
+        return Ok(());
+    }
+
+    let space = write_ref(space);
+    let shading = dict_get(&dict, b"Shading").ok_or("failed to find shading")?;
+    match parse_ref(shading) {
+        Some(id) => {
+            let body = match update.get_stream(id) {
+                Some((shading, data)) => {
+                    write_stream(&dict_set(shading, b"ColorSpace", &space), data)
+                }
+                None => {
+                    let shading = update.get(id).ok_or("failed to read shading")?;
+                    dict_set(shading, b"ColorSpace", &space)
+                }
+            };
+            update.write(id, body);
+        }
+        None => {
+            let shading = dict_set(shading, b"ColorSpace", &space);
+            update.write(pattern, dict_set(&dict, b"Shading", &shading));
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the dictionary in an entry of `dict`, which may be given directly or
+/// by reference. Also returns the object number in the latter case.
+fn resolve(
+    update: &Update,
+    dict: &[u8],
+    key: &[u8],
+) -> StrResult<(Option<i32>, Vec<u8>)> {
+    let value = dict_get(dict, key).unwrap_or(b"<<>>");
+    Ok(match parse_ref(value) {
+        Some(id) => {
+            (Some(id), update.get(id).ok_or("failed to read resources")?.to_vec())
+        }
+        None => (None, value.to_vec()),
+    })
+}
+
+/// Stores a dictionary read with [`resolve`]. Returns the new `dict`.
+fn store(
+    update: &mut Update,
+    dict: &[u8],
+    key: &[u8],
+    id: Option<i32>,
+    value: Vec<u8>,
+) -> Vec<u8> {
+    match id {
+        Some(id) => {
+            update.write(id, value);
+            dict.to_vec()
+        }
+        None => dict_set(dict, key, &value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use typst_library::foundations::Content;
+    use typst_library::layout::{
+        Abs, Frame, FrameItem, GroupItem, PagedDocument, Point, Size,
+    };
+    use typst_library::visualize::Geometry;
+
+    use super::*;
+    use crate::page::test_page;
+    use crate::{PdfStandard, PdfStandards};
+
+    const FILL: Region = Region::Paint { fill: Some(0), stroke: None };
+    const STROKE: Region = Region::Paint { fill: None, stroke: Some(1) };
+
+    /// Rewrites a content stream and returns it as a string.
+    fn rewritten(content: &str, regions: &[Region]) -> Option<(String, Used)> {
+        let (content, used) = rewrite(content.as_bytes(), regions)?;
+        Some((String::from_utf8(content).unwrap(), used))
+    }
+
+    #[test]
+    fn test_rewrite_solid() {
+        let content = "0.2 g\nq -1 0 0 1 0 0 cm\nq -1 0 0 1 0 0 cm\n\
+                       /cs0 cs 0.5 scn 0.1 G\n0 0 10 10 re B\nQ\nQ\n0.7 g";
+        let (content, used) = rewritten(content, &[FILL]).unwrap();
+        assert_eq!(
+            content,
+            "0.2 g\nq -1 0 0 1 0 0 cm\nq -1 0 0 1 0 0 cm\n\n\
+             /TypstSpot0 cs 0.5 scn 0.1 G\n0 0 10 10 re B\nQ\nQ\n0.7 g"
+        );
+        assert_eq!(used.regions, BTreeSet::from([0]));
+        assert_eq!(used.inks, BTreeSet::from([0]));
+        assert!(used.patterns.is_empty());
+    }
+
+    #[test]
+    fn test_rewrite_stroke_and_pattern() {
+        let content = "q -1 0 0 1 0 0 cm q -1 0 0 1 0 0 cm /Pattern cs /p0 scn Q Q \
+                       q -1 0 0 1 1 0 cm q -1 0 0 1 1 0 cm 0.25 G 1 g S Q Q";
+        let (content, used) = rewritten(content, &[FILL, STROKE]).unwrap();
+        assert_eq!(
+            content,
+            "q -1 0 0 1 0 0 cm q -1 0 0 1 0 0 cm /Pattern cs /p0 scn Q Q \
+             q -1 0 0 1 1 0 cm q -1 0 0 1 1 0 cm\n/TypstSpot1 CS 0.25 SCN 1 g S Q Q"
+        );
+        assert_eq!(used.inks, BTreeSet::from([1]));
+        assert_eq!(used.patterns, vec![(b"p0".to_vec(), 0)]);
+    }
+
+    #[test]
+    fn test_rewrite_without_region() {
+        // A single flip is not a marker.
+        let content = "q -1 0 0 1 0 0 cm 0.5 g (a\\) g) Tj [<0102> 3] TJ Q";
+        assert_eq!(rewritten(content, &[FILL]), None);
+        // Neither are two flips with different markers.
+        let content = "q -1 0 0 1 0 0 cm q -1 0 0 1 1 0 cm 0.5 g Q Q";
+        assert_eq!(rewritten(content, &[FILL, STROKE]), None);
+    }
+
+    #[test]
+    fn test_separation() {
+        let fallback = Cmyk { c: 0.0, m: 0.8, y: 0.6, k: 0.1 };
+        let space = String::from_utf8(separation("PANTONE 186 C", fallback)).unwrap();
+        assert!(space.starts_with("[/Separation /PANTONE#20186#20C /DeviceCMYK <<"));
+    }
+
+    #[test]
+    fn test_rewrite_layer() {
+        let content = "q -1 0 0 1 0 0 cm q -1 0 0 1 0 0 cm\n\
+                       q -1 0 0 1 1 0 cm q -1 0 0 1 1 0 cm 0.5 g f Q Q\nQ Q";
+        let (content, used) = rewritten(content, &[Region::Layer(2), FILL]).unwrap();
+        assert_eq!(
+            content,
+            "q -1 0 0 1 0 0 cm q -1 0 0 1 0 0 cm\n/OC /TypstLayer2 BDC\n\
+             q -1 0 0 1 1 0 cm q -1 0 0 1 1 0 cm\n/TypstSpot0 cs 0.5 scn f Q Q\
+             \nEMC\nQ Q"
+        );
+        assert_eq!(used.layers, BTreeSet::from([2]));
+        assert_eq!(used.inks, BTreeSet::from([0]));
+    }
+
+    #[test]
+    fn test_optional_content_properties() {
+        let layers = IndexMap::from([
+            ("Drawing".into(), Layer { visible: true, print: true }),
+            ("Solutions".into(), Layer { visible: false, print: false }),
+        ]);
+        let properties = optional_content_properties(&[4, 5], &layers);
+        assert_eq!(
+            String::from_utf8(properties).unwrap(),
+            "<</OCGs [4 0 R 5 0 R] /D <</Order [4 0 R 5 0 R] /OFF [5 0 R] /AS [\
+             <</Event /View /OCGs [4 0 R 5 0 R] /Category [/View]>>\
+             <</Event /Print /OCGs [4 0 R 5 0 R] /Category [/Print]>>]>>>>"
+        );
+
+        let group =
+            optional_content_group("Solutions", Layer { visible: false, print: true });
+        assert_eq!(
+            String::from_utf8(group).unwrap(),
+            "<</Type /OCG /Name (Solutions) /Usage \
+             <</View <</ViewState /OFF>> /Print <</PrintState /ON>>>>>>"
+        );
+    }
+
+    /// Exports a page with a single item.
+    fn export(item: FrameItem, options: &PdfOptions) -> Vec<u8> {
+        let mut frame = Frame::hard(Size::splat(Abs::pt(10.0)));
+        frame.push(Point::zero(), item);
+        let page = test_page(frame);
+        let document = PagedDocument {
+            pages: vec![page],
+            info: Default::default(),
+            introspector: Default::default(),
+        };
+        crate::pdf(&document, options).unwrap()
+    }
+
+    /// A rectangle filled with the given color.
+    fn rect(color: Color) -> FrameItem {
+        let shape = Geometry::Rect(Size::splat(Abs::pt(5.0))).filled(color);
+        FrameItem::Shape(shape, Span::detached())
+    }
+
+    /// Whether a file contains the given bytes.
+    fn contains(pdf: &[u8], bytes: &[u8]) -> bool {
+        pdf.windows(bytes.len()).any(|window| window == bytes)
+    }
+
+    #[test]
+    fn test_write_separation() {
+        let spot = Spot {
+            name: PicoStr::intern("Brand"),
+            tint: 0.5,
+            fallback: Cmyk { c: 1.0, m: 0.5, y: 0.0, k: 0.0 },
+        };
+        let options = PdfOptions { tagged: false, ..Default::default() };
+        let pdf = export(rect(Color::Spot(spot)), &options);
+        assert!(contains(&pdf, b"[/Separation /Brand /DeviceCMYK"));
+        assert!(contains(&pdf, b"/TypstSpot0 "));
+    }
+
+    #[test]
+    fn test_write_layers() {
+        let mut frame = Frame::soft(Size::splat(Abs::pt(10.0)));
+        frame.push(Point::zero(), rect(Color::BLACK));
+        let mut group = GroupItem::new(frame);
+        let layer = LayerElem::new("Solutions".into(), Content::empty());
+        group.layer = Some(Packed::new(layer.with_visible(false)));
+        let item = FrameItem::Group(group);
+
+        let options = PdfOptions { tagged: false, ..Default::default() };
+        let pdf = export(item.clone(), &options);
+        assert!(contains(&pdf, b"/OCProperties <</OCGs ["));
+        assert!(contains(&pdf, b"/Type /OCG /Name (Solutions)"));
+        assert!(contains(&pdf, b"/TypstLayer0 "));
+
+        // PDF 1.4 does not support optional content.
+        let options = PdfOptions {
+            standards: PdfStandards::new(&[PdfStandard::V_1_4]).unwrap(),
+            tagged: false,
+            ..Default::default()
+        };
+        let pdf = export(item, &options);
+        assert!(!contains(&pdf, b"/OCProperties"));
+    }
+
+    #[test]
+    fn test_write_missing_marker() {
+        let options = PdfOptions { tagged: false, ..Default::default() };
+        let pdf = export(rect(Color::BLACK), &options);
+        let mut update = Update::new(pdf).unwrap();
+        let marks = Marks { regions: vec![FILL], ..Default::default() };
+        assert_eq!(
+            write(&mut update, &marks).unwrap_err(),
+            "failed to find marked region 0 in content streams"
+        );
+    }
+}
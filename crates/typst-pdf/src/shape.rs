@@ -7,7 +7,7 @@ use typst_utils::defer;
 
 use crate::convert::{FrameContext, GlobalContext};
 use crate::util::{AbsExt, TransformExt, convert_path};
-use crate::{paint, tags};
+use crate::{paint, print, rewrite, tags};
 
 #[typst_macros::time(name = "handle shape")]
 pub(crate) fn handle_shape(
@@ -29,6 +29,7 @@ pub(crate) fn handle_shape(
 
     if let Some(path) = convert_geometry(&shape.geometry) {
         let fill = if let Some(paint) = &shape.fill {
+            print::check_paint(gc, paint, span)?;
             Some(paint::convert_fill(
                 gc,
                 paint,
@@ -45,8 +46,10 @@ pub(crate) fn handle_shape(
         let stroke = shape.stroke.as_ref().and_then(|stroke| {
             if stroke.thickness.to_f32() > 0.0 { Some(stroke) } else { None }
         });
+        let marker = gc.marks.paint(shape.fill.as_ref(), stroke.map(|s| &s.paint));
 
         let stroke = if let Some(stroke) = &stroke {
+            print::check_paint(gc, &stroke.paint, span)?;
             let stroke = paint::convert_stroke(
                 gc,
                 stroke,
@@ -65,7 +68,7 @@ pub(crate) fn handle_shape(
         if fill.is_some() || stroke.is_some() {
             surface.set_fill(fill);
             surface.set_stroke(stroke);
-            surface.draw_path(&path);
+            rewrite::marked(&mut surface, marker, |surface| surface.draw_path(&path));
         }
     }
 
@@ -0,0 +1,194 @@
+use std::io::Write;
+
+use typst_library::diag::{StrResult, bail};
+
+use crate::PdfSigner;
+use crate::update::Update;
+
+/// The key of the byte range in the signature dictionary.
+const BYTE_RANGE: &[u8] = b"/ByteRange [";
+
+/// The number of digits reserved for each offset in the byte range.
+const DIGITS: usize = 10;
+
+/// Writes a signature dictionary with placeholders for the byte range and a
+/// signature of at most `size` bytes. Returns its object number.
+pub(crate) fn write_placeholder(update: &mut Update, size: usize) -> i32 {
+    let id = update.alloc();
+    let mut dict = b"<</Type /Sig\n/Filter /Adobe.PPKLite\n".to_vec();
+    dict.extend(b"/SubFilter /adbe.pkcs7.detached\n");
+    dict.extend(BYTE_RANGE);
+    for i in 0..4 {
+        if i > 0 {
+            dict.push(b' ');
+        }
+        dict.extend([b'0'; DIGITS]);
+    }
+    dict.extend(b"]\n/Contents <");
+    dict.resize(dict.len() + 2 * size, b'0');
+    dict.extend(b">>>");
+    update.write(id, dict);
+    id
+}
+
+/// Fills in the last signature placeholder in a PDF file.
+pub(crate) fn sign(pdf: &mut [u8], signer: &dyn PdfSigner) -> StrResult<()> {
+    let Some(start) = pdf.windows(BYTE_RANGE.len()).rposition(|w| w == BYTE_RANGE) else {
+        bail!("PDF has no signature placeholder");
+    };
+
+    let range_start = start + BYTE_RANGE.len();
+    let range_len = 4 * DIGITS + 3;
+    let Some(offset) = pdf[range_start..].windows(11).position(|w| w == b"/Contents <")
+    else {
+        bail!("signature placeholder is malformed");
+    };
+
+    // The signature covers everything but the hex string in `/Contents`.
+    let contents_start = range_start + offset + 10;
+    let Some(len) = pdf[contents_start..].iter().position(|&b| b == b'>') else {
+        bail!("signature placeholder is malformed");
+    };
+    let contents_end = contents_start + len + 1;
+
+    let range = [0, contents_start, contents_end, pdf.len() - contents_end];
+    let mut formatted = vec![];
+    for (i, value) in range.iter().enumerate() {
+        if i > 0 {
+            formatted.push(b' ');
+        }
+        write!(formatted, "{value:<DIGITS$}").unwrap();
+    }
+    if formatted.len() != range_len {
+        bail!("PDF is too large to be signed");
+    }
+    pdf[range_start..range_start + range_len].copy_from_slice(&formatted);
+
+    let mut data = pdf[..contents_start].to_vec();
+    data.extend_from_slice(&pdf[contents_end..]);
+    let signature = signer.sign(&data)?;
+
+    let capacity = (contents_end - contents_start - 2) / 2;
+    if signature.len() > capacity {
+        bail!(
+            "signature is too large ({} bytes, but only {capacity} bytes are reserved)",
+            signature.len(),
+        );
+    }
+
+    let mut hex = Vec::with_capacity(2 * signature.len());
+    for byte in signature {
+        write!(hex, "{byte:02X}").unwrap();
+    }
+    pdf[contents_start + 1..contents_start + 1 + hex.len()].copy_from_slice(&hex);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A minimal PDF file with just a catalog.
+    fn minimal_pdf() -> Vec<u8> {
+        let mut pdf = b"%PDF-1.7\n".to_vec();
+        pdf.extend(b"1 0 obj\n<</Type /Catalog>>\nendobj\n");
+        let xref = pdf.len();
+        pdf.extend(b"xref\n0 2\n0000000000 65535 f\r\n0000000009 00000 n\r\n");
+        write!(pdf, "trailer\n<</Size 2 /Root 1 0 R>>\nstartxref\n{xref}\n%%EOF\n")
+            .unwrap();
+        pdf
+    }
+
+    #[test]
+    fn test_sign_placeholder() {
+        let mut update = Update::new(minimal_pdf()).unwrap();
+        write_placeholder(&mut update, 4);
+        let mut pdf = update.finish();
+
+        let signer = |data: &[u8]| -> StrResult<Vec<u8>> {
+            assert!(data.starts_with(b"%PDF-1.7"));
+            assert!(data.ends_with(b"%%EOF\n"));
+            assert!(!data.windows(9).any(|w| w == b"<00000000"));
+            Ok(vec![0xCA, 0xFE])
+        };
+        sign(&mut pdf, &signer).unwrap();
+
+        let text = String::from_utf8_lossy(&pdf);
+        let contents = text.find("/Contents <").unwrap() + 10;
+        let end = contents + 10;
+        assert_eq!(&text[contents..end], "<CAFE0000>");
+        assert!(text.contains(&format!("/ByteRange [0 {contents:<10} {end:<10} ")));
+        let large = |_: &[u8]| -> StrResult<Vec<u8>> { Ok(vec![0; 5]) };
+        assert!(sign(&mut pdf, &large).is_err());
+    }
+
+    #[test]
+    fn test_sign_cms_round_trip() {
+        use openssl::asn1::Asn1Time;
+        use openssl::cms::{CMSOptions, CmsContentInfo};
+        use openssl::hash::MessageDigest;
+        use openssl::pkey::PKey;
+        use openssl::rsa::Rsa;
+        use openssl::x509::{X509, X509NameBuilder};
+
+        // A self-signed certificate for a throwaway key.
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", "Typst").unwrap();
+        let name = name.build();
+        let mut cert = X509::builder().unwrap();
+        cert.set_version(2).unwrap();
+        cert.set_subject_name(&name).unwrap();
+        cert.set_issuer_name(&name).unwrap();
+        cert.set_pubkey(&key).unwrap();
+        cert.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+        cert.set_not_after(&Asn1Time::days_from_now(1).unwrap()).unwrap();
+        cert.sign(&key, MessageDigest::sha256()).unwrap();
+        let cert = cert.build();
+
+        let signer = |data: &[u8]| -> StrResult<Vec<u8>> {
+            let flags = CMSOptions::DETACHED | CMSOptions::BINARY;
+            let cms =
+                CmsContentInfo::sign(Some(&cert), Some(&key), None, Some(data), flags)
+                    .map_err(|err| err.to_string())?;
+            cms.to_der().map_err(|err| err.to_string().into())
+        };
+
+        let mut update = Update::new(minimal_pdf()).unwrap();
+        write_placeholder(&mut update, 4096);
+        let mut pdf = update.finish();
+        sign(&mut pdf, &signer).unwrap();
+
+        // Reassemble the signed data from the byte range.
+        let text = String::from_utf8(pdf.clone()).unwrap();
+        let start = text.find("/ByteRange [").unwrap() + BYTE_RANGE.len();
+        let end = start + text[start..].find(']').unwrap();
+        let range: Vec<usize> = text[start..end]
+            .split_whitespace()
+            .map(|v| v.parse().unwrap())
+            .collect();
+        assert_eq!(range[0], 0);
+        assert_eq!(range[2] + range[3], pdf.len());
+        let mut data = pdf[..range[1]].to_vec();
+        data.extend_from_slice(&pdf[range[2]..]);
+
+        // The hex string between the ranges holds the signature, padded with
+        // zeros. The DER encoding carries its own length, so the padding is
+        // ignored when parsing.
+        let hex = &text[range[1] + 1..range[2] - 1];
+        let der: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+        let mut cms = CmsContentInfo::from_der(&der).unwrap();
+
+        let flags = CMSOptions::BINARY | CMSOptions::NO_SIGNER_CERT_VERIFY;
+        cms.verify(None, None, Some(&data), None, flags).unwrap();
+
+        // Changing any covered byte invalidates the signature.
+        let last = data.len() - 1;
+        data[last] ^= 1;
+        assert!(cms.verify(None, None, Some(&data), None, flags).is_err());
+    }
+}
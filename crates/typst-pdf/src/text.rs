@@ -6,14 +6,14 @@ use krilla::surface::{Location, Surface};
 use krilla::text::GlyphId;
 use typst_library::diag::{SourceResult, bail};
 use typst_library::layout::Size;
-use typst_library::text::{Font, Glyph, TextItem};
+use typst_library::text::{Font, FontEmbedding, Glyph, TextItem};
 use typst_library::visualize::FillRule;
 use typst_syntax::Span;
 use typst_utils::defer;
 
 use crate::convert::{FrameContext, GlobalContext};
 use crate::util::{AbsExt, TransformExt, display_font};
-use crate::{paint, tags};
+use crate::{RestrictedFonts, font, paint, print, rewrite, tags};
 
 #[typst_macros::time(name = "handle text")]
 pub(crate) fn handle_text(
@@ -26,6 +26,15 @@ pub(crate) fn handle_text(
     let surface = handle.surface();
 
     let font = convert_font(gc, t.font.clone())?;
+    let span = t.glyphs.first().map_or(Span::detached(), |glyph| glyph.span.0);
+    let outlined = check_embedding(gc, &t.font, span)?;
+    if !outlined {
+        font::register_glyphs(gc, &t.font, &font, surface, span)?;
+    }
+    print::check_paint(gc, &t.fill, span)?;
+    if let Some(stroke) = &t.stroke {
+        print::check_paint(gc, &stroke.paint, span)?;
+    }
     let fill = paint::convert_fill(
         gc,
         &t.fill,
@@ -51,18 +60,44 @@ pub(crate) fn handle_text(
     let mut surface = defer(surface, |s| s.pop());
     surface.set_fill(Some(fill));
     surface.set_stroke(stroke);
-    surface.draw_glyphs(
-        krilla::geom::Point::from_xy(0.0, 0.0),
-        glyphs,
-        font.clone(),
-        text,
-        size.to_f32(),
-        false,
-    );
+    let marker = gc.marks.paint(Some(&t.fill), t.stroke.as_ref().map(|s| &s.paint));
+    rewrite::marked(&mut surface, marker, |surface| {
+        surface.draw_glyphs(
+            krilla::geom::Point::from_xy(0.0, 0.0),
+            glyphs,
+            font.clone(),
+            text,
+            size.to_f32(),
+            outlined,
+        )
+    });
 
     Ok(())
 }
 
+/// Checks whether the license of a font permits embedding it. Returns whether
+/// the glyphs should be drawn as outlines instead.
+fn check_embedding(gc: &GlobalContext, font: &Font, span: Span) -> SourceResult<bool> {
+    let reason = match font.embedding() {
+        FontEmbedding::Installable => return Ok(false),
+        FontEmbedding::NoSubsetting if gc.options.full_fonts => return Ok(false),
+        FontEmbedding::NoSubsetting => "only permits embedding it in full",
+        FontEmbedding::BitmapOnly => "only permits embedding its bitmaps",
+        FontEmbedding::Restricted => "does not permit embedding it",
+    };
+
+    match gc.options.restricted_fonts {
+        RestrictedFonts::Embed => Ok(false),
+        RestrictedFonts::Outline => Ok(true),
+        RestrictedFonts::Error => bail!(
+            span,
+            "the license of the font {} {reason}", display_font(font);
+            hint: "use a different font";
+            hint: "or draw the text as outlines with `--pdf-restricted-fonts outline`"
+        ),
+    }
+}
+
 fn convert_font(
     gc: &mut GlobalContext,
     typst_font: Font,
@@ -136,3 +171,114 @@ impl krilla::text::Glyph for PdfGlyph {
         Some(self.0.span.0.into_raw())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ecow::EcoString;
+    use typst_library::foundations::Bytes;
+    use typst_library::layout::{Abs, Em, Frame, FrameItem, PagedDocument, Point};
+    use typst_library::text::{Lang, TextItem};
+    use typst_library::visualize::{Color, Paint};
+
+    use super::*;
+    use crate::PdfOptions;
+    use crate::page::test_page;
+
+    /// A bundled TrueType font whose `fsType` is replaced with the given value.
+    fn font(fs_type: u16) -> Font {
+        let mut data = typst_assets::fonts()
+            .find(|data| data.starts_with(&[0, 1, 0, 0]))
+            .unwrap()
+            .to_vec();
+        let tables = u16::from_be_bytes([data[4], data[5]]) as usize;
+        let record = (0..tables)
+            .map(|i| 12 + 16 * i)
+            .find(|&record| &data[record..record + 4] == b"OS/2")
+            .unwrap();
+        let offset =
+            u32::from_be_bytes(data[record + 8..record + 12].try_into().unwrap());
+        let offset = offset as usize + 8;
+        data[offset..offset + 2].copy_from_slice(&fs_type.to_be_bytes());
+        Font::new(Bytes::new(data), 0).unwrap()
+    }
+
+    /// Exports a page with a single glyph in the given font.
+    fn export(font: Font, options: PdfOptions) -> Result<Vec<u8>, EcoString> {
+        let id = font.ttf().glyph_index('A').unwrap().0;
+        let text = TextItem {
+            font,
+            size: Abs::pt(10.0),
+            fill: Paint::Solid(Color::BLACK),
+            stroke: None,
+            lang: Lang::ENGLISH,
+            region: None,
+            text: "A".into(),
+            glyphs: vec![Glyph {
+                id,
+                x_advance: Em::new(0.5),
+                x_offset: Em::zero(),
+                y_advance: Em::zero(),
+                y_offset: Em::zero(),
+                range: 0..1,
+                span: (Span::detached(), 0),
+            }],
+        };
+        let mut frame = Frame::hard(Size::splat(Abs::pt(20.0)));
+        frame.push(Point::with_y(Abs::pt(10.0)), FrameItem::Text(text));
+        let page = test_page(frame);
+        let document = PagedDocument {
+            pages: vec![page],
+            info: Default::default(),
+            introspector: Default::default(),
+        };
+        let options = PdfOptions { tagged: false, ..options };
+        crate::pdf(&document, &options).map_err(|errors| errors[0].message.clone())
+    }
+
+    #[test]
+    fn test_restricted_font_error() {
+        let options = PdfOptions {
+            restricted_fonts: RestrictedFonts::Error,
+            ..Default::default()
+        };
+        let message = export(font(0x0002), options).unwrap_err();
+        assert!(message.contains("does not permit embedding it"), "{message}");
+        let options = PdfOptions {
+            restricted_fonts: RestrictedFonts::Error,
+            ..Default::default()
+        };
+        assert!(export(font(0x0000), options).is_ok());
+    }
+
+    #[test]
+    fn test_restricted_font_outline() {
+        let options = PdfOptions {
+            restricted_fonts: RestrictedFonts::Outline,
+            ..Default::default()
+        };
+        let pdf = export(font(0x0002), options).unwrap();
+        assert!(!pdf.windows(9).any(|w| w == b"/FontFile"));
+    }
+
+    #[test]
+    fn test_full_font() {
+        let options = PdfOptions {
+            restricted_fonts: RestrictedFonts::Error,
+            ..Default::default()
+        };
+        let message = export(font(0x0100), options).unwrap_err();
+        assert!(message.contains("only permits embedding it in full"), "{message}");
+
+        let font = font(0x0100);
+        let name = font.find_name(6).unwrap();
+        let options = PdfOptions {
+            restricted_fonts: RestrictedFonts::Error,
+            full_fonts: true,
+            ..Default::default()
+        };
+        let pdf = export(font, options).unwrap();
+        let untagged = format!("/FontName /{name}");
+        assert!(pdf.windows(untagged.len()).any(|w| w == untagged.as_bytes()));
+        assert!(!pdf.windows(7).any(|w| w == b"/CIDSet"));
+    }
+}
@@ -0,0 +1,723 @@
+//! Appending incremental updates to a serialized PDF.
+//!
+//! Some PDF features, like form fields, are not supported by krilla. To still
+//! provide them, we append an incremental update to the file produced by
+//! krilla. The update can add new objects and replace existing ones while
+//! leaving the original bytes untouched.
+//!
+//! All features share a single update, which is appended once after all of
+//! them were written, so the file is parsed only once and receives a single
+//! additional cross-reference section.
+
+use std::io::Write;
+
+use rustc_hash::FxHashMap;
+use typst_library::diag::{StrResult, bail};
+
+/// An incremental update to a PDF file.
+pub(crate) struct Update {
+    /// The original file.
+    pdf: Vec<u8>,
+    /// The byte offsets of the original file's objects by object number.
+    offsets: FxHashMap<i32, usize>,
+    /// The trailer dictionary of the original file.
+    trailer: Vec<u8>,
+    /// The byte offset of the original file's cross-reference table.
+    startxref: usize,
+    /// The next free object number.
+    next: i32,
+    /// The objects to write, by object number.
+    objects: FxHashMap<i32, Vec<u8>>,
+}
+
+impl Update {
+    /// Prepares an update to the given file.
+    pub fn new(pdf: Vec<u8>) -> StrResult<Self> {
+        let Some(startxref) = find_startxref(&pdf) else {
+            bail!("failed to find cross-reference table");
+        };
+
+        let mut offsets = FxHashMap::default();
+        let Some(trailer) = parse_xref_table(&pdf, startxref, &mut offsets) else {
+            bail!("failed to parse cross-reference table");
+        };
+
+        // Files that already received an update have multiple sections, each
+        // pointing to the previous one. Newer sections take precedence.
+        let mut prev = dict_get(&trailer, b"Prev").and_then(parse_int);
+        let mut visited = vec![startxref];
+        while let Some(offset) = prev.and_then(|offset| usize::try_from(offset).ok()) {
+            if visited.contains(&offset) {
+                bail!("cross-reference tables form a cycle");
+            }
+            visited.push(offset);
+            let Some(older) = parse_xref_table(&pdf, offset, &mut offsets) else {
+                bail!("failed to parse cross-reference table");
+            };
+            prev = dict_get(&older, b"Prev").and_then(parse_int);
+        }
+
+        let Some(size) = dict_get(&trailer, b"Size").and_then(parse_int) else {
+            bail!("failed to parse trailer");
+        };
+
+        Ok(Self {
+            pdf,
+            offsets,
+            trailer,
+            startxref,
+            next: size as i32,
+            objects: FxHashMap::default(),
+        })
+    }
+
+    /// Allocates a new object number.
+    pub fn alloc(&mut self) -> i32 {
+        let id = self.next;
+        self.next += 1;
+        id
+    }
+
+    /// The object number of the document catalog.
+    pub fn root(&self) -> StrResult<i32> {
+        match dict_get(&self.trailer, b"Root").and_then(parse_ref) {
+            Some(id) => Ok(id),
+            None => bail!("failed to find document catalog"),
+        }
+    }
+
+    /// The object number of the document information dictionary, if any.
+    pub fn info(&self) -> Option<i32> {
+        dict_get(&self.trailer, b"Info").and_then(parse_ref)
+    }
+
+    /// Sets the document information dictionary.
+    pub fn set_info(&mut self, id: i32) {
+        self.trailer = dict_set(&self.trailer, b"Info", &write_ref(id));
+    }
+
+    /// Retrieves the value of an object, taking replaced objects into
+    /// account. For stream objects, only the stream dictionary is returned.
+    pub fn get(&self, id: i32) -> Option<&[u8]> {
+        if let Some(body) = self.objects.get(&id) {
+            let mut lexer = Lexer::new(body);
+            let start = lexer.skip_ws();
+            lexer.skip_value()?;
+            return Some(&body[start..lexer.pos]);
+        }
+
+        let (start, end) = self.locate(id)?;
+        Some(&self.pdf[start..end])
+    }
+
+    /// Finds the start and end offsets of an object's value in the original
+    /// file.
+    fn locate(&self, id: i32) -> Option<(usize, usize)> {
+        let offset = *self.offsets.get(&id)?;
+        let mut lexer = Lexer::new(&self.pdf);
+        lexer.pos = offset;
+        lexer.skip_ws();
+        lexer.token()?;
+        lexer.skip_ws();
+        lexer.token()?;
+        lexer.skip_ws();
+        if lexer.token()? != b"obj" {
+            return None;
+        }
+        let start = lexer.skip_ws();
+        lexer.skip_value()?;
+        Some((start, lexer.pos))
+    }
+
+    /// Retrieves the dictionary and data of a stream object, taking replaced
+    /// objects into account.
+    pub fn get_stream(&self, id: i32) -> Option<(&[u8], &[u8])> {
+        if let Some(body) = self.objects.get(&id) {
+            let mut lexer = Lexer::new(body);
+            let start = lexer.skip_ws();
+            lexer.skip_value()?;
+            return split_stream(body, start, lexer.pos);
+        }
+
+        let (start, end) = self.locate(id)?;
+        split_stream(&self.pdf, start, end)
+    }
+
+    /// Retrieves the complete body of an object from the original file. For
+    /// stream objects, this includes the stream data.
+    pub fn get_raw(&self, id: i32) -> Option<&[u8]> {
+        let (start, end) = self.locate(id)?;
+        let mut lexer = Lexer::new(&self.pdf);
+        lexer.pos = end;
+        lexer.skip_ws();
+        if !lexer.eat(b"stream") {
+            return Some(&self.pdf[start..end]);
+        }
+        if !lexer.eat(b"\r\n") && !lexer.eat(b"\n") {
+            return None;
+        }
+
+        let length = dict_get(&self.pdf[start..end], b"Length")?;
+        let len = match parse_ref(length) {
+            Some(id) => self.get(id).and_then(parse_int)?,
+            None => parse_int(length)?,
+        };
+        lexer.pos = lexer.pos.checked_add(usize::try_from(len).ok()?)?;
+        if lexer.pos > self.pdf.len() {
+            return None;
+        }
+        lexer.skip_ws();
+        if !lexer.eat(b"endstream") {
+            return None;
+        }
+
+        Some(&self.pdf[start..lexer.pos])
+    }
+
+    /// The numbers of the objects in the original file, in ascending order.
+    pub fn ids(&self) -> Vec<i32> {
+        let mut ids: Vec<i32> = self.offsets.keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// The trailer dictionary of the original file.
+    pub fn trailer(&self) -> &[u8] {
+        &self.trailer
+    }
+
+    /// Retrieves the object numbers of all pages in order.
+    pub fn pages(&self) -> StrResult<Vec<i32>> {
+        let catalog = self.get(self.root()?).ok_or("failed to read document catalog")?;
+        let Some(tree) = dict_get(catalog, b"Pages").and_then(parse_ref) else {
+            bail!("failed to find page tree");
+        };
+
+        let mut pages = vec![];
+        self.collect_pages(tree, &mut pages, 0)?;
+        Ok(pages)
+    }
+
+    /// Collects the pages in a page tree node.
+    fn collect_pages(
+        &self,
+        id: i32,
+        pages: &mut Vec<i32>,
+        depth: usize,
+    ) -> StrResult<()> {
+        if depth > 64 {
+            bail!("page tree is too deep");
+        }
+
+        let node = self.get(id).ok_or("failed to read page tree")?;
+        if dict_get(node, b"Type") == Some(b"/Page") {
+            pages.push(id);
+            return Ok(());
+        }
+
+        let kids = dict_get(node, b"Kids").and_then(parse_array).unwrap_or_default();
+        for kid in kids {
+            let kid = parse_ref(kid).ok_or("failed to parse page tree")?;
+            self.collect_pages(kid, pages, depth + 1)?;
+        }
+
+        Ok(())
+    }
+
+    /// Appends annotations to the annotation array of a page.
+    pub fn add_annotations(
+        &mut self,
+        page: i32,
+        annotations: impl IntoIterator<Item = i32>,
+    ) -> StrResult<()> {
+        let dict = self.get(page).ok_or("failed to read page")?;
+        let mut items: Vec<Vec<u8>> = match dict_get(dict, b"Annots") {
+            Some(value) => {
+                let array = match parse_ref(value) {
+                    Some(id) => self.get(id).ok_or("failed to read annotations")?,
+                    None => value,
+                };
+                let items = parse_array(array).ok_or("failed to read annotations")?;
+                items.into_iter().map(Vec::from).collect()
+            }
+            None => vec![],
+        };
+        items.extend(annotations.into_iter().map(write_ref));
+
+        let dict = dict_set(dict, b"Annots", &write_array(&items));
+        self.write(page, dict);
+        Ok(())
+    }
+
+    /// Writes an object, replacing the existing one with the same number if
+    /// there is one.
+    pub fn write(&mut self, id: i32, body: Vec<u8>) {
+        self.objects.insert(id, body);
+    }
+
+    /// Appends the update to the file. Leaves the file untouched if nothing
+    /// was written.
+    pub fn finish(self) -> Vec<u8> {
+        let mut out = self.pdf;
+        if self.objects.is_empty() {
+            return out;
+        }
+
+        if !out.ends_with(b"\n") {
+            out.push(b'\n');
+        }
+
+        let mut objects: Vec<_> = self.objects.into_iter().collect();
+        objects.sort_by_key(|&(id, _)| id);
+
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (id, body) in objects {
+            offsets.push((id, out.len()));
+            writeln!(out, "{id} 0 obj").unwrap();
+            out.extend(body);
+            out.extend(b"\nendobj\n");
+        }
+
+        let xref = out.len();
+        out.extend(b"xref\n");
+        for run in offsets.chunk_by(|a, b| a.0 + 1 == b.0) {
+            writeln!(out, "{} {}", run[0].0, run.len()).unwrap();
+            for (_, offset) in run {
+                // Each entry must be exactly 20 bytes long.
+                write!(out, "{offset:010} 00000 n\r").unwrap();
+                out.push(b'\n');
+            }
+        }
+
+        let mut trailer =
+            dict_set(&self.trailer, b"Size", self.next.to_string().as_bytes());
+        trailer = dict_set(&trailer, b"Prev", self.startxref.to_string().as_bytes());
+        out.extend(b"trailer\n");
+        out.extend(trailer);
+        writeln!(out, "\nstartxref\n{xref}\n%%EOF").unwrap();
+        out
+    }
+}
+
+/// Splits a stream object whose dictionary spans `start..end` into the
+/// dictionary and the stream data.
+fn split_stream(data: &[u8], start: usize, end: usize) -> Option<(&[u8], &[u8])> {
+    let dict = &data[start..end];
+    let len = usize::try_from(dict_get(dict, b"Length").and_then(parse_int)?).ok()?;
+
+    let mut lexer = Lexer::new(data);
+    lexer.pos = end;
+    lexer.skip_ws();
+    if !lexer.eat(b"stream") {
+        return None;
+    }
+    if !lexer.eat(b"\r\n") && !lexer.eat(b"\n") {
+        return None;
+    }
+
+    let stream = data.get(lexer.pos..lexer.pos.checked_add(len)?)?;
+    Some((dict, stream))
+}
+
+/// Finds the offset of the cross-reference table.
+fn find_startxref(pdf: &[u8]) -> Option<usize> {
+    const KEYWORD: &[u8] = b"startxref";
+    let pos = pdf.windows(KEYWORD.len()).rposition(|w| w == KEYWORD)?;
+    let mut lexer = Lexer::new(pdf);
+    lexer.pos = pos + KEYWORD.len();
+    lexer.skip_ws();
+    parse_int(lexer.token()?).and_then(|offset| usize::try_from(offset).ok())
+}
+
+/// Parses a cross-reference table at the given offset into `offsets` and
+/// returns the trailer dictionary that follows it. Objects that already have an
+/// offset are left untouched.
+fn parse_xref_table(
+    pdf: &[u8],
+    offset: usize,
+    offsets: &mut FxHashMap<i32, usize>,
+) -> Option<Vec<u8>> {
+    let mut lexer = Lexer::new(pdf);
+    lexer.pos = offset;
+    lexer.skip_ws();
+    if lexer.token()? != b"xref" {
+        return None;
+    }
+
+    loop {
+        lexer.skip_ws();
+        let token = lexer.token()?;
+        if token == b"trailer" {
+            break;
+        }
+
+        let start = parse_int(token)?;
+        lexer.skip_ws();
+        let count = parse_int(lexer.token()?)?;
+        for id in start..start + count {
+            lexer.skip_ws();
+            let offset = parse_int(lexer.token()?)?;
+            lexer.skip_ws();
+            lexer.token()?;
+            lexer.skip_ws();
+            if lexer.token()? == b"n" {
+                offsets.entry(id as i32).or_insert(offset as usize);
+            }
+        }
+    }
+
+    let start = lexer.skip_ws();
+    lexer.skip_value()?;
+    Some(pdf[start..lexer.pos].to_vec())
+}
+
+/// Returns the entries of a dictionary.
+pub(crate) fn dict_entries(dict: &[u8]) -> Option<Vec<(&[u8], &[u8])>> {
+    let mut lexer = Lexer::new(dict);
+    lexer.skip_ws();
+    if !lexer.eat(b"<<") {
+        return None;
+    }
+
+    let mut entries = vec![];
+    loop {
+        lexer.skip_ws();
+        if lexer.eat(b">>") {
+            break;
+        }
+
+        let start = lexer.pos;
+        if !lexer.eat(b"/") {
+            return None;
+        }
+        lexer.skip_regular();
+        let key = &dict[start + 1..lexer.pos];
+        let start = lexer.skip_ws();
+        lexer.skip_value()?;
+        entries.push((key, &dict[start..lexer.pos]));
+    }
+
+    Some(entries)
+}
+
+/// Returns the raw value of a dictionary entry.
+pub(crate) fn dict_get<'a>(dict: &'a [u8], key: &[u8]) -> Option<&'a [u8]> {
+    dict_entries(dict)?
+        .into_iter()
+        .find(|&(k, _)| k == key)
+        .map(|(_, v)| v)
+}
+
+/// Returns a copy of a dictionary with an entry added or replaced.
+pub(crate) fn dict_set(dict: &[u8], key: &[u8], value: &[u8]) -> Vec<u8> {
+    let mut out = dict_remove(dict, key);
+    out.truncate(out.len() - 2);
+    out.push(b'/');
+    out.extend(key);
+    out.push(b' ');
+    out.extend(value);
+    out.extend(b">>");
+    out
+}
+
+/// Returns a copy of a dictionary without an entry.
+pub(crate) fn dict_remove(dict: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut out = b"<<".to_vec();
+    for (k, v) in dict_entries(dict).unwrap_or_default() {
+        if k != key {
+            out.push(b'/');
+            out.extend(k);
+            out.push(b' ');
+            out.extend(v);
+            out.push(b'\n');
+        }
+    }
+    out.extend(b">>");
+    out
+}
+
+/// Returns the items of an array.
+pub(crate) fn parse_array(array: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut lexer = Lexer::new(array);
+    lexer.skip_ws();
+    if !lexer.eat(b"[") {
+        return None;
+    }
+
+    let mut items = vec![];
+    loop {
+        let start = lexer.skip_ws();
+        if lexer.eat(b"]") {
+            break;
+        }
+        lexer.skip_value()?;
+        items.push(&array[start..lexer.pos]);
+    }
+
+    Some(items)
+}
+
+/// Parses an integer.
+pub(crate) fn parse_int(value: &[u8]) -> Option<i64> {
+    std::str::from_utf8(value).ok()?.trim().parse().ok()
+}
+
+/// Parses an indirect reference into its object number.
+pub(crate) fn parse_ref(value: &[u8]) -> Option<i32> {
+    let mut lexer = Lexer::new(value);
+    lexer.skip_ws();
+    let id = parse_int(lexer.token()?)?;
+    lexer.skip_ws();
+    parse_int(lexer.token()?)?;
+    lexer.skip_ws();
+    (lexer.token()? == b"R").then_some(id as i32)
+}
+
+/// Formats an indirect reference.
+pub(crate) fn write_ref(id: i32) -> Vec<u8> {
+    format!("{id} 0 R").into_bytes()
+}
+
+/// Formats an array.
+pub(crate) fn write_array(items: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = b"[".to_vec();
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(b' ');
+        }
+        out.extend(item);
+    }
+    out.push(b']');
+    out
+}
+
+/// Formats a string as a PDF text string.
+pub(crate) fn write_text_str(text: &str) -> Vec<u8> {
+    if text.bytes().all(|b| (0x20..0x7f).contains(&b)) {
+        let mut out = b"(".to_vec();
+        for b in text.bytes() {
+            if matches!(b, b'(' | b')' | b'\\') {
+                out.push(b'\\');
+            }
+            out.push(b);
+        }
+        out.push(b')');
+        out
+    } else {
+        let mut out = b"<FEFF".to_vec();
+        for unit in text.encode_utf16() {
+            write!(out, "{unit:04X}").unwrap();
+        }
+        out.push(b'>');
+        out
+    }
+}
+
+/// Formats a string as a PDF name.
+pub(crate) fn write_name(name: &str) -> Vec<u8> {
+    let mut out = b"/".to_vec();
+    for b in name.bytes() {
+        if (0x21..0x7f).contains(&b) && !is_delimiter(b) && b != b'#' {
+            out.push(b);
+        } else {
+            write!(out, "#{b:02X}").unwrap();
+        }
+    }
+    out
+}
+
+/// Formats a stream object.
+pub(crate) fn write_stream(dict: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut out = dict_set(dict, b"Length", data.len().to_string().as_bytes());
+    out.extend(b"\nstream\n");
+    out.extend(data);
+    out.extend(b"\nendstream");
+    out
+}
+
+/// A minimal lexer for PDF syntax.
+pub(crate) struct Lexer<'a> {
+    data: &'a [u8],
+    pub(crate) pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub(crate) fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, s: &[u8]) -> bool {
+        let matches = self.data[self.pos..].starts_with(s);
+        if matches {
+            self.pos += s.len();
+        }
+        matches
+    }
+
+    /// Skips whitespace and comments and returns the new position.
+    pub(crate) fn skip_ws(&mut self) -> usize {
+        while let Some(b) = self.peek() {
+            if is_whitespace(b) {
+                self.pos += 1;
+            } else if b == b'%' {
+                while self.peek().is_some_and(|b| b != b'\n' && b != b'\r') {
+                    self.pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+        self.pos
+    }
+
+    fn skip_regular(&mut self) {
+        while self.peek().is_some_and(|b| !is_whitespace(b) && !is_delimiter(b)) {
+            self.pos += 1;
+        }
+    }
+
+    /// Reads a token consisting of regular characters.
+    pub(crate) fn token(&mut self) -> Option<&'a [u8]> {
+        let start = self.pos;
+        self.skip_regular();
+        (self.pos > start).then(|| &self.data[start..self.pos])
+    }
+
+    /// Skips over a full value, including indirect references.
+    pub(crate) fn skip_value(&mut self) -> Option<()> {
+        match self.peek()? {
+            b'<' if self.eat(b"<<") => loop {
+                self.skip_ws();
+                if self.eat(b">>") {
+                    break Some(());
+                }
+                self.skip_value()?;
+            },
+            b'<' => {
+                let end = self.data[self.pos..].iter().position(|&b| b == b'>')?;
+                self.pos += end + 1;
+                Some(())
+            }
+            b'[' => {
+                self.pos += 1;
+                loop {
+                    self.skip_ws();
+                    if self.eat(b"]") {
+                        break Some(());
+                    }
+                    self.skip_value()?;
+                }
+            }
+            b'(' => {
+                let mut depth = 0;
+                loop {
+                    match self.peek()? {
+                        b'\\' => self.pos += 1,
+                        b'(' => depth += 1,
+                        b')' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                self.pos += 1;
+                                break Some(());
+                            }
+                        }
+                        _ => {}
+                    }
+                    self.pos += 1;
+                }
+            }
+            b'/' => {
+                self.pos += 1;
+                self.skip_regular();
+                Some(())
+            }
+            _ => {
+                let token = self.token()?;
+                if parse_int(token).is_some() {
+                    // Check whether this is an indirect reference.
+                    let end = self.pos;
+                    self.skip_ws();
+                    let is_ref = self.token().is_some_and(|t| parse_int(t).is_some())
+                        && {
+                            self.skip_ws();
+                            self.token() == Some(b"R")
+                        };
+                    if !is_ref {
+                        self.pos = end;
+                    }
+                }
+                Some(())
+            }
+        }
+    }
+}
+
+pub(crate) fn is_whitespace(b: u8) -> bool {
+    matches!(b, b'\0' | b'\t' | b'\n' | b'\x0C' | b'\r' | b' ')
+}
+
+pub(crate) fn is_delimiter(b: u8) -> bool {
+    matches!(b, b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A minimal file with a catalog and a single page.
+    fn minimal_pdf() -> Vec<u8> {
+        let objects: [&[u8]; 3] = [
+            b"<</Type /Catalog /Pages 2 0 R>>",
+            b"<</Type /Pages /Kids [3 0 R] /Count 1>>",
+            b"<</Type /Page /Parent 2 0 R>>",
+        ];
+
+        let mut pdf = b"%PDF-1.7\n".to_vec();
+        let mut offsets = vec![];
+        for (i, body) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            writeln!(pdf, "{} 0 obj", i + 1).unwrap();
+            pdf.extend(*body);
+            pdf.extend(b"\nendobj\n");
+        }
+
+        let xref = pdf.len();
+        pdf.extend(b"xref\n0 4\n0000000000 65535 f\r\n");
+        for offset in offsets {
+            write!(pdf, "{offset:010} 00000 n\r\n").unwrap();
+        }
+        write!(pdf, "trailer\n<</Size 4 /Root 1 0 R>>\nstartxref\n{xref}\n%%EOF\n")
+            .unwrap();
+        pdf
+    }
+
+    #[test]
+    fn test_chained_updates() {
+        let mut update = Update::new(minimal_pdf()).unwrap();
+        let id = update.alloc();
+        update.write(id, b"<</Test true>>".to_vec());
+        let pdf = update.finish();
+
+        // The second update must still find the objects of the original file.
+        let update = Update::new(pdf).unwrap();
+        assert_eq!(update.pages().unwrap(), vec![3]);
+        assert_eq!(update.get(id), Some(b"<</Test true>>".as_slice()));
+    }
+
+    #[test]
+    fn test_written_stream() {
+        let pdf = minimal_pdf();
+        let mut update = Update::new(pdf.clone()).unwrap();
+        update.write(3, write_stream(b"<</Type /Page>>", b"data"));
+        let (dict, data) = update.get_stream(3).unwrap();
+        assert_eq!(dict, b"<</Type /Page\n/Length 4>>");
+        assert_eq!(data, b"data");
+
+        // An update without any objects leaves the file untouched.
+        assert_eq!(Update::new(pdf.clone()).unwrap().finish(), pdf);
+    }
+}
@@ -172,6 +172,10 @@ fn render_frame(canvas: &mut sk::Pixmap, state: State, frame: &Frame) {
 
 /// Render a group frame with optional transform and clipping into the canvas.
 fn render_group(canvas: &mut sk::Pixmap, state: State, pos: Point, group: &GroupItem) {
+    if group.in_hidden_layer() {
+        return;
+    }
+
     let sk_transform = to_sk_transform(&group.transform);
     let state = match group.frame.kind() {
         FrameKind::Soft => state.pre_translate(pos).pre_concat(sk_transform),
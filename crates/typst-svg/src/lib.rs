@@ -296,6 +296,10 @@ impl<'a> SVGRenderer<'a> {
     /// Render a group. If the group has `clips` set to true, a clip path will
     /// be created.
     fn render_group(&mut self, state: &State, group: &GroupItem) {
+        if group.in_hidden_layer() {
+            return;
+        }
+
         self.xml.start_element("g");
         self.xml.write_attribute("class", "typst-group");
 
@@ -354,6 +358,11 @@ impl<'a> SVGRenderer<'a> {
                 self.xml.write_attribute("href", url.as_str());
                 self.xml.write_attribute("xlink:href", url.as_str());
             }
+            Destination::File(link) => {
+                let href = link.href();
+                self.xml.write_attribute("href", href.as_str());
+                self.xml.write_attribute("xlink:href", href.as_str());
+            }
         }
 
         self.xml.start_element("rect");
@@ -501,6 +501,7 @@ impl ColorEncode for Color {
             c @ Color::Rgb(_)
             | c @ Color::Luma(_)
             | c @ Color::Cmyk(_)
+            | c @ Color::Spot(_)
             | c @ Color::Hsv(_) => c.to_hex(),
             Color::LinearRgb(rgb) => {
                 if rgb.alpha != 1.0 {
@@ -0,0 +1,6 @@
+--- html-page html ---
+// Without splitting, a page just shows its body.
+#html.page(path: "intro.html")[
+  = Introduction
+  Welcome.
+]
@@ -0,0 +1,14 @@
+--- html-stylesheet html ---
+#set html.document(stylesheet: true)
+#set text(size: 12pt)
+
+= Introduction
+Some #text(red)[very red] text.
+
+--- html-stylesheet-table html ---
+#set html.document(stylesheet: true)
+#table(
+  columns: 2,
+  fill: (x, _) => if x == 0 { luma(230) },
+  [A], [B],
+)
@@ -0,0 +1,42 @@
+--- mark-kinds ---
+// Shows the bodies of the first, the last, and the first level-2 heading.
+#set par(spacing: 0pt)
+#show heading: set block(spacing: 0pt)
+#let sq(fill) = box(width: 10pt, height: 10pt, fill: fill)
+
+#heading(sq(aqua))
+#heading(level: 2, sq(blue))
+#heading(level: 2, sq(red))
+
+#context {
+  test(mark(heading, kind: "top"), none)
+  test(mark(figure), none)
+  mark(heading).body
+  mark(heading, kind: "last").body
+  mark(heading.where(level: 2)).body
+}
+
+--- mark-previous-page ---
+// A page without a matching element shows the one still in effect from an
+// earlier page.
+#set par(spacing: 0pt)
+#show heading: set block(spacing: 0pt)
+#let sq(fill) = box(width: 10pt, height: 10pt, fill: fill)
+
+#heading(sq(aqua))
+#heading(sq(blue))
+
+#pagebreak()
+
+#context {
+  test(here().page(), 2)
+  mark(heading).body
+  mark(heading, kind: "last").body
+  mark(heading, kind: "top").body
+}
+
+--- mark-no-context ---
+// Error: 2-15 can only be used when context is known
+// Hint: 2-15 try wrapping this in a `context` expression
+// Hint: 2-15 the `context` expression should wrap everything that depends on this function
+#mark(heading)
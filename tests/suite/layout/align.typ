@@ -148,3 +148,6 @@ A
 #v(1fr)
 B
 #align(bottom + right)[C]
+
+--- align-html html ---
+#align(center)[Centered]
@@ -129,3 +129,65 @@ Hallo
 A
 #colbreak(weak: true)
 B
+
+--- columns-balance ---
+// Test that balanced columns end at the same height.
+#set block(spacing: 0pt, breakable: false)
+#let body = range(10).map(_ => block(width: 100%, height: 10pt)).join()
+#context {
+  let unbalanced = measure(block(width: 100pt, columns(2, body)))
+  let balanced = measure(block(width: 100pt, columns(2, balance: true, body)))
+  test(unbalanced.height, 100pt)
+  test(balanced.height, 50pt)
+}
+
+--- columns-balance-unbreakable ---
+// Test that balancing respects unbreakable blocks.
+#set block(spacing: 0pt, breakable: false)
+#let body = {
+  block(width: 100%, height: 30pt)
+  block(width: 100%, height: 10pt)
+  block(width: 100%, height: 10pt)
+}
+#context test(
+  measure(block(width: 100pt, columns(2, balance: true, body))).height,
+  30pt,
+)
+
+--- columns-balance-colbreak ---
+// Test that balancing keeps explicit column breaks and balances the columns
+// as a whole.
+#set block(spacing: 0pt, breakable: false)
+#let body = {
+  block(width: 100%, height: 10pt)
+  colbreak()
+  range(3).map(_ => block(width: 100%, height: 10pt)).join()
+}
+#context test(
+  measure(block(width: 100pt, columns(2, balance: true, body))).height,
+  30pt,
+)
+
+--- columns-block-span-all ---
+// Test that a spanning block interrupts the columns.
+#set block(spacing: 0pt, breakable: false)
+#let body = {
+  range(4).map(_ => block(width: 100%, height: 10pt)).join()
+  block(span: "all", width: 100%, height: 5pt)
+  range(2).map(_ => block(width: 100%, height: 10pt)).join()
+}
+#context {
+  test(measure(block(width: 100pt, columns(2, body))).height, 45pt)
+  test(measure(block(width: 100pt, columns(2, balance: true, body))).height, 35pt)
+}
+
+--- columns-block-span-invalid ---
+// Error: 14-20 expected "column" or "all"
+#block(span: "page")
+
+--- columns-html html ---
+#columns(2, gutter: 12pt)[
+  A
+  #colbreak()
+  B
+]
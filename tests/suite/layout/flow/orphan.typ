@@ -39,3 +39,70 @@ A
 // to another page.
 #set page(width: 16cm)
 #block(height: 30pt, fill: aqua, columns(2, lorem(19)))
+
+--- flow-par-orphans-count ---
+// Test that the configured number of orphan lines is kept together.
+#set par(leading: 0pt, spacing: 0pt)
+#set block(spacing: 0pt)
+#let ln = box(width: 100%, height: 10pt, fill: blue)
+#let body(marker) = block(width: 100pt, height: 40pt, columns(2, gutter: 0pt, {
+  block(width: 100%, height: 20pt, fill: aqua)
+  [#marker#ln#ln#ln#ln]
+}))
+#body[#metadata(none) <a>]
+#set par(orphans: 3)
+#body[#metadata(none) <b>]
+#context {
+  test(locate(<a>).position().x, 10pt)
+  test(locate(<b>).position().x, 60pt)
+}
+
+--- flow-par-widows-count ---
+// Test that the configured number of widow lines is kept together.
+#set par(leading: 0pt, spacing: 0pt)
+#set block(spacing: 0pt)
+#let ln = box(width: 100%, height: 10pt, fill: blue)
+#let body(marker) = block(width: 100pt, height: 40pt, columns(2, gutter: 0pt, {
+  [#ln#ln#ln#marker#ln#ln#ln]
+}))
+#body[#metadata(none) <a>]
+#set par(widows: 3)
+#body[#metadata(none) <b>]
+#context {
+  test(locate(<a>).position().x, 10pt)
+  test(locate(<b>).position().x, 60pt)
+}
+
+--- flow-par-widows-exceeding-lines ---
+// Test that a paragraph that cannot be split without leaving too few lines on
+// either side is kept together, even if orphans are allowed.
+#set par(leading: 0pt, spacing: 0pt, orphans: 1)
+#set block(spacing: 0pt)
+#let ln = box(width: 100%, height: 10pt, fill: blue)
+#let body(marker) = block(width: 100pt, height: 40pt, columns(2, gutter: 0pt, {
+  block(width: 100%, height: 20pt, fill: aqua)
+  [#marker#ln#ln#ln]
+}))
+#body[#metadata(none) <a>]
+#set par(widows: 3)
+#body[#metadata(none) <b>]
+#context {
+  test(locate(<a>).position().x, 10pt)
+  test(locate(<b>).position().x, 60pt)
+}
+
+--- flow-block-keep-with-next ---
+// Test that a block is kept with the given number of following blocks.
+#set block(width: 100%, spacing: 0pt, breakable: false)
+#let body(keep, marker) = block(width: 100pt, height: 40pt, columns(2, gutter: 0pt, {
+  block(height: 20pt, fill: aqua)
+  block(height: 10pt, fill: red, keep-with-next: keep, marker)
+  block(height: 10pt, fill: blue)
+  block(height: 10pt, fill: blue)
+}))
+#body(1)[#metadata(none) <a>]
+#body(2)[#metadata(none) <b>]
+#context {
+  test(locate(<a>).position().x, 10pt)
+  test(locate(<b>).position().x, 60pt)
+}
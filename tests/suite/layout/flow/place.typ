@@ -321,6 +321,67 @@ C
 // Error: 2-34 vertical floating placement must be `auto`, `top`, or `bottom`
 #place(right, float: true)[Hello]
 
+--- place-float-here ---
+// Test that floats with `here` placement stay in the flow.
+#set block(spacing: 0pt)
+#block(width: 100%, height: 10pt, fill: aqua)
+#place(float: true, placement: "here", clearance: 0pt)[
+  #metadata(none) <here>
+  #block(width: 100%, height: 20pt, fill: blue)
+]
+#metadata(none) <after>
+#block(width: 100%, height: 10pt, fill: aqua)
+#context {
+  test(locate(<here>).position().y, 20pt)
+  test(locate(<after>).position().y, 40pt)
+}
+
+--- place-float-here-parent ---
+// Error: 2-63 parent-scoped placement is not available for `here` placement
+// Hint: 2-63 try using `placement: "edge"` or `placement: "page"` instead
+#place(float: true, placement: "here", scope: "parent")[Hello]
+
+--- place-float-limit ---
+// Test that the float limit defers additional floats to the next column.
+#set place(float-limit: 1)
+#set block(spacing: 0pt)
+#let floater(fill, body) = place(top, float: true, clearance: 0pt, {
+  body
+  block(width: 100%, height: 10pt, fill: fill)
+})
+#block(width: 100pt, height: 50pt, columns(2, gutter: 0pt, {
+  floater(aqua)[#metadata(none) <a>]
+  floater(blue)[#metadata(none) <b>]
+  block(width: 100%, height: 10pt, fill: red)
+}))
+#context {
+  test(locate(<a>).position().x, 10pt)
+  test(locate(<b>).position().x, 60pt)
+}
+
+--- place-float-reorder ---
+// Test that later floats may overtake a deferred one when reordering is
+// enabled.
+#set block(spacing: 0pt)
+#let floater(height, fill, body) = place(bottom, float: true, clearance: 0pt, {
+  body
+  block(width: 100%, height: height, fill: fill)
+})
+#let floats(a, b) = block(width: 100pt, height: 50pt, columns(2, gutter: 0pt, {
+  block(width: 100%, height: 20pt, fill: aqua)
+  floater(40pt, blue, a)
+  floater(10pt, red, b)
+}))
+#floats[#metadata(none) <a>][#metadata(none) <b>]
+#set place(reorder: true)
+#floats[#metadata(none) <c>][#metadata(none) <d>]
+#context {
+  test(locate(<a>).position().x, 60pt)
+  test(locate(<b>).position().x, 60pt)
+  test(locate(<c>).position().x, 60pt)
+  test(locate(<d>).position().x, 10pt)
+}
+
 --- place-flush ---
 #set page(height: 120pt)
 #let floater(align, height) = place(
@@ -387,3 +448,6 @@ Paragraph after place.
 #place(auto, float: true, block(height: 100%, width: 100%, fill: aqua))
 #place(auto, float: true, block(height: 100%, width: 100%, fill: red))
 #lines(7)
+
+--- place-html html ---
+#place(right, dx: -5pt)[Placed]
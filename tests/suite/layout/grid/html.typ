@@ -132,3 +132,11 @@
     [Ending], [Table],
   ),
 )
+
+--- grid-html html ---
+#grid(
+  columns: (1fr, 2fr),
+  column-gutter: 8pt,
+  grid.cell(colspan: 2)[Title],
+  [Left], [Right],
+)
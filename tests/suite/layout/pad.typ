@@ -36,3 +36,6 @@ Hi #box(pad(left: 10pt)[A]) there
 --- issue-5160-unbreakable-pad ---
 #set block(breakable: false)
 #block(width: 100%, pad(x: 20pt, align(right)[A]))
+
+--- pad-html html ---
+#pad(x: 10pt)[Padded]
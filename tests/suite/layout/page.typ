@@ -348,6 +348,12 @@ A
   A
 ]
 
+--- page-bleed-frame ---
+// The page's frame includes the bleed, so positions are shifted by it.
+#set page(width: 110pt, height: 10pt, margin: 0pt, bleed: 5pt)
+#context test(page.bleed, 5pt)
+#context test(here().position(), (page: 1, x: 5pt, y: 5pt))
+
 --- issue-2631-page-header-ordering ---
 #set text(6pt)
 #show heading: set text(6pt, weight: "regular")
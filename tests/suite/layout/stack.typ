@@ -89,3 +89,6 @@ World! 🌍
   // Error: 3-40 stack spacing is infinite
   stack(spacing: infinite-length)[A][B]
 })
+
+--- stack-html html ---
+#stack(dir: ltr, spacing: 4pt, [A], [B])
@@ -304,3 +304,40 @@ Looks at the @quadratic formula.
 #set text(stroke: green + 0.5pt)
 
 A $B^2$ $ grave(C)' $
+
+--- math-equation-html-inline html ---
+Let $x^2 + 1/n$ be given.
+
+--- math-equation-html-block html ---
+#set math.equation(numbering: "(1)")
+$ sum_(i=1)^n i $
+
+--- math-equation-html-mat html ---
+$mat(1, 2; 3, 4) sqrt(x)$
+
+--- math-equation-html-accent html ---
+$hat(x) arrow(v)$
+
+--- math-equation-html-lr html ---
+$(a + b)$
+
+--- math-equation-html-root html ---
+$root(3, x)$
+
+--- math-equation-html-underover html ---
+$underline(x) overline(y) underbrace(a, 1) overbracket(b)$
+
+--- math-equation-html-cases html ---
+$cases(a, b) cases(reverse: #true, c, d)$
+
+--- math-equation-html-spacing html ---
+$a #h(10pt) b #h(50%) c$
+
+--- math-equation-html-spacing-fr html ---
+// Warning: 4-10 fractional spacing was ignored during MathML export
+// Hint: 4-10 use absolute or relative spacing instead
+$a #h(1fr) b$
+
+--- math-equation-html-frame html ---
+// Content that MathML can't express is laid out into an SVG.
+$#rect(width: 11pt, height: 11pt, fill: red)$
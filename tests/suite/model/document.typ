@@ -16,6 +16,26 @@ What's up?
 #set document(author: (123,))
 What's up?
 
+--- document-viewer-preferences ---
+#set document(
+  page-layout: "two-page-right",
+  page-mode: "outline",
+  initial-page: 2,
+  initial-zoom: "fit",
+)
+#set page(transition: "dissolve", duration: duration(seconds: 3))
+#context {
+  test(document.page-layout, "two-page-right")
+  test(document.initial-page, 2)
+  test(document.initial-zoom, "fit")
+  test(document.display-title, auto)
+  test(page.transition, "dissolve")
+}
+
+--- document-initial-zoom-invalid ---
+// Error: 29-36 expected ratio, "fit", "fit-width", or auto
+#set document(initial-zoom: "width")
+
 --- document-set-after-content ---
 // Document set rules can appear anywhere in top-level realization, also after
 // content.
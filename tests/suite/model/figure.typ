@@ -77,6 +77,36 @@ We can clearly see that @fig-cylinder and
 
 #lines(15)
 
+--- figure-placement-values ---
+// Test that the placements that predate `here` and `page` still parse and
+// place figures like before.
+#test(figure[].placement, none)
+#test(figure(placement: none)[].placement, none)
+#test(figure(placement: auto)[].placement, auto)
+#test(figure(placement: top)[].placement, top)
+#test(figure(placement: bottom)[].placement, bottom)
+
+#set block(spacing: 0pt)
+#let fig(placement, fill, marker) = figure(
+  placement: placement,
+  clearance: 0pt,
+  block(width: 100%, height: 10pt, fill: fill, marker),
+)
+#block(height: 80pt, {
+  // At the start of the flow, the top is closer than the bottom.
+  fig(auto, aqua)[#metadata(none) <auto>]
+  fig(top, blue)[#metadata(none) <top>]
+  block(width: 100%, height: 10pt, fill: red)
+  fig(none, green)[#metadata(none) <none>]
+  fig(bottom, olive)[#metadata(none) <bottom>]
+})
+#context {
+  test(locate(<auto>).position().y, 10pt)
+  test(locate(<top>).position().y, 20pt)
+  test(locate(<none>).position().y, 40pt)
+  test(locate(<bottom>).position().y, 80pt)
+}
+
 --- figure-scope-without-placement ---
 // Error: 2-27 parent-scoped placement is only available for floating figures
 // Hint: 2-27 you can enable floating placement with `figure(placement: auto, ..)`
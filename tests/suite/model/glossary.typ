@@ -0,0 +1,30 @@
+--- glossary-first-use ---
+#set text(2pt)
+#place(hide[@pdf#metadata(none)<a> @pdf#metadata(none)<b>])
+
+#context {
+  let a = locate(<a>).position().x
+  let b = locate(<b>).position().x
+  test(a - 10pt, measure[Portable Document Format (PDF)].width)
+  test(b - a, measure[x PDF].width - measure[x].width)
+}
+
+#place(hide(glossary(title: none, (
+  (key: "pdf", short: [PDF], long: [Portable Document Format]),
+  (key: "svg", short: [SVG], description: [A vector format.]),
+))))
+
+--- glossary-entries-field ---
+#let g = glossary(((key: "pdf", short: [PDF]),))
+#test(g.entries, ((key: "pdf", short: [PDF]),))
+
+--- glossary-label-ambiguous ---
+// Error: 1-5 label `<pdf>` occurs both in the document and its glossary
+// Hint: 1-5 change either the metadata's label or the glossary key to resolve the ambiguity
+@pdf
+#metadata(none) <pdf>
+#glossary(((key: "pdf", short: [PDF]),))
+
+--- glossary-empty-key ---
+// Error: 11-35 glossary key must not be empty
+#glossary(((key: "", short: [X]),))
@@ -0,0 +1,32 @@
+--- index-marker-fields ---
+#index("Typst", key: "typst")
+#index("Markup", sub: "Lists", see-also: ("Lists",))
+
+#context {
+  let markers = query(index)
+  test(markers.len(), 2)
+  test(markers.first().term, "Typst")
+  test(markers.first().key, "typst")
+  test(markers.last().sub, "Lists")
+  test(markers.last().see-also, ("Lists",))
+}
+
+--- index-marker-invisible ---
+#let body = [#index("Typst")#index("Markup", sub: "Lists")]
+#context test(measure(body).width, 0pt)
+
+--- make-index ---
+Zebras#index("Zebra") and apples#index("apple").
+
+#pagebreak()
+Red apples#index("apple", sub: "Red")#index("Äpfel", see-also: ("Zebra",)).
+
+#pagebreak()
+More apples#index("apple")#index("apple", sub: "Red").
+
+#make-index()
+
+--- make-index-html html ---
+Zebras#index("Zebra") and apples#index("Äpfel", see-also: ("Zebra",))#index("apple", sub: "Red") are fine.
+
+#make-index(title: none)
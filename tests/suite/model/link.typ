@@ -193,6 +193,21 @@ Text <hey>
 // Error: 7-9 URL must not be empty
 #link("")[Empty]
 
+--- link-file ---
+#test(
+  link(file: "volume-2.pdf", dest: <setup>)[Setup].dest,
+  (file: "volume-2.pdf", dest: "setup"),
+)
+#test(link(file: "volume-2.pdf", 3)[Page 3].dest, (file: "volume-2.pdf", dest: 3))
+#test(link(file: "volume-2.pdf")[Volume 2].dest, (file: "volume-2.pdf"))
+
+--- link-file-empty ---
+// Error: 13-15 file path must not be empty
+#link(file: "")[Empty]
+
+--- link-file-html html ---
+#link(file: "volume-2.pdf", dest: <setup>)[Setup]
+
 --- link-empty-block ---
 #link("https://example.com", block(height: 10pt, width: 100%))
 
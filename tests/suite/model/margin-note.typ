@@ -0,0 +1,24 @@
+// Test margin notes.
+
+--- margin-note-outer-right ---
+#set page(margin: (left: 10pt, right: 50pt))
+#set margin-note(gap: 5pt)
+#margin-note[#metadata(none) <note>]
+#context test(locate(<note>).position().x, 75pt)
+
+--- margin-note-binding-right ---
+#set page(margin: (left: 50pt, right: 10pt), binding: right)
+#set margin-note(gap: 5pt)
+#margin-note[#metadata(none) <note>]
+#context test(locate(<note>).position().x, 5pt)
+
+--- margin-note-stacked ---
+// Notes at the same position must not overlap.
+#set page(margin: (right: 50pt))
+#set margin-note(gap: 0pt, spacing: 5pt)
+#margin-note[#metadata(none) <a> #block(height: 10pt)]
+#margin-note[#metadata(none) <b> #block(height: 10pt)]
+#context test(locate(<b>).position().y - locate(<a>).position().y, 15pt)
+
+--- margin-note-html html ---
+Typst is a typesetting system.#margin-note[Like LaTeX.]
@@ -182,6 +182,18 @@
 = A
 = B
 
+--- outline-entry-short ---
+// Test that outline entries use short titles and captions.
+#place(hide[
+  #heading(short: [Short])[A much longer title]
+  #figure(rect(), caption: figure.caption(short: [Brief])[A longer caption])
+])
+#context {
+  let body(elem) = outline.entry(1, elem).body()
+  test(body(query(heading).first()).text, "Short")
+  test(body(query(figure).first()).text, "Brief")
+}
+
 --- outline-heading-start-of-page ---
 #set page(width: 140pt, height: 200pt, margin: (bottom: 20pt), numbering: "1")
 #set heading(numbering: "(1/a)")
@@ -0,0 +1,28 @@
+// Test PDF annotations. They only exist in the PDF, so we can only test that
+// they don't affect the layout and can be queried.
+
+--- pdf-annotation-size ---
+#context {
+  test(measure(pdf.annotation("Note")).width, 0pt)
+  test(
+    measure(pdf.annotation("Typo", kind: "highlight")[Helo]),
+    measure[Helo],
+  )
+}
+
+--- pdf-annotation-query ---
+#place(hide[
+  #pdf.annotation("Check this", author: "Reviewer")
+  #pdf.annotation("Nice", kind: "highlight", date: datetime(year: 2025, month: 1, day: 2))[Text]
+])
+#context {
+  let annotations = query(pdf.annotation)
+  test(annotations.len(), 2)
+  test(annotations.map(a => a.contents), ("Check this", "Nice"))
+  test(annotations.first().author, "Reviewer")
+  test(annotations.last().kind, "highlight")
+}
+
+--- pdf-annotation-invalid-kind ---
+// Error: 28-35 expected "note", "highlight", or "free-text"
+#pdf.annotation("a", kind: "stamp")
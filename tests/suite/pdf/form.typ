@@ -0,0 +1,28 @@
+// Test interactive form fields. The widgets themselves only exist in the PDF,
+// so we can only test the boxes here.
+
+--- pdf-form-field-default-size ---
+#set text(size: 10pt)
+#context {
+  test(measure(pdf.form-field("a")).width, 80pt)
+  test(measure(pdf.form-field("a", multiline: true)).height, 30pt)
+  test(measure(pdf.form-field("b", kind: "checkbox")).width, 10pt)
+  test(measure(pdf.form-field("c", kind: "signature")).height, 30pt)
+  test(measure(pdf.form-field("d", width: 20pt, height: 5pt)).width, 20pt)
+}
+
+--- pdf-form-field-query ---
+#place(hide[
+  #pdf.form-field("plan", kind: "radio", value: "basic", checked: true)
+  #pdf.form-field("plan", kind: "radio", value: "pro", tab-index: 1)
+])
+#context {
+  let fields = query(pdf.form-field.where(name: "plan"))
+  test(fields.len(), 2)
+  test(fields.map(f => f.value), ("basic", "pro"))
+  test(fields.last().tab-index, 1)
+}
+
+--- pdf-form-field-invalid-kind ---
+// Error: 28-34 expected "text", "checkbox", "radio", "choice", or "signature"
+#pdf.form-field("a", kind: "date")
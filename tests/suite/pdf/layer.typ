@@ -0,0 +1,23 @@
+// Test PDF layers. Their visibility can only be toggled in the PDF, so we can
+// only test the default visibility here.
+
+--- pdf-layer-size ---
+// The content of a hidden layer still takes up space.
+#context test(
+  measure(pdf.layer("Solutions", visible: false)[Paris]),
+  measure[Paris],
+)
+
+--- pdf-layer-query ---
+#hide[
+  #pdf.layer("Solutions", visible: false)[A]
+  #pdf.layer("Solutions", visible: false, print: true)[B]
+]
+#context {
+  let layers = query(pdf.layer.where(name: "Solutions"))
+  test(layers.len(), 2)
+  test(layers.map(l => l.print), (auto, true))
+}
+
+--- pdf-layer-html html ---
+Answer: #pdf.layer("Solutions")[Paris]#pdf.layer("Notes", visible: false)[Lyon]
@@ -249,6 +249,35 @@
   box(square(size: 9pt, fill: c.darken(x * 10%)))
 }
 
+--- color-spot ---
+// Test spot colors.
+#let c = color.spot("Brand", cmyk(100%, 50%, 0%, 0%))
+#test(c.space(), cmyk)
+#test(c.components(), (100%, 50%, 0%, 0%))
+#test(c.lighten(50%).components(alpha: false), (50%, 25%, 0%, 0%))
+#test(c, color.spot("Brand", cmyk(100%, 50%, 0%, 0%)))
+#test(c != color.spot("Other", cmyk(100%, 50%, 0%, 0%)), true)
+#test(c != cmyk(100%, 50%, 0%, 0%), true)
+#test-repr(c.lighten(50%), color.spot("Brand", cmyk(100%, 50%, 0%, 0%), tint: 50%))
+#test-repr(c.lighten(50%).darken(100%), c)
+#test(cmyk(c.lighten(50%)), cmyk(50%, 25%, 0%, 0%))
+
+--- color-spot-mix ---
+// Mixing spot colors of the same ink keeps the ink.
+#let c = color.spot("Brand", cmyk(100%, 50%, 0%, 0%))
+#test-repr(color.mix(c, c.lighten(100%)), c.lighten(50%))
+#test-repr(color.mix(c, c.lighten(100%), space: color.hsl), c.lighten(50%))
+#test-repr(gradient.linear(c, c.lighten(100%)).sample(25%), c.lighten(25%))
+#test(color.mix(c, color.spot("Other", cyan)).space(), oklab)
+
+--- color-spot-empty-name ---
+// Error: 2-40 spot color name must not be empty
+#color.spot("", cmyk(0%, 0%, 0%, 100%))
+
+--- color-spot-transparentize ---
+// Error: 2-48 spot colors do not have an alpha component
+#color.spot("Brand", black).transparentize(50%)
+
 --- color-outside-srgb-gamut ---
 // Colors outside the sRGB gamut.
 #box(square(size: 9pt, fill: oklab(90%, -0.2, -0.1)))
@@ -20,6 +20,11 @@
 #image("/assets/images/f2t.jpg", height: 80pt, alt: "height: 80pt")  
 #image("/assets/images/f2t.jpg", height: 20% + 40pt, alt: "height: calc(20% + 40pt)")
 
+--- image-html-assets html ---
+// The image is smaller than its displayed size, so there is no `srcset`.
+#set html.document(assets: true)
+#image("/assets/images/f2t.jpg", width: 48pt, alt: "The letter F")
+
 --- image-sizing ---
 // Test configuring the size and fitting behaviour of images.
 
@@ -248,19 +253,11 @@ A #box(image("/assets/images/tiger.jpg", height: 1cm, width: 80%)) B
   img("pixelated"),
 )
 
-#context if target() == "html" {
-  // TODO: Remove this once `stack` is supported in HTML export.
-  html.div(
-    style: "display: flex; flex-direction: row; gap: 4pt",
-    images.join(),
-  )
-} else {
-  stack(
-    dir: ltr,
-    spacing: 4pt,
-    ..images,
-  )
-}
+#stack(
+  dir: ltr,
+  spacing: 4pt,
+  ..images,
+)
 
 --- image-natural-dpi-sizing ---
 // Test that images aren't upscaled.